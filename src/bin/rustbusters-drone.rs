@@ -0,0 +1,276 @@
+//! Runs a single `RustBustersDrone` as its own OS process, connected to its
+//! neighbors and to the controller through Unix datagram sockets.
+//!
+//! ```text
+//! rustbusters-drone --config drone-10.toml
+//! rustbusters-drone --id 10 --pdr 0.1 --socket /tmp/rb/10.sock \
+//!     --neighbor 11=/tmp/rb/11.sock --control-socket /tmp/rb/10.ctl.sock
+//! ```
+use clap::Parser;
+use crossbeam_channel::{unbounded, Sender};
+use log::{error, info, warn};
+use rustbusters_drone::firewall::FirewallConfig;
+use rustbusters_drone::transport::unix_socket::{
+    RemoteCommand, RemoteEvent, UnixLink, UnixPacketLink, UnixPacketSource, UnixSource,
+};
+use rustbusters_drone::transport::PacketLink;
+use rustbusters_drone::RustBustersDrone;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+use wg_2024::controller::{DroneCommand, DroneEvent};
+use wg_2024::drone::Drone;
+use wg_2024::network::NodeId;
+use wg_2024::packet::Packet;
+
+/// Command line flags, overriding the values of the configuration file
+#[derive(Debug, Parser)]
+#[command(
+    name = "rustbusters-drone",
+    version,
+    about = "Runs a single RustBustersDrone over Unix sockets"
+)]
+struct Args {
+    /// TOML configuration file
+    #[arg(short, long)]
+    config: Option<PathBuf>,
+    /// ID of the drone
+    #[arg(long)]
+    id: Option<NodeId>,
+    /// Packet Drop Rate (0.0-1.0)
+    #[arg(long)]
+    pdr: Option<f32>,
+    /// Socket the drone receives packets on
+    #[arg(long)]
+    socket: Option<PathBuf>,
+    /// Neighbor of the drone, as `<id>=<socket path>` (repeatable)
+    #[arg(long = "neighbor", value_parser = parse_neighbor)]
+    neighbors: Vec<NeighborConfig>,
+    /// Socket the drone receives controller commands on
+    #[arg(long)]
+    control_socket: Option<PathBuf>,
+    /// Socket of the controller the drone events are sent to
+    #[arg(long)]
+    event_socket: Option<PathBuf>,
+    /// Enables optimized routing
+    #[arg(long)]
+    optimized_routing: bool,
+    /// Enables hunt mode
+    #[arg(long)]
+    hunt_mode: bool,
+    /// Seconds to wait for the sockets of neighbors and controller to appear
+    #[arg(long)]
+    connect_timeout: Option<u64>,
+}
+
+/// Content of the TOML configuration file
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct DroneConfig {
+    id: Option<NodeId>,
+    pdr: f32,
+    socket: Option<PathBuf>,
+    #[serde(rename = "neighbor")]
+    neighbors: Vec<NeighborConfig>,
+    control_socket: Option<PathBuf>,
+    event_socket: Option<PathBuf>,
+    optimized_routing: bool,
+    hunt_mode: bool,
+    connect_timeout: Option<u64>,
+    firewall: FirewallConfig,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct NeighborConfig {
+    id: NodeId,
+    socket: PathBuf,
+}
+
+const DEFAULT_CONNECT_TIMEOUT: u64 = 5;
+
+fn parse_neighbor(value: &str) -> Result<NeighborConfig, String> {
+    let (id, socket) = value
+        .split_once('=')
+        .ok_or_else(|| format!("expected <id>=<socket path>, got '{value}'"))?;
+    let id = id
+        .trim()
+        .parse::<NodeId>()
+        .map_err(|e| format!("invalid neighbor id '{id}': {e}"))?;
+    Ok(NeighborConfig {
+        id,
+        socket: PathBuf::from(socket.trim()),
+    })
+}
+
+impl DroneConfig {
+    /// Loads the configuration file, if any, and applies the command line flags on top of it
+    fn load(args: Args) -> Result<Self, String> {
+        let mut config = match &args.config {
+            Some(path) => {
+                let content = std::fs::read_to_string(path)
+                    .map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+                toml::from_str::<DroneConfig>(&content)
+                    .map_err(|e| format!("cannot parse {}: {}", path.display(), e))?
+            }
+            None => DroneConfig::default(),
+        };
+
+        config.id = args.id.or(config.id);
+        config.pdr = args.pdr.unwrap_or(config.pdr);
+        config.socket = args.socket.or(config.socket);
+        config.control_socket = args.control_socket.or(config.control_socket);
+        config.event_socket = args.event_socket.or(config.event_socket);
+        config.optimized_routing |= args.optimized_routing;
+        config.hunt_mode |= args.hunt_mode;
+        config.connect_timeout = args.connect_timeout.or(config.connect_timeout);
+        for neighbor in args.neighbors {
+            config.neighbors.retain(|n| n.id != neighbor.id);
+            config.neighbors.push(neighbor);
+        }
+
+        if !(0.0..=1.0).contains(&config.pdr) {
+            return Err(format!("pdr must be between 0 and 1, got {}", config.pdr));
+        }
+        Ok(config)
+    }
+}
+
+/// Connects to a peer socket, retrying until the peer is listening or the timeout expires
+fn connect_with_retry<T: serde::Serialize>(
+    path: &Path,
+    timeout: Duration,
+) -> Result<UnixLink<T>, String> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        match UnixLink::connect(path) {
+            Ok(link) => return Ok(link),
+            Err(e) if Instant::now() >= deadline => {
+                return Err(format!("cannot connect to {}: {}", path.display(), e));
+            }
+            Err(_) => sleep(Duration::from_millis(100)),
+        }
+    }
+}
+
+/// Bridges a socket link into a channel, as `DroneCommand::AddSender` only accepts a `Sender<Packet>`
+///
+/// > Note: the bridge stops on the first failed send, closing the channel so the drone
+/// > removes the neighbor on its next send
+fn bridge_link(node_id: NodeId, link: UnixPacketLink) -> Sender<Packet> {
+    let (packet_send, packet_recv) = unbounded::<Packet>();
+    std::thread::spawn(move || {
+        for packet in packet_recv {
+            if let Err(e) = link.send_packet(packet) {
+                warn!("Bridge {} - Closing link: {}", node_id, e);
+                break;
+            }
+        }
+    });
+    packet_send
+}
+
+/// Forwards the commands received on the control socket to the drone
+fn spawn_control(
+    path: &Path,
+    command_send: Sender<DroneCommand>,
+    timeout: Duration,
+) -> Result<(), String> {
+    let commands = UnixSource::<RemoteCommand>::bind(path)
+        .map_err(|e| format!("cannot bind {}: {}", path.display(), e))?
+        .into_channel();
+    std::thread::spawn(move || {
+        for command in commands {
+            let command = match command {
+                RemoteCommand::Crash => DroneCommand::Crash,
+                RemoteCommand::SetPacketDropRate(pdr) => DroneCommand::SetPacketDropRate(pdr),
+                RemoteCommand::RemoveSender(node_id) => DroneCommand::RemoveSender(node_id),
+                RemoteCommand::AddSender(node_id, socket) => {
+                    match connect_with_retry(&socket, timeout) {
+                        Ok(link) => DroneCommand::AddSender(node_id, bridge_link(node_id, link)),
+                        Err(e) => {
+                            error!("Control - Cannot add sender {}: {}", node_id, e);
+                            continue;
+                        }
+                    }
+                }
+            };
+            if command_send.send(command).is_err() {
+                break;
+            }
+        }
+    });
+    Ok(())
+}
+
+fn run() -> Result<(), String> {
+    let config = DroneConfig::load(Args::parse())?;
+    let id = config.id.ok_or("missing drone id")?;
+    let socket = config.socket.clone().ok_or("missing drone socket")?;
+    let timeout = Duration::from_secs(config.connect_timeout.unwrap_or(DEFAULT_CONNECT_TIMEOUT));
+
+    let packet_source = UnixPacketSource::bind(&socket)
+        .map_err(|e| format!("cannot bind {}: {}", socket.display(), e))?;
+
+    let mut packet_send: HashMap<NodeId, Box<dyn PacketLink>> = HashMap::new();
+    for neighbor in &config.neighbors {
+        let link: UnixPacketLink = connect_with_retry(&neighbor.socket, timeout)?;
+        packet_send.insert(neighbor.id, Box::new(link));
+    }
+
+    let (event_send, event_recv) = unbounded::<DroneEvent>();
+    let (command_send, command_recv) = unbounded::<DroneCommand>();
+
+    // `command_send` is kept alive until the drone stops, even without a control socket:
+    // the run loop would otherwise spin on the disconnected receiver
+    if let Some(path) = &config.control_socket {
+        spawn_control(path, command_send.clone(), timeout)?;
+    }
+
+    let event_link = match &config.event_socket {
+        Some(path) => Some(connect_with_retry::<RemoteEvent>(path, timeout)?),
+        None => None,
+    };
+    std::thread::spawn(move || {
+        for event in event_recv {
+            match &event_link {
+                Some(link) => {
+                    if let Err(e) = link.send(&RemoteEvent::from(event)) {
+                        warn!("Events - Cannot reach controller: {}", e);
+                    }
+                }
+                None => info!("Events - {:?}", event),
+            }
+        }
+    });
+
+    let mut drone = RustBustersDrone::with_transport(
+        id,
+        event_send,
+        command_recv,
+        packet_source,
+        packet_send,
+        config.pdr,
+    );
+    drone.set_optimized_routing(config.optimized_routing);
+    drone.set_hunt_mode(config.hunt_mode);
+    drone.set_firewall(config.firewall);
+
+    drone.run();
+    drop(command_send);
+
+    let _ = std::fs::remove_file(&socket);
+    if let Some(path) = &config.control_socket {
+        let _ = std::fs::remove_file(path);
+    }
+    Ok(())
+}
+
+fn main() {
+    env_logger::init();
+    if let Err(e) = run() {
+        eprintln!("rustbusters-drone: {e}");
+        std::process::exit(1);
+    }
+}
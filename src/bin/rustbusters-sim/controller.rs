@@ -0,0 +1,193 @@
+use crate::network::Topology;
+use crossbeam_channel::{Receiver, Select, Sender};
+use log::{info, warn};
+use rustbusters_drone::hunt::PACKET_CONST;
+use rustbusters_drone::report::Report;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::Duration;
+use wg_2024::controller::{DroneCommand, DroneEvent};
+use wg_2024::network::NodeId;
+use wg_2024::packet::{NodeType, Packet, PacketType};
+
+/// Counters collected from the events of a drone
+#[derive(Debug, Clone, Default)]
+pub struct DroneStats {
+    pub fragments_forwarded: u64,
+    pub other_sent: u64,
+    pub dropped: u64,
+    pub shortcuts: u64,
+    pub hunts: u64,
+    pub reports: u64,
+    pub crashed: bool,
+}
+
+impl DroneStats {
+    /// Share of the fragments handled by the drone that were forwarded instead of dropped
+    pub fn delivery_ratio(&self) -> f64 {
+        let handled = self.fragments_forwarded + self.dropped;
+        if handled == 0 {
+            return 1.0;
+        }
+        self.fragments_forwarded as f64 / handled as f64
+    }
+}
+
+/// Minimal simulation controller: routes shortcuts, counts events and handles hunts
+pub struct SimController {
+    drone_ids: Vec<NodeId>,
+    event_recv: Vec<Receiver<DroneEvent>>,
+    command_send: HashMap<NodeId, Sender<DroneCommand>>,
+    packet_send: HashMap<NodeId, Sender<Packet>>,
+    topology: Topology,
+    ghosts: HashSet<NodeId>,
+    stats: BTreeMap<NodeId, DroneStats>,
+}
+
+impl SimController {
+    pub fn new(
+        drones: Vec<(NodeId, Receiver<DroneEvent>, Sender<DroneCommand>)>,
+        packet_send: HashMap<NodeId, Sender<Packet>>,
+        topology: Topology,
+        ghosts: HashSet<NodeId>,
+    ) -> Self {
+        let mut controller = Self {
+            drone_ids: Vec::new(),
+            event_recv: Vec::new(),
+            command_send: HashMap::new(),
+            packet_send,
+            topology,
+            ghosts,
+            stats: BTreeMap::new(),
+        };
+        for (id, event_recv, command_send) in drones {
+            controller.drone_ids.push(id);
+            controller.event_recv.push(event_recv);
+            controller.command_send.insert(id, command_send);
+            controller.stats.insert(id, DroneStats::default());
+        }
+        controller
+    }
+
+    /// Handles the drone events received within the timeout
+    ///
+    /// #### Returns
+    /// Whether an event was handled
+    pub fn poll(&mut self, timeout: Duration) -> bool {
+        let index = {
+            let mut select = Select::new();
+            for receiver in &self.event_recv {
+                select.recv(receiver);
+            }
+            match select.select_timeout(timeout) {
+                Ok(operation) => {
+                    let index = operation.index();
+                    match operation.recv(&self.event_recv[index]) {
+                        Ok(event) => Some((index, event)),
+                        Err(_) => None,
+                    }
+                }
+                Err(_) => None,
+            }
+        };
+        match index {
+            Some((index, event)) => {
+                self.handle_event(self.drone_ids[index], event);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Crashes every drone that is still running
+    pub fn crash_all(&mut self) {
+        for (id, stats) in &mut self.stats {
+            if !stats.crashed {
+                if let Some(sender) = self.command_send.get(id) {
+                    let _ = sender.send(DroneCommand::Crash);
+                }
+                stats.crashed = true;
+            }
+        }
+    }
+
+    pub fn stats(&self) -> &BTreeMap<NodeId, DroneStats> {
+        &self.stats
+    }
+
+    fn handle_event(&mut self, drone_id: NodeId, event: DroneEvent) {
+        let stats = self.stats.entry(drone_id).or_default();
+        match event {
+            DroneEvent::PacketSent(packet) => match &packet.pack_type {
+                PacketType::MsgFragment(fragment)
+                    if fragment.fragment_index == 0
+                        && fragment.total_n_fragments == 0
+                        && fragment.length == PACKET_CONST =>
+                {
+                    stats.hunts += 1;
+                    self.handle_hunt(drone_id, fragment.data[1]);
+                }
+                PacketType::MsgFragment(_) if Report::parse(&packet).is_some() => {
+                    stats.reports += 1;
+                }
+                PacketType::MsgFragment(_) => stats.fragments_forwarded += 1,
+                _ => stats.other_sent += 1,
+            },
+            DroneEvent::PacketDropped(_) => stats.dropped += 1,
+            DroneEvent::ControllerShortcut(mut packet) => {
+                stats.shortcuts += 1;
+                let Some(&destination) = packet.routing_header.hops.last() else {
+                    return;
+                };
+                packet.routing_header.hop_index = packet.routing_header.hops.len() - 1;
+                match self.packet_send.get(&destination) {
+                    Some(sender) => {
+                        let _ = sender.send(packet);
+                    }
+                    None => warn!(
+                        "Controller - Cannot shortcut to unknown node {}",
+                        destination
+                    ),
+                }
+            }
+        }
+    }
+
+    /// Reference hunt handling: the target is crashed only if it is a ghost
+    /// (i.e. not a RustBusters drone) and the network stays connected without it
+    fn handle_hunt(&mut self, hunter: NodeId, target: NodeId) {
+        if self.topology.node_type(target) != Some(NodeType::Drone) {
+            warn!(
+                "Controller - Hunt from {} on non-drone {} aborted",
+                hunter, target
+            );
+            return;
+        }
+        if !self.ghosts.contains(&target) {
+            info!(
+                "Controller - Hunt from {} on RustBusters drone {} aborted",
+                hunter, target
+            );
+            return;
+        }
+        if !self.topology.stays_connected_without(target) {
+            info!(
+                "Controller - Hunt from {} on {} aborted: network would be partitioned",
+                hunter, target
+            );
+            return;
+        }
+
+        info!("Controller - Hunt from {} crashes ghost {}", hunter, target);
+        if let Some(sender) = self.command_send.get(&target) {
+            let _ = sender.send(DroneCommand::Crash);
+        }
+        for neighbor in self.topology.remove_node(target) {
+            if let Some(sender) = self.command_send.get(&neighbor) {
+                let _ = sender.send(DroneCommand::RemoveSender(target));
+            }
+        }
+        if let Some(stats) = self.stats.get_mut(&target) {
+            stats.crashed = true;
+        }
+    }
+}
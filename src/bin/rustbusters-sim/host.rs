@@ -0,0 +1,330 @@
+use crate::network::Topology;
+use crossbeam_channel::{Receiver, Sender};
+use log::{debug, info, warn};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use wg_2024::network::{NodeId, SourceRoutingHeader};
+use wg_2024::packet::{
+    Ack, FloodRequest, FloodResponse, Fragment, Nack, NackType, NodeType, Packet, PacketType,
+    FRAGMENT_DSIZE,
+};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+const FLOOD_WAIT: Duration = Duration::from_millis(300);
+const RETRANSMIT_AFTER: Duration = Duration::from_secs(1);
+
+/// Traffic generated by the built-in clients
+#[derive(Debug, Clone, Copy)]
+pub struct Workload {
+    /// Messages sent by every client to every server
+    pub messages: u64,
+    /// Fragments per message
+    pub fragments: u64,
+}
+
+/// Counters collected by a client or a server
+#[derive(Debug, Clone, Default)]
+pub struct HostStats {
+    pub fragments: u64,
+    pub transmissions: u64,
+    pub acked: u64,
+    pub nacks: u64,
+    pub floods: u64,
+    pub received: u64,
+}
+
+impl HostStats {
+    /// Share of transmissions that were acknowledged
+    pub fn delivery_ratio(&self) -> f64 {
+        if self.transmissions == 0 {
+            return 0.0;
+        }
+        self.acked as f64 / self.transmissions as f64
+    }
+}
+
+struct PendingFragment {
+    destination: NodeId,
+    fragment: Fragment,
+    sent_at: Option<Instant>,
+}
+
+/// Built-in client or server of the simulation
+pub struct Host {
+    id: NodeId,
+    node_type: NodeType,
+    packet_recv: Receiver<Packet>,
+    packet_send: HashMap<NodeId, Sender<Packet>>,
+    stop: Arc<AtomicBool>,
+    topology: Topology,
+    next_flood_id: u64,
+    next_session_id: u64,
+    pending: HashMap<(u64, u64), PendingFragment>,
+    stale_topology: bool,
+    stats: HostStats,
+}
+
+impl Host {
+    pub fn new(
+        id: NodeId,
+        node_type: NodeType,
+        packet_recv: Receiver<Packet>,
+        packet_send: HashMap<NodeId, Sender<Packet>>,
+        stop: Arc<AtomicBool>,
+    ) -> Self {
+        let mut topology = Topology::default();
+        topology.add_node(id, node_type);
+        Self {
+            id,
+            node_type,
+            packet_recv,
+            packet_send,
+            stop,
+            topology,
+            next_flood_id: 0,
+            next_session_id: u64::from(id) << 32,
+            pending: HashMap::new(),
+            stale_topology: false,
+            stats: HostStats::default(),
+        }
+    }
+
+    /// Answers floods and acknowledges fragments until the simulation stops
+    pub fn run_server(mut self) -> HostStats {
+        while !self.stop.load(Ordering::Relaxed) {
+            if let Ok(packet) = self.packet_recv.recv_timeout(POLL_INTERVAL) {
+                self.handle_packet(packet);
+            }
+        }
+        self.stats
+    }
+
+    /// Discovers the network, then sends the workload to every server until it is fully acknowledged
+    pub fn run_client(mut self, workload: Workload, deadline: Instant) -> HostStats {
+        let mut servers = Vec::new();
+        while servers.is_empty() && Instant::now() < deadline && !self.stop.load(Ordering::Relaxed)
+        {
+            self.flood();
+            servers = self.topology.nodes_of_type(NodeType::Server);
+        }
+        info!("Client {} - Discovered servers {:?}", self.id, servers);
+
+        for &server in &servers {
+            for _ in 0..workload.messages {
+                self.queue_message(server, workload.fragments);
+            }
+        }
+
+        while !self.pending.is_empty()
+            && Instant::now() < deadline
+            && !self.stop.load(Ordering::Relaxed)
+        {
+            self.retransmit_pending();
+            if let Ok(packet) = self.packet_recv.recv_timeout(POLL_INTERVAL) {
+                self.handle_packet(packet);
+            }
+        }
+        if !self.pending.is_empty() {
+            warn!(
+                "Client {} - {} fragments never acknowledged",
+                self.id,
+                self.pending.len()
+            );
+        }
+        self.stats
+    }
+
+    /// Discards the known topology and floods the network to rebuild it
+    fn flood(&mut self) {
+        self.topology = Topology::default();
+        self.topology.add_node(self.id, self.node_type);
+        self.stale_topology = false;
+        self.next_flood_id += 1;
+        self.stats.floods += 1;
+        let request = FloodRequest {
+            flood_id: self.next_flood_id,
+            initiator_id: self.id,
+            path_trace: vec![(self.id, self.node_type)],
+        };
+        for (&neighbor, sender) in &self.packet_send {
+            let _ = sender.send(Packet {
+                pack_type: PacketType::FloodRequest(request.clone()),
+                routing_header: SourceRoutingHeader {
+                    hop_index: 1,
+                    hops: vec![self.id, neighbor],
+                },
+                session_id: 0,
+            });
+        }
+
+        let until = Instant::now() + FLOOD_WAIT;
+        while Instant::now() < until {
+            if let Ok(packet) = self.packet_recv.recv_timeout(POLL_INTERVAL) {
+                self.handle_packet(packet);
+            }
+        }
+    }
+
+    fn queue_message(&mut self, destination: NodeId, fragments: u64) {
+        self.next_session_id += 1;
+        for fragment_index in 0..fragments {
+            let mut data = [0; FRAGMENT_DSIZE];
+            let text = format!("{}->{} #{}", self.id, destination, fragment_index);
+            data[..text.len()].copy_from_slice(text.as_bytes());
+            let fragment = Fragment {
+                fragment_index,
+                total_n_fragments: fragments,
+                length: text.len() as u8,
+                data,
+            };
+            self.stats.fragments += 1;
+            self.pending.insert(
+                (self.next_session_id, fragment_index),
+                PendingFragment {
+                    destination,
+                    fragment,
+                    sent_at: None,
+                },
+            );
+        }
+    }
+
+    /// Sends the fragments that were never sent, were Nacked or timed out
+    fn retransmit_pending(&mut self) {
+        let now = Instant::now();
+        let due: Vec<(u64, u64)> = self
+            .pending
+            .iter()
+            .filter(|(_, p)| p.sent_at.is_none_or(|t| now - t >= RETRANSMIT_AFTER))
+            .map(|(&key, _)| key)
+            .collect();
+
+        let mut unroutable = self.stale_topology;
+        for (session_id, fragment_index) in due {
+            let Some(pending) = self.pending.get_mut(&(session_id, fragment_index)) else {
+                continue;
+            };
+            let Some(hops) = self.topology.route(self.id, pending.destination) else {
+                unroutable = true;
+                continue;
+            };
+            let packet = Packet {
+                pack_type: PacketType::MsgFragment(pending.fragment.clone()),
+                routing_header: SourceRoutingHeader { hop_index: 1, hops },
+                session_id,
+            };
+            pending.sent_at = Some(now);
+            self.stats.transmissions += 1;
+            self.send(packet);
+        }
+        if unroutable {
+            self.flood();
+        }
+    }
+
+    fn send(&mut self, packet: Packet) {
+        let next_hop = packet.routing_header.hops[packet.routing_header.hop_index];
+        match self.packet_send.get(&next_hop) {
+            Some(sender) => {
+                if sender.send(packet).is_err() {
+                    warn!("Host {} - Neighbor {} is unreachable", self.id, next_hop);
+                    self.packet_send.remove(&next_hop);
+                    self.topology.remove_edge(self.id, next_hop);
+                }
+            }
+            None => warn!("Host {} - {} is not a neighbor", self.id, next_hop),
+        }
+    }
+
+    fn handle_packet(&mut self, packet: Packet) {
+        match packet.pack_type {
+            PacketType::FloodRequest(request) => {
+                self.answer_flood(request, packet.session_id);
+            }
+            PacketType::FloodResponse(response) => self.learn(&response),
+            PacketType::MsgFragment(fragment) => {
+                self.stats.received += 1;
+                let hops: Vec<NodeId> = packet
+                    .routing_header
+                    .hops
+                    .get(..=packet.routing_header.hop_index)
+                    .unwrap_or_default()
+                    .iter()
+                    .rev()
+                    .copied()
+                    .collect();
+                if hops.len() > 1 {
+                    self.send(Packet {
+                        pack_type: PacketType::Ack(Ack {
+                            fragment_index: fragment.fragment_index,
+                        }),
+                        routing_header: SourceRoutingHeader { hop_index: 1, hops },
+                        session_id: packet.session_id,
+                    });
+                }
+            }
+            PacketType::Ack(ack) => {
+                if self
+                    .pending
+                    .remove(&(packet.session_id, ack.fragment_index))
+                    .is_some()
+                {
+                    self.stats.acked += 1;
+                }
+            }
+            PacketType::Nack(nack) => {
+                self.stats.nacks += 1;
+                let reporter = packet.routing_header.hops.first().copied();
+                self.handle_nack(packet.session_id, &nack, reporter);
+            }
+        }
+    }
+
+    fn handle_nack(&mut self, session_id: u64, nack: &Nack, reporter: Option<NodeId>) {
+        debug!("Host {} - Received {:?} from {:?}", self.id, nack, reporter);
+        match (nack.nack_type, reporter) {
+            (NackType::ErrorInRouting(unreachable), Some(reporter)) => {
+                self.topology.remove_edge(reporter, unreachable);
+            }
+            (NackType::UnexpectedRecipient(_) | NackType::DestinationIsDrone, _) => {
+                self.stale_topology = true;
+            }
+            _ => {}
+        }
+        if let Some(pending) = self.pending.get_mut(&(session_id, nack.fragment_index)) {
+            pending.sent_at = None;
+        }
+    }
+
+    fn answer_flood(&mut self, mut request: FloodRequest, session_id: u64) {
+        request.path_trace.push((self.id, self.node_type));
+        let mut hops: Vec<NodeId> = request.path_trace.iter().map(|(id, _)| *id).rev().collect();
+        if hops.last() != Some(&request.initiator_id) {
+            hops.push(request.initiator_id);
+        }
+        if hops.len() < 2 {
+            return;
+        }
+        self.send(Packet {
+            pack_type: PacketType::FloodResponse(FloodResponse {
+                flood_id: request.flood_id,
+                path_trace: request.path_trace,
+            }),
+            routing_header: SourceRoutingHeader { hop_index: 1, hops },
+            session_id,
+        });
+    }
+
+    fn learn(&mut self, response: &FloodResponse) {
+        for &(id, node_type) in &response.path_trace {
+            if self.topology.node_type(id).is_none() {
+                self.topology.add_node(id, node_type);
+            }
+        }
+        for pair in response.path_trace.windows(2) {
+            self.topology.add_edge(pair[0].0, pair[1].0);
+        }
+    }
+}
@@ -0,0 +1,292 @@
+//! Runs a local simulation of a `wg_2024` network initialization file where every drone
+//! is a `RustBustersDrone`, with built-in clients and servers generating traffic.
+//!
+//! ```text
+//! rustbusters-sim topology.toml --messages 10 --fragments 5 --hunt-mode --ghost 3
+//! rustbusters-sim topology.toml --dot network.dot && dot -Tsvg network.dot -o network.svg
+//! ```
+mod controller;
+mod host;
+mod network;
+
+use crate::controller::SimController;
+use crate::host::{Host, HostStats, Workload};
+use crate::network::Topology;
+use clap::Parser;
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use rustbusters_drone::scheduler::TrafficClass;
+use rustbusters_drone::topology::NetworkView;
+use rustbusters_drone::RustBustersDrone;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use wg_2024::config::Config;
+use wg_2024::drone::Drone;
+use wg_2024::network::NodeId;
+use wg_2024::packet::{NodeType, Packet};
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "rustbusters-sim",
+    version,
+    about = "Runs a wg_2024 topology file with RustBustersDrones and built-in clients and servers"
+)]
+struct Args {
+    /// wg_2024 network initialization file
+    topology: PathBuf,
+    /// Messages sent by every client to every server
+    #[arg(long, default_value_t = 5)]
+    messages: u64,
+    /// Fragments per message
+    #[arg(long, default_value_t = 5)]
+    fragments: u64,
+    /// Maximum duration of the simulation, in seconds
+    #[arg(long, default_value_t = 30)]
+    duration: u64,
+    /// Enables optimized routing on every drone
+    #[arg(long)]
+    optimized_routing: bool,
+    /// Enables hunt mode on every drone
+    #[arg(long)]
+    hunt_mode: bool,
+    /// Maximum length of the path_trace of the flood requests spread by the drones
+    #[arg(long)]
+    max_flood_path_len: Option<usize>,
+    /// Drone that may be crashed by a hunt, as if it were another team's drone (repeatable)
+    #[arg(long = "ghost")]
+    ghosts: Vec<NodeId>,
+    /// Writes the network as seen by the drones to this Graphviz DOT file
+    #[arg(long)]
+    dot: Option<PathBuf>,
+}
+
+fn load_config(path: &PathBuf) -> Result<Config, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+    toml::from_str::<Config>(&content)
+        .map_err(|e| format!("cannot parse {}: {}", path.display(), e))
+}
+
+fn run() -> Result<(), String> {
+    let args = Args::parse();
+    let config = load_config(&args.topology)?;
+    let topology = Topology::from_config(&config);
+
+    let channels: HashMap<NodeId, (Sender<Packet>, Receiver<Packet>)> = config
+        .drone
+        .iter()
+        .map(|d| d.id)
+        .chain(config.client.iter().map(|c| c.id))
+        .chain(config.server.iter().map(|s| s.id))
+        .map(|id| (id, unbounded()))
+        .collect();
+    let packet_send: HashMap<NodeId, Sender<Packet>> = channels
+        .iter()
+        .map(|(&id, (sender, _))| (id, sender.clone()))
+        .collect();
+    let neighbor_senders = |id: NodeId| -> HashMap<NodeId, Sender<Packet>> {
+        topology
+            .neighbors(id)
+            .filter_map(|n| packet_send.get(&n).map(|s| (n, s.clone())))
+            .collect()
+    };
+
+    let mut drone_handles: Vec<(NodeId, JoinHandle<RustBustersDrone>)> = Vec::new();
+    let mut controller_drones = Vec::new();
+    for drone_config in &config.drone {
+        let (event_send, event_recv) = unbounded();
+        let (command_send, command_recv) = unbounded();
+        let mut drone = RustBustersDrone::new(
+            drone_config.id,
+            event_send,
+            command_recv,
+            channels[&drone_config.id].1.clone(),
+            neighbor_senders(drone_config.id),
+            drone_config.pdr,
+        );
+        drone.set_optimized_routing(args.optimized_routing);
+        drone.set_hunt_mode(args.hunt_mode);
+        drone.set_max_flood_path_len(args.max_flood_path_len);
+        drone_handles.push((
+            drone_config.id,
+            std::thread::spawn(move || {
+                drone.run();
+                drone
+            }),
+        ));
+        controller_drones.push((drone_config.id, event_recv, command_send));
+    }
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let deadline = Instant::now() + Duration::from_secs(args.duration);
+    let workload = Workload {
+        messages: args.messages,
+        fragments: args.fragments,
+    };
+    let host = |id: NodeId, node_type: NodeType| {
+        Host::new(
+            id,
+            node_type,
+            channels[&id].1.clone(),
+            neighbor_senders(id),
+            stop.clone(),
+        )
+    };
+    let client_handles: Vec<(NodeId, JoinHandle<HostStats>)> = config
+        .client
+        .iter()
+        .map(|c| {
+            let client = host(c.id, NodeType::Client);
+            (
+                c.id,
+                std::thread::spawn(move || client.run_client(workload, deadline)),
+            )
+        })
+        .collect();
+    let server_handles: Vec<(NodeId, JoinHandle<HostStats>)> = config
+        .server
+        .iter()
+        .map(|s| {
+            let server = host(s.id, NodeType::Server);
+            (s.id, std::thread::spawn(move || server.run_server()))
+        })
+        .collect();
+    drop(channels);
+
+    let ghosts: HashSet<NodeId> = args.ghosts.into_iter().collect();
+    let mut controller = SimController::new(controller_drones, packet_send, topology, ghosts);
+    while Instant::now() < deadline && client_handles.iter().any(|(_, h)| !h.is_finished()) {
+        controller.poll(Duration::from_millis(20));
+    }
+
+    controller.crash_all();
+    stop.store(true, Ordering::Relaxed);
+    while drone_handles.iter().any(|(_, h)| !h.is_finished()) {
+        controller.poll(Duration::from_millis(20));
+    }
+    while controller.poll(Duration::ZERO) {}
+    let drones: BTreeMap<NodeId, RustBustersDrone> = drone_handles
+        .into_iter()
+        .filter_map(|(id, handle)| Some((id, handle.join().ok()?)))
+        .collect();
+    if let Some(path) = &args.dot {
+        let dot = NetworkView::merge(drones.values().map(RustBustersDrone::network_view)).to_dot();
+        std::fs::write(path, dot).map_err(|e| format!("cannot write {}: {}", path.display(), e))?;
+    }
+
+    let clients = join_hosts(client_handles);
+    let servers = join_hosts(server_handles);
+    print_report(&controller, &clients, &servers);
+    print_latency(&drones);
+    Ok(())
+}
+
+fn join_hosts(handles: Vec<(NodeId, JoinHandle<HostStats>)>) -> BTreeMap<NodeId, HostStats> {
+    handles
+        .into_iter()
+        .map(|(id, handle)| (id, handle.join().unwrap_or_default()))
+        .collect()
+}
+
+fn print_report(
+    controller: &SimController,
+    clients: &BTreeMap<NodeId, HostStats>,
+    servers: &BTreeMap<NodeId, HostStats>,
+) {
+    println!(
+        "{:>5} {:>10} {:>8} {:>8} {:>9} {:>6} {:>8} {:>9}",
+        "drone", "forwarded", "other", "dropped", "shortcuts", "hunts", "reports", "delivery"
+    );
+    for (id, stats) in controller.stats() {
+        println!(
+            "{:>5} {:>10} {:>8} {:>8} {:>9} {:>6} {:>8} {:>8.1}%",
+            id,
+            stats.fragments_forwarded,
+            stats.other_sent,
+            stats.dropped,
+            stats.shortcuts,
+            stats.hunts,
+            stats.reports,
+            stats.delivery_ratio() * 100.0
+        );
+    }
+
+    println!();
+    println!(
+        "{:>6} {:>9} {:>13} {:>6} {:>6} {:>7} {:>9}",
+        "client", "fragments", "transmissions", "acked", "nacks", "floods", "delivery"
+    );
+    for (id, stats) in clients {
+        println!(
+            "{:>6} {:>9} {:>13} {:>6} {:>6} {:>7} {:>8.1}%",
+            id,
+            stats.fragments,
+            stats.transmissions,
+            stats.acked,
+            stats.nacks,
+            stats.floods,
+            stats.delivery_ratio() * 100.0
+        );
+    }
+
+    println!();
+    println!("{:>6} {:>9}", "server", "received");
+    for (id, stats) in servers {
+        println!("{:>6} {:>9}", id, stats.received);
+    }
+
+    let fragments: u64 = clients.values().map(|s| s.fragments).sum();
+    let acked: u64 = clients.values().map(|s| s.acked).sum();
+    if fragments > 0 {
+        println!();
+        println!(
+            "Delivered {}/{} fragments ({:.1}%)",
+            acked,
+            fragments,
+            acked as f64 / fragments as f64 * 100.0
+        );
+    }
+}
+
+/// Prints the forwarding latency of every drone, per traffic class
+fn print_latency(drones: &BTreeMap<NodeId, RustBustersDrone>) {
+    println!();
+    println!(
+        "{:>5} {:>8} {:>6} {:>10} {:>10} {:>10}",
+        "drone", "class", "sends", "p50", "p99", "max"
+    );
+    for (id, drone) in drones {
+        let latency = drone.stats().latency;
+        for class in [
+            TrafficClass::Control,
+            TrafficClass::Flood,
+            TrafficClass::Data,
+        ] {
+            let histogram = latency.class(class);
+            if histogram.is_empty() {
+                continue;
+            }
+            let (p50, p99, max) = histogram.tail();
+            println!(
+                "{:>5} {:>8} {:>6} {:>10} {:>10} {:>10}",
+                id,
+                format!("{class:?}"),
+                histogram.count(),
+                format!("{p50:?}"),
+                format!("{p99:?}"),
+                format!("{max:?}")
+            );
+        }
+    }
+}
+
+fn main() {
+    env_logger::init();
+    if let Err(e) = run() {
+        eprintln!("rustbusters-sim: {e}");
+        std::process::exit(1);
+    }
+}
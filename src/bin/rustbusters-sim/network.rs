@@ -0,0 +1,145 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use wg_2024::config::Config;
+use wg_2024::network::NodeId;
+use wg_2024::packet::NodeType;
+
+/// Undirected graph of the simulated network
+#[derive(Debug, Clone, Default)]
+pub struct Topology {
+    nodes: HashMap<NodeId, NodeType>,
+    edges: HashMap<NodeId, HashSet<NodeId>>,
+}
+
+impl Topology {
+    /// Builds the topology described by a network initialization file
+    pub fn from_config(config: &Config) -> Self {
+        let mut topology = Self::default();
+        for drone in &config.drone {
+            topology.add_node(drone.id, NodeType::Drone);
+        }
+        for client in &config.client {
+            topology.add_node(client.id, NodeType::Client);
+        }
+        for server in &config.server {
+            topology.add_node(server.id, NodeType::Server);
+        }
+        for drone in &config.drone {
+            for &neighbor in &drone.connected_node_ids {
+                topology.add_edge(drone.id, neighbor);
+            }
+        }
+        for (id, neighbors) in config
+            .client
+            .iter()
+            .map(|c| (c.id, &c.connected_drone_ids))
+            .chain(config.server.iter().map(|s| (s.id, &s.connected_drone_ids)))
+        {
+            for &neighbor in neighbors {
+                topology.add_edge(id, neighbor);
+            }
+        }
+        topology
+    }
+
+    pub fn add_node(&mut self, id: NodeId, node_type: NodeType) {
+        self.nodes.insert(id, node_type);
+        self.edges.entry(id).or_default();
+    }
+
+    /// Adds an edge between two known nodes, ignoring unknown endpoints
+    pub fn add_edge(&mut self, a: NodeId, b: NodeId) {
+        if a == b || !self.nodes.contains_key(&a) || !self.nodes.contains_key(&b) {
+            return;
+        }
+        self.edges.entry(a).or_default().insert(b);
+        self.edges.entry(b).or_default().insert(a);
+    }
+
+    pub fn remove_edge(&mut self, a: NodeId, b: NodeId) {
+        if let Some(neighbors) = self.edges.get_mut(&a) {
+            neighbors.remove(&b);
+        }
+        if let Some(neighbors) = self.edges.get_mut(&b) {
+            neighbors.remove(&a);
+        }
+    }
+
+    /// Removes a node and all its edges
+    ///
+    /// #### Returns
+    /// The neighbors the node was connected to
+    pub fn remove_node(&mut self, id: NodeId) -> HashSet<NodeId> {
+        self.nodes.remove(&id);
+        let neighbors = self.edges.remove(&id).unwrap_or_default();
+        for neighbor in &neighbors {
+            if let Some(edges) = self.edges.get_mut(neighbor) {
+                edges.remove(&id);
+            }
+        }
+        neighbors
+    }
+
+    pub fn node_type(&self, id: NodeId) -> Option<NodeType> {
+        self.nodes.get(&id).copied()
+    }
+
+    pub fn neighbors(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        self.edges.get(&id).into_iter().flatten().copied()
+    }
+
+    pub fn nodes_of_type(&self, node_type: NodeType) -> Vec<NodeId> {
+        let mut nodes: Vec<NodeId> = self
+            .nodes
+            .iter()
+            .filter(|(_, &t)| t == node_type)
+            .map(|(&id, _)| id)
+            .collect();
+        nodes.sort_unstable();
+        nodes
+    }
+
+    /// Finds the shortest path between two nodes, using only drones as intermediate hops
+    pub fn route(&self, from: NodeId, to: NodeId) -> Option<Vec<NodeId>> {
+        let mut previous: HashMap<NodeId, NodeId> = HashMap::new();
+        let mut queue = VecDeque::from([from]);
+        let mut visited = HashSet::from([from]);
+
+        while let Some(node) = queue.pop_front() {
+            if node == to {
+                let mut path = vec![to];
+                let mut current = to;
+                while let Some(&prev) = previous.get(&current) {
+                    path.push(prev);
+                    current = prev;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            if node != from && self.node_type(node) != Some(NodeType::Drone) {
+                continue;
+            }
+            for neighbor in self.neighbors(node) {
+                if visited.insert(neighbor) {
+                    previous.insert(neighbor, node);
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        None
+    }
+
+    /// Checks that every client can still reach every server once `removed` is taken out
+    pub fn stays_connected_without(&self, removed: NodeId) -> bool {
+        let mut topology = self.clone();
+        topology.remove_node(removed);
+        let servers = topology.nodes_of_type(NodeType::Server);
+        topology
+            .nodes_of_type(NodeType::Client)
+            .iter()
+            .all(|&client| {
+                servers
+                    .iter()
+                    .all(|&server| topology.route(client, server).is_some())
+            })
+    }
+}
@@ -0,0 +1,176 @@
+use crate::drone::report::ReportKind;
+use crate::drone::telemetry::TelemetryEvent;
+use crate::RustBustersDrone;
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use wg_2024::controller::DroneEvent;
+use wg_2024::network::NodeId;
+use wg_2024::packet::{Nack, NackType, Packet, PacketType};
+
+/// Settings of the local retransmission of the fragments dropped by the next hop
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArqSettings {
+    /// Maximum number of forwarded fragments kept in the buffer, the oldest are discarded first
+    pub capacity: usize,
+    /// Times a fragment is sent again before the `Nack::Dropped` is let through
+    pub max_retries: u8,
+}
+
+impl Default for ArqSettings {
+    fn default() -> Self {
+        Self {
+            capacity: 256,
+            max_retries: 2,
+        }
+    }
+}
+
+/// Key of a fragment: `(session_id, fragment_index)`
+type FragmentKey = (u64, u64);
+
+struct Buffered {
+    /// The fragment as it was sent, with the `hop_index` pointing to the next hop
+    packet: Packet,
+    next_hop: NodeId,
+    retries: u8,
+}
+
+/// Recently forwarded fragments, kept to be sent again if the next hop drops them
+#[derive(Default)]
+pub struct RetransmitBuffer {
+    settings: Option<ArqSettings>,
+    fragments: HashMap<FragmentKey, Buffered>,
+    order: VecDeque<FragmentKey>,
+}
+
+impl RetransmitBuffer {
+    /// Returns the settings of the buffer, `None` if the fragments are not kept
+    pub fn settings(&self) -> Option<ArqSettings> {
+        self.settings
+    }
+
+    /// Keeps a forwarded fragment
+    pub fn push(&mut self, key: FragmentKey, packet: Packet, next_hop: NodeId) {
+        let Some(settings) = self.settings else {
+            return;
+        };
+        let buffered = Buffered {
+            packet,
+            next_hop,
+            retries: 0,
+        };
+        if self.fragments.insert(key, buffered).is_none() {
+            self.order.push_back(key);
+        }
+        while self.fragments.len() > settings.capacity {
+            let Some(oldest) = self.order.pop_front() else {
+                break;
+            };
+            self.fragments.remove(&oldest);
+        }
+    }
+
+    /// Takes a fragment to send again, if it was sent to `dropped_by` and has retries left
+    ///
+    /// #### Returns
+    /// The fragment, the next hop and the number of the retry
+    pub fn retry(&mut self, key: FragmentKey, dropped_by: NodeId) -> Option<(Packet, NodeId, u8)> {
+        let settings = self.settings?;
+        let buffered = self.fragments.get_mut(&key)?;
+        if buffered.next_hop != dropped_by {
+            return None;
+        }
+        if buffered.retries >= settings.max_retries {
+            self.fragments.remove(&key);
+            self.order.retain(|&k| k != key);
+            return None;
+        }
+        buffered.retries += 1;
+        Some((buffered.packet.clone(), buffered.next_hop, buffered.retries))
+    }
+
+    /// Returns the number of buffered fragments
+    pub fn len(&self) -> usize {
+        self.fragments.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fragments.is_empty()
+    }
+}
+
+impl RustBustersDrone {
+    /// Enables the local retransmission of the fragments dropped by the next hop
+    ///
+    /// #### Arguments
+    /// - `settings`: The size of the buffer and the number of retries, or `None` to disable it
+    pub fn set_local_arq(&mut self, settings: Option<ArqSettings>) {
+        self.retransmit_buffer = RetransmitBuffer {
+            settings,
+            ..RetransmitBuffer::default()
+        };
+        debug!("Drone {} - Local ARQ set to {:?}", self.id, settings);
+    }
+
+    /// Keeps a fragment forwarded to a neighbor in the retransmission buffer
+    pub(crate) fn buffer_forwarded(&mut self, packet: &Packet, next_hop: NodeId) {
+        if let PacketType::MsgFragment(fragment) = &packet.pack_type {
+            self.retransmit_buffer.push(
+                (packet.session_id, fragment.fragment_index),
+                packet.clone(),
+                next_hop,
+            );
+        }
+    }
+
+    /// Sends again a fragment dropped by the next hop, instead of relaying its `Nack::Dropped`
+    ///
+    /// #### Arguments
+    /// - `packet`: The Nack packet, passing back through the drone
+    /// - `nack`: The Nack
+    ///
+    /// #### Returns
+    /// Whether the fragment was sent again, in which case the Nack must not be forwarded
+    ///
+    /// > Note: every retransmission is reported to the controller with a `DroneEvent::PacketSent`
+    /// > and a `ReportKind::Retransmitted` report: `[next_hop, retry, session_id as u64 LE, fragment_index as u64 LE]`
+    pub(crate) fn retransmit_dropped(&mut self, packet: &Packet, nack: &Nack) -> bool {
+        if nack.nack_type != NackType::Dropped || self.retransmit_buffer.is_empty() {
+            return false;
+        }
+        let Some(&dropped_by) = packet.routing_header.hops.first() else {
+            return false;
+        };
+        let key = (packet.session_id, nack.fragment_index);
+        let Some((fragment, next_hop, retry)) = self.retransmit_buffer.retry(key, dropped_by)
+        else {
+            return false;
+        };
+
+        if let Err(e) = self.send_to_neighbor(next_hop, fragment.clone()) {
+            warn!(
+                "Drone {} - Error in retransmitting fragment {:?} to {}: {}",
+                self.id, key, next_hop, e
+            );
+            return false;
+        }
+        info!(
+            "Drone {} - Retransmitted fragment {:?} dropped by {} (retry {})",
+            self.id, key, next_hop, retry
+        );
+        self.stats.retransmitted_fragments += 1;
+        self.send_to_sc(DroneEvent::PacketSent(fragment));
+        let mut payload = vec![next_hop, retry];
+        payload.extend(packet.session_id.to_le_bytes());
+        payload.extend(nack.fragment_index.to_le_bytes());
+        self.send_report(ReportKind::Retransmitted, &payload);
+        self.emit(TelemetryEvent::Retransmitted {
+            session_id: packet.session_id,
+            fragment_index: nack.fragment_index,
+            next_hop,
+            retry,
+        });
+        true
+    }
+}
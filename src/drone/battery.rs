@@ -0,0 +1,188 @@
+use crate::drone::report::ReportKind;
+use crate::drone::telemetry::TelemetryEvent;
+use crate::RustBustersDrone;
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+use wg_2024::packet::PacketType;
+
+/// Settings of the battery of the drone, in arbitrary energy units
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BatterySettings {
+    /// Energy of a full battery
+    pub capacity: f64,
+    /// Energy spent to send a `MsgFragment` to a neighbor
+    pub fragment_cost: f64,
+    /// Energy spent to send an `Ack`, a `Nack` or a `FloodResponse` to a neighbor
+    pub control_cost: f64,
+    /// Energy spent to spread a `FloodRequest` to a neighbor
+    pub flood_cost: f64,
+    /// Energy spent to receive a packet
+    pub receive_cost: f64,
+    /// Energy spent per second, even with no traffic
+    pub idle_drain: f64,
+    /// The level is reported every time it drops by this many percentage points
+    pub report_step: u8,
+}
+
+impl Default for BatterySettings {
+    fn default() -> Self {
+        Self {
+            capacity: 10_000.0,
+            fragment_cost: 1.0,
+            control_cost: 0.2,
+            flood_cost: 0.5,
+            receive_cost: 0.1,
+            idle_drain: 1.0,
+            report_step: 10,
+        }
+    }
+}
+
+/// Charge of the battery of a drone
+#[derive(Debug, Clone, Copy)]
+pub struct Battery {
+    settings: BatterySettings,
+    energy: f64,
+    last_drain: Instant,
+    /// Last step reached by the level, in percent
+    last_reported: u8,
+}
+
+impl Battery {
+    /// Creates a full battery
+    pub fn new(settings: BatterySettings, now: Instant) -> Self {
+        Self {
+            settings,
+            energy: settings.capacity.max(0.0),
+            last_drain: now,
+            last_reported: 100,
+        }
+    }
+
+    pub fn settings(&self) -> BatterySettings {
+        self.settings
+    }
+
+    /// Returns the energy left
+    pub fn energy(&self) -> f64 {
+        self.energy
+    }
+
+    /// Returns the level of the battery, in percent
+    pub fn level(&self) -> f64 {
+        if self.settings.capacity <= 0.0 {
+            return 0.0;
+        }
+        self.energy / self.settings.capacity * 100.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.energy <= 0.0
+    }
+
+    /// Returns the cost of sending a packet to a neighbor
+    pub fn send_cost(&self, packet_type: &PacketType) -> f64 {
+        match packet_type {
+            PacketType::MsgFragment(_) => self.settings.fragment_cost,
+            PacketType::FloodRequest(_) => self.settings.flood_cost,
+            PacketType::Ack(_) | PacketType::Nack(_) | PacketType::FloodResponse(_) => {
+                self.settings.control_cost
+            }
+        }
+    }
+
+    /// Spends some energy, down to an empty battery
+    pub fn consume(&mut self, cost: f64) {
+        self.energy = (self.energy - cost.max(0.0)).max(0.0);
+    }
+
+    /// Spends the idle drain of the time passed since the last call
+    pub fn drain(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_drain).as_secs_f64();
+        self.last_drain = now;
+        self.consume(self.settings.idle_drain * elapsed);
+    }
+
+    /// Returns the level to report, if it dropped by a step since the last report
+    fn take_report(&mut self) -> Option<u8> {
+        let step = f64::from(self.settings.report_step.max(1));
+        let level = self.level().clamp(0.0, 100.0);
+        // lowest step the level has reached
+        let reached = ((level / step).ceil() * step).min(100.0) as u8;
+        if reached >= self.last_reported {
+            return None;
+        }
+        self.last_reported = reached;
+        Some(level.floor() as u8)
+    }
+}
+
+impl RustBustersDrone {
+    /// Sets the battery of the drone
+    ///
+    /// #### Arguments
+    /// - `settings`: The capacity and the energy costs of a full battery, or `None` for unlimited energy
+    ///
+    /// > Note: the drone crashes itself, as on `DroneCommand::Crash`, when the battery is empty
+    pub fn set_battery(&mut self, settings: Option<BatterySettings>) {
+        self.battery = settings.map(|settings| Battery::new(settings, Instant::now()));
+        debug!("Drone {} - Battery set to {:?}", self.id, settings);
+    }
+
+    /// Returns the level of the battery, in percent, or `None` if the drone has unlimited energy
+    pub fn battery_level(&self) -> Option<f64> {
+        self.battery.as_ref().map(Battery::level)
+    }
+
+    /// Returns the energy needed to send a packet to a neighbor, 0 if the drone has unlimited energy
+    pub(crate) fn send_energy(&self, packet_type: &PacketType) -> f64 {
+        self.battery
+            .as_ref()
+            .map_or(0.0, |battery| battery.send_cost(packet_type))
+    }
+
+    /// Spends the energy of receiving a packet
+    pub(crate) fn consume_receive_energy(&mut self) {
+        let cost = self
+            .battery
+            .as_ref()
+            .map_or(0.0, |battery| battery.settings.receive_cost);
+        self.consume_energy(cost);
+    }
+
+    /// Spends some energy, if the drone has a battery
+    pub(crate) fn consume_energy(&mut self, cost: f64) {
+        if let Some(battery) = &mut self.battery {
+            battery.consume(cost);
+            self.check_battery();
+        }
+    }
+
+    /// Spends the idle drain of the battery up to `now`
+    pub(crate) fn drain_battery_at(&mut self, now: Instant) {
+        if let Some(battery) = &mut self.battery {
+            battery.drain(now);
+            self.check_battery();
+        }
+    }
+
+    /// Reports the level of the battery if it dropped by a step, and crashes the drone if it is empty
+    ///
+    /// > Note: the payload of the `ReportKind::Battery` report is `[level in percent]`
+    fn check_battery(&mut self) {
+        let Some(battery) = &mut self.battery else {
+            return;
+        };
+        let empty = battery.is_empty();
+        if let Some(level) = battery.take_report() {
+            info!("Drone {} - Battery at {}%", self.id, level);
+            self.send_report(ReportKind::Battery, &[level]);
+            self.emit(TelemetryEvent::Battery { level });
+        }
+        if empty && self.running {
+            warn!("Drone {} - Battery empty. Shutting down.", self.id);
+            self.begin_crash();
+        }
+    }
+}
@@ -0,0 +1,243 @@
+use crate::drone::report::ReportKind;
+use crate::drone::telemetry::TelemetryEvent;
+use crate::RustBustersDrone;
+use log::{debug, warn};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use wg_2024::network::NodeId;
+
+/// When a step of a `ChaosPlan` is due
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChaosTrigger {
+    /// Once, after the drone received this many packets since the plan was attached
+    AfterPackets(u64),
+    /// Once, this long after the plan was attached
+    AfterTime(Duration),
+    /// Periodically, every time this long has passed
+    Every(Duration),
+}
+
+/// What the drone does to itself when a step of a `ChaosPlan` fires
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChaosAction {
+    /// Crash, as on `DroneCommand::Crash`
+    Crash,
+    /// Refuse to send anything to a neighbor for a while, as if the link was down
+    LinkFlap {
+        neighbor: NodeId,
+        duration: Duration,
+    },
+    /// Drop the fragments with at least this Packet Drop Rate (0-100) for a while
+    RaisePdr { pdr: u8, duration: Duration },
+}
+
+impl ChaosAction {
+    /// Encodes the action as the payload of a `ReportKind::Chaos` report
+    ///
+    /// #### Returns
+    /// `[0]` for a crash, `[1, neighbor, duration ms as u32 LE]` for a link flap
+    /// and `[2, pdr, duration ms as u32 LE]` for a raised PDR
+    pub fn encode(&self) -> Vec<u8> {
+        let (kind, key, duration) = match *self {
+            ChaosAction::Crash => return vec![0],
+            ChaosAction::LinkFlap { neighbor, duration } => (1, neighbor, duration),
+            ChaosAction::RaisePdr { pdr, duration } => (2, pdr, duration),
+        };
+        let millis = u32::try_from(duration.as_millis()).unwrap_or(u32::MAX);
+        let mut payload = vec![kind, key];
+        payload.extend(millis.to_le_bytes());
+        payload
+    }
+}
+
+/// A step of a `ChaosPlan`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ChaosStep {
+    pub trigger: ChaosTrigger,
+    /// Probability (0-1) that the action is taken when the step is due
+    pub probability: f64,
+    pub action: ChaosAction,
+}
+
+/// Faults the drone injects into itself, reproducible from the seed
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct ChaosPlan {
+    /// Seed of the RNG rolling the probabilities of the steps
+    pub seed: u64,
+    pub steps: Vec<ChaosStep>,
+}
+
+/// State of the `ChaosPlan` attached to a drone
+#[derive(Debug, Clone)]
+pub struct Chaos {
+    seed: u64,
+    steps: Vec<ChaosStep>,
+    /// When each step is due next, `None` once a one-shot step fired
+    next_due: Vec<Option<Instant>>,
+    rng: StdRng,
+    packets: u64,
+    /// Neighbors the drone refuses to send to, until when
+    flaps: HashMap<NodeId, Instant>,
+    /// Raised Packet Drop Rate, until when
+    pdr: Option<(u8, Instant)>,
+}
+
+impl Chaos {
+    /// Attaches a plan, with its steps due from `now`
+    ///
+    /// > Note: the probabilities are clamped to 0-1, and a step with a non-finite probability never fires
+    pub fn new(mut plan: ChaosPlan, now: Instant) -> Self {
+        for step in &mut plan.steps {
+            if !step.probability.is_finite() {
+                warn!(
+                    "Chaos step {:?} has a non-finite probability, it will never fire",
+                    step
+                );
+                step.probability = 0.0;
+            }
+            step.probability = step.probability.clamp(0.0, 1.0);
+        }
+        let next_due = plan
+            .steps
+            .iter()
+            .map(|step| match step.trigger {
+                ChaosTrigger::AfterPackets(_) => Some(now),
+                ChaosTrigger::AfterTime(delay) | ChaosTrigger::Every(delay) => Some(now + delay),
+            })
+            .collect();
+        Self {
+            seed: plan.seed,
+            steps: plan.steps,
+            next_due,
+            rng: StdRng::seed_from_u64(plan.seed),
+            packets: 0,
+            flaps: HashMap::new(),
+            pdr: None,
+        }
+    }
+
+    /// Returns the plan the chaos was created from
+    pub fn plan(&self) -> ChaosPlan {
+        ChaosPlan {
+            seed: self.seed,
+            steps: self.steps.clone(),
+        }
+    }
+
+    /// Counts a received packet
+    pub fn note_packet(&mut self) {
+        self.packets += 1;
+    }
+
+    /// Takes the actions of the steps due at `now`
+    ///
+    /// #### Returns
+    /// The actions taken, in the order of the plan
+    ///
+    /// > Note: the probability of a step is rolled every time it is due, also when the action is not taken
+    pub fn fire(&mut self, now: Instant) -> Vec<ChaosAction> {
+        let mut actions = Vec::new();
+        for (step, next_due) in self.steps.iter().zip(self.next_due.iter_mut()) {
+            let Some(due) = *next_due else {
+                continue;
+            };
+            let is_due = match step.trigger {
+                ChaosTrigger::AfterPackets(packets) => self.packets >= packets,
+                ChaosTrigger::AfterTime(_) | ChaosTrigger::Every(_) => now >= due,
+            };
+            if !is_due {
+                continue;
+            }
+            *next_due = match step.trigger {
+                // the periods missed while the drone was busy are skipped
+                ChaosTrigger::Every(period) => Some((due + period).max(now)),
+                _ => None,
+            };
+            if self.rng.gen_bool(step.probability) {
+                actions.push(step.action);
+            }
+        }
+        for &action in &actions {
+            self.apply(action, now);
+        }
+        actions
+    }
+
+    /// Applies a link flap or a raised PDR until its end
+    fn apply(&mut self, action: ChaosAction, now: Instant) {
+        match action {
+            ChaosAction::Crash => {}
+            ChaosAction::LinkFlap { neighbor, duration } => {
+                self.flaps.insert(neighbor, now + duration);
+            }
+            ChaosAction::RaisePdr { pdr, duration } => {
+                self.pdr = Some((pdr.min(100), now + duration));
+            }
+        }
+    }
+
+    /// Checks whether the link to a neighbor is flapping at `now`
+    pub fn is_flapping(&self, neighbor: NodeId, now: Instant) -> bool {
+        self.flaps.get(&neighbor).is_some_and(|&until| now < until)
+    }
+
+    /// Returns the raised Packet Drop Rate at `now`, if any
+    pub fn pdr(&self, now: Instant) -> Option<u8> {
+        self.pdr
+            .and_then(|(pdr, until)| (now < until).then_some(pdr))
+    }
+}
+
+impl RustBustersDrone {
+    /// Attaches a chaos plan to the drone, replacing the previous one
+    ///
+    /// #### Arguments
+    /// - `plan`: The faults to inject, or `None` to stop injecting them
+    ///
+    /// > Note: the plan is reproducible: the same seed takes the same actions for the same traffic and timing
+    pub fn set_chaos_plan(&mut self, plan: Option<ChaosPlan>) {
+        debug!("Drone {} - Chaos plan set to {:?}", self.id, plan);
+        self.chaos = plan.map(|plan| Chaos::new(plan, Instant::now()));
+    }
+
+    /// Returns the Packet Drop Rate currently applied, raised by the chaos plan if needed
+    pub(crate) fn current_pdr(&self) -> u8 {
+        let raised = self
+            .chaos
+            .as_ref()
+            .and_then(|chaos| chaos.pdr(Instant::now()));
+        raised.map_or(self.pdr, |pdr| pdr.max(self.pdr))
+    }
+
+    /// Checks whether the chaos plan forbids sending to a neighbor
+    pub(crate) fn is_flapping(&self, neighbor: NodeId) -> bool {
+        self.chaos
+            .as_ref()
+            .is_some_and(|chaos| chaos.is_flapping(neighbor, Instant::now()))
+    }
+
+    /// Counts a received packet for the `ChaosTrigger::AfterPackets` steps
+    pub(crate) fn note_chaos_packet(&mut self) {
+        if let Some(chaos) = &mut self.chaos {
+            chaos.note_packet();
+        }
+    }
+
+    /// Takes the actions of the chaos plan due at `now`, reporting each of them to the controller
+    pub(crate) fn run_chaos_at(&mut self, now: Instant) {
+        let Some(chaos) = &mut self.chaos else {
+            return;
+        };
+        for action in chaos.fire(now) {
+            warn!("Drone {} - Chaos: {:?}", self.id, action);
+            self.send_report(ReportKind::Chaos, &action.encode());
+            self.emit(TelemetryEvent::Chaos(action));
+            if action == ChaosAction::Crash {
+                self.begin_crash();
+            }
+        }
+    }
+}
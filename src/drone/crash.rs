@@ -0,0 +1,180 @@
+use crate::drone::report::ReportKind;
+#[cfg(feature = "sounds")]
+use crate::drone::sounds::sounds_feat::SoundEvent;
+use crate::drone::telemetry::{Lifecycle, TelemetryEvent};
+use crate::RustBustersDrone;
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use wg_2024::controller::DroneEvent;
+use wg_2024::packet::{Nack, NackType, Packet, PacketType};
+
+/// Default time given to the drone to handle the packets still in flight after a crash
+pub const DEFAULT_DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// What happens to the fragments received after a crash
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum InFlightPolicy {
+    /// The fragments are answered with `Nack::ErrorInRouting`
+    #[default]
+    Nack,
+    /// The fragments are sent to the controller as `ControllerShortcut`s
+    Shortcut,
+}
+
+/// Procedure followed by the drone on `DroneCommand::Crash`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CrashPolicy {
+    /// Time given to the drone to handle the packets still in flight
+    pub drain_timeout: Duration,
+    /// What happens to the fragments received after the crash
+    pub in_flight: InFlightPolicy,
+    /// Whether the senders to the neighbors are dropped once the drone stops
+    pub close_neighbors: bool,
+}
+
+impl Default for CrashPolicy {
+    fn default() -> Self {
+        Self {
+            drain_timeout: DEFAULT_DRAIN_TIMEOUT,
+            in_flight: InFlightPolicy::default(),
+            close_neighbors: true,
+        }
+    }
+}
+
+/// Final report sent to the controller when the drone stops
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ShutdownReport {
+    pub packets_received: u64,
+    pub fragments_forwarded: u64,
+    pub fragments_dropped: u64,
+    pub nacks_sent: u64,
+    /// Packets still queued or waiting on the receiver when the drain deadline expired
+    pub abandoned: u64,
+}
+
+impl ShutdownReport {
+    /// Encodes the report as the payload of a `ReportKind::Shutdown` report,
+    /// with every counter as a little endian `u64`
+    pub fn encode(&self) -> Vec<u8> {
+        [
+            self.packets_received,
+            self.fragments_forwarded,
+            self.fragments_dropped,
+            self.nacks_sent,
+            self.abandoned,
+        ]
+        .iter()
+        .flat_map(|counter| counter.to_le_bytes())
+        .collect()
+    }
+
+    /// Decodes the payload of a `ReportKind::Shutdown` report
+    ///
+    /// #### Returns
+    /// The report, or `None` if the payload is too short
+    pub fn decode(payload: &[u8]) -> Option<Self> {
+        let mut counters = payload
+            .chunks_exact(8)
+            .map(|chunk| u64::from_le_bytes(chunk.try_into().unwrap_or_default()));
+        Some(Self {
+            packets_received: counters.next()?,
+            fragments_forwarded: counters.next()?,
+            fragments_dropped: counters.next()?,
+            nacks_sent: counters.next()?,
+            abandoned: counters.next()?,
+        })
+    }
+}
+
+impl RustBustersDrone {
+    /// Sets the procedure followed by the drone on `DroneCommand::Crash`
+    ///
+    /// #### Arguments
+    /// - `policy`: The crash procedure
+    pub fn set_crash_policy(&mut self, policy: CrashPolicy) {
+        self.crash_policy = policy;
+        debug!("Drone {} - Crash policy set to {:?}", self.id, policy);
+    }
+
+    /// Starts the crash procedure: the drone stops spreading floods and accepting fragments,
+    /// and keeps handling the packets in flight until the drain deadline
+    pub(crate) fn begin_crash(&mut self) {
+        if !self.running {
+            return;
+        }
+        #[cfg(feature = "sounds")]
+        self.play_sound(SoundEvent::Crash);
+        self.running = false;
+        self.emit(TelemetryEvent::Lifecycle(Lifecycle::Crashing));
+        self.crash_deadline = Some(Instant::now() + self.crash_policy.drain_timeout);
+    }
+
+    /// Checks whether the drain deadline of the crash procedure has expired
+    pub(crate) fn drain_expired(&self) -> bool {
+        self.crash_deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    /// Handles a fragment received after the crash, according to the in-flight policy
+    ///
+    /// #### Arguments
+    /// - `packet`: The fragment, with the `hop_index` already incremented
+    /// - `allow_optimized`: A boolean indicating whether optimized routing is allowed
+    pub(crate) fn handle_in_flight_fragment(&mut self, packet: &Packet, allow_optimized: bool) {
+        let PacketType::MsgFragment(ref fragment) = packet.pack_type else {
+            return;
+        };
+        match self.crash_policy.in_flight {
+            InFlightPolicy::Nack => self.send_nack(
+                packet,
+                Nack {
+                    fragment_index: fragment.fragment_index,
+                    nack_type: NackType::ErrorInRouting(self.id),
+                },
+                allow_optimized,
+            ),
+            InFlightPolicy::Shortcut => {
+                debug!(
+                    "Drone {} - Crashed, sending fragment {} to the controller",
+                    self.id, fragment.fragment_index
+                );
+                self.send_to_sc(DroneEvent::ControllerShortcut(packet.clone()));
+            }
+        }
+    }
+
+    /// Ends the crash procedure: discards what was not drained in time, closes the senders
+    /// to the neighbors and emits the final report on the telemetry stream
+    ///
+    /// > Note: the final report is also sent to the controller if the controller reports are enabled
+    pub(crate) fn finish_crash(&mut self) {
+        let abandoned = self.scheduler.len() + self.packet_recv.try_iter().count();
+        while self.scheduler.pop().is_some() {}
+        if abandoned > 0 {
+            warn!(
+                "Drone {} - Drain deadline expired, abandoned {} packets",
+                self.id, abandoned
+            );
+        }
+
+        self.report_neighbor_changes();
+        if self.crash_policy.close_neighbors {
+            self.packet_send.clear();
+            info!("Drone {} - Closed the senders to the neighbors", self.id);
+        }
+
+        let report = ShutdownReport {
+            packets_received: self.stats.packets_received,
+            fragments_forwarded: self.stats.fragments_forwarded,
+            fragments_dropped: self.stats.fragments_dropped,
+            nacks_sent: self.stats.nacks_sent,
+            abandoned: abandoned as u64,
+        };
+        info!("Drone {} - Shutdown with {:?}", self.id, report);
+        self.send_report(ReportKind::Shutdown, &report.encode());
+        self.emit(TelemetryEvent::Shutdown(report));
+        self.report_latency();
+    }
+}
@@ -0,0 +1,258 @@
+use crate::RustBustersDrone;
+use log::{debug, info};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+use wg_2024::controller::DroneEvent;
+use wg_2024::network::{NodeId, SourceRoutingHeader};
+use wg_2024::packet::{Ack, Nack, NackType, Packet, PacketType};
+
+/// What happens to a fragment already forwarded to the same next hop within the window
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum DuplicatePolicy {
+    /// The duplicate is forwarded again, and only counted
+    #[default]
+    Forward,
+    /// The duplicate is not forwarded. If an `Ack` for the fragment already came back through the drone,
+    /// the `Ack` is sent again to its source through the controller
+    Suppress,
+}
+
+/// Settings of the cache of the recently forwarded fragments
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DedupSettings {
+    pub policy: DuplicatePolicy,
+    /// How long a forwarded fragment is remembered
+    pub window: Duration,
+    /// Maximum number of fragments remembered, the oldest are forgotten first
+    pub capacity: usize,
+}
+
+impl Default for DedupSettings {
+    fn default() -> Self {
+        Self {
+            policy: DuplicatePolicy::default(),
+            window: Duration::from_secs(2),
+            capacity: 4096,
+        }
+    }
+}
+
+/// Key of a fragment: `(session_id, fragment_index)`
+type FragmentKey = (u64, u64);
+
+/// A fragment forwarded within the window
+#[derive(Debug, Clone, Copy)]
+struct Forwarded {
+    next_hop: NodeId,
+    recorded: Instant,
+    /// Whether an `Ack` for the fragment came back through the drone
+    delivered: bool,
+}
+
+/// Recently forwarded fragments, with their next hop
+#[derive(Debug, Clone, Default)]
+pub struct FragmentCache {
+    settings: Option<DedupSettings>,
+    entries: HashMap<FragmentKey, Forwarded>,
+    order: VecDeque<(FragmentKey, Instant)>,
+}
+
+impl FragmentCache {
+    /// Returns the settings of the cache, `None` if duplicates are not checked
+    pub fn settings(&self) -> Option<DedupSettings> {
+        self.settings
+    }
+
+    /// Checks whether a fragment was already forwarded to the same next hop within the window
+    pub fn is_duplicate(&mut self, key: FragmentKey, next_hop: NodeId, now: Instant) -> bool {
+        self.evict(now);
+        self.entries
+            .get(&key)
+            .is_some_and(|forwarded| forwarded.next_hop == next_hop)
+    }
+
+    /// Checks whether an `Ack` for a remembered fragment came back through the drone
+    pub fn is_delivered(&self, key: FragmentKey) -> bool {
+        self.entries
+            .get(&key)
+            .is_some_and(|forwarded| forwarded.delivered)
+    }
+
+    /// Marks a remembered fragment as delivered, when its `Ack` comes back through the drone
+    pub fn confirm(&mut self, key: FragmentKey) {
+        if let Some(forwarded) = self.entries.get_mut(&key) {
+            forwarded.delivered = true;
+        }
+    }
+
+    /// Forgets a fragment, when a `Nack` for it comes back through the drone,
+    /// so that its retransmission is not taken for a duplicate
+    pub fn forget(&mut self, key: FragmentKey) {
+        self.entries.remove(&key);
+    }
+
+    /// Remembers a forwarded fragment
+    pub fn record(&mut self, key: FragmentKey, next_hop: NodeId, now: Instant) {
+        let Some(settings) = self.settings else {
+            return;
+        };
+        let forwarded = Forwarded {
+            next_hop,
+            recorded: now,
+            delivered: false,
+        };
+        self.entries.insert(key, forwarded);
+        self.order.push_back((key, now));
+        while self.entries.len() > settings.capacity {
+            self.pop_oldest();
+        }
+    }
+
+    /// Returns the number of fragments remembered
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn evict(&mut self, now: Instant) {
+        let Some(settings) = self.settings else {
+            return;
+        };
+        while let Some(&(_, recorded)) = self.order.front() {
+            if now.saturating_duration_since(recorded) <= settings.window {
+                break;
+            }
+            self.pop_oldest();
+        }
+    }
+
+    /// Forgets the oldest fragment, skipping the keys recorded again since
+    fn pop_oldest(&mut self) {
+        while let Some((key, recorded)) = self.order.pop_front() {
+            if self
+                .entries
+                .get(&key)
+                .is_some_and(|forwarded| forwarded.recorded == recorded)
+            {
+                self.entries.remove(&key);
+                return;
+            }
+        }
+    }
+}
+
+impl RustBustersDrone {
+    /// Enables the suppression of the duplicate fragments
+    ///
+    /// #### Arguments
+    /// - `settings`: The policy, window and size of the cache, or `None` to disable it
+    pub fn set_duplicate_suppression(&mut self, settings: Option<DedupSettings>) {
+        self.fragment_cache = FragmentCache {
+            settings,
+            ..FragmentCache::default()
+        };
+        debug!(
+            "Drone {} - Duplicate suppression set to {:?}",
+            self.id, settings
+        );
+    }
+
+    /// Checks a fragment about to be forwarded against the recently forwarded ones
+    ///
+    /// #### Arguments
+    /// - `packet`: The fragment, with the `hop_index` already incremented
+    /// - `fragment_index`: The index of the fragment
+    /// - `next_hop`: The neighbor the fragment is about to be sent to
+    ///
+    /// #### Returns
+    /// Whether the fragment must be forwarded
+    pub(crate) fn check_duplicate(
+        &mut self,
+        packet: &Packet,
+        fragment_index: u64,
+        next_hop: NodeId,
+    ) -> bool {
+        let Some(settings) = self.fragment_cache.settings else {
+            return true;
+        };
+        let key = (packet.session_id, fragment_index);
+        if !self
+            .fragment_cache
+            .is_duplicate(key, next_hop, Instant::now())
+        {
+            return true;
+        }
+
+        self.stats.duplicate_fragments += 1;
+        match settings.policy {
+            DuplicatePolicy::Forward => {
+                debug!(
+                    "Drone {} - Forwarding duplicate fragment {:?} to {}",
+                    self.id, key, next_hop
+                );
+                true
+            }
+            DuplicatePolicy::Suppress => {
+                info!(
+                    "Drone {} - Suppressing duplicate fragment {:?} to {}",
+                    self.id, key, next_hop
+                );
+                // delivered copies are acknowledged, the ones still in flight are answered
+                // with Nack::Dropped, so that every copy gets an answer
+                let pack_type = if self.fragment_cache.is_delivered(key) {
+                    PacketType::Ack(Ack { fragment_index })
+                } else {
+                    PacketType::Nack(Nack {
+                        fragment_index,
+                        nack_type: NackType::Dropped,
+                    })
+                };
+                // hops[hop_index - 1] is the drone: the answer goes back to the source from here
+                let hop_index = packet.routing_header.hop_index;
+                let hops = packet.routing_header.hops[..hop_index]
+                    .iter()
+                    .rev()
+                    .copied()
+                    .collect();
+                let answer = Packet {
+                    pack_type,
+                    routing_header: SourceRoutingHeader { hop_index: 1, hops },
+                    session_id: packet.session_id,
+                };
+                self.send_to_sc(DroneEvent::ControllerShortcut(answer));
+                false
+            }
+        }
+    }
+
+    /// Updates the recently forwarded fragments with an `Ack` or a `Nack` coming back through the drone
+    ///
+    /// #### Arguments
+    /// - `packet`: The received `Ack` or `Nack`
+    pub(crate) fn note_fragment_outcome(&mut self, packet: &Packet) {
+        match &packet.pack_type {
+            PacketType::Ack(ack) => self
+                .fragment_cache
+                .confirm((packet.session_id, ack.fragment_index)),
+            PacketType::Nack(nack) => self
+                .fragment_cache
+                .forget((packet.session_id, nack.fragment_index)),
+            _ => {}
+        }
+    }
+
+    /// Remembers a fragment forwarded to a neighbor
+    pub(crate) fn record_forwarded(&mut self, packet: &Packet, next_hop: NodeId) {
+        if let PacketType::MsgFragment(fragment) = &packet.pack_type {
+            self.fragment_cache.record(
+                (packet.session_id, fragment.fragment_index),
+                next_hop,
+                Instant::now(),
+            );
+        }
+    }
+}
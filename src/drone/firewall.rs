@@ -0,0 +1,262 @@
+use crate::drone::neighbors::previous_hop;
+use crate::RustBustersDrone;
+use log::{debug, info};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex, MutexGuard};
+use wg_2024::network::NodeId;
+use wg_2024::packet::{Nack, NackType, Packet, PacketType};
+
+/// What happens to the packets matched by a rule
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleAction {
+    Allow,
+    #[default]
+    Deny,
+}
+
+/// Type of a packet, without its content
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PacketKind {
+    Fragment,
+    Ack,
+    Nack,
+    FloodRequest,
+    FloodResponse,
+}
+
+impl PacketKind {
+    pub fn of(packet: &Packet) -> Self {
+        match packet.pack_type {
+            PacketType::MsgFragment(_) => PacketKind::Fragment,
+            PacketType::Ack(_) => PacketKind::Ack,
+            PacketType::Nack(_) => PacketKind::Nack,
+            PacketType::FloodRequest(_) => PacketKind::FloodRequest,
+            PacketType::FloodResponse(_) => PacketKind::FloodResponse,
+        }
+    }
+}
+
+/// How the sender of a denied fragment is told about it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DenyResponse {
+    /// The fragment is dropped without notice
+    Silent,
+    /// The fragment is dropped with `Nack::Dropped`, as if lost because of the PDR
+    #[default]
+    Dropped,
+    /// The fragment is rejected with `Nack::ErrorInRouting` of the next hop
+    ErrorInRouting,
+}
+
+/// Filter on the packets received by the drone
+///
+/// > Note: the fields left empty match every packet. The source of a flood request
+/// > is its initiator, and flood requests never match a destination
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FirewallRule {
+    pub action: RuleAction,
+    /// First node of the route
+    pub source: Option<NodeId>,
+    /// Last node of the route
+    pub destination: Option<NodeId>,
+    /// Neighbor the packet was received from
+    pub previous_hop: Option<NodeId>,
+    /// Inclusive range of session IDs
+    pub sessions: Option<(u64, u64)>,
+    /// Types of packet, any if empty
+    pub packet_types: Vec<PacketKind>,
+}
+
+impl FirewallRule {
+    /// Checks whether the rule applies to a packet
+    pub fn matches(&self, packet: &Packet) -> bool {
+        let hops = &packet.routing_header.hops;
+        let (source, destination) = match &packet.pack_type {
+            PacketType::FloodRequest(flood_request) => (Some(flood_request.initiator_id), None),
+            _ => (hops.first().copied(), hops.last().copied()),
+        };
+        self.source.is_none_or(|id| source == Some(id))
+            && self.destination.is_none_or(|id| destination == Some(id))
+            && self
+                .previous_hop
+                .is_none_or(|id| previous_hop(packet) == Some(id))
+            && self
+                .sessions
+                .is_none_or(|(min, max)| (min..=max).contains(&packet.session_id))
+            && (self.packet_types.is_empty() || self.packet_types.contains(&PacketKind::of(packet)))
+    }
+}
+
+/// Rules of the firewall, as loaded from the configuration
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FirewallConfig {
+    /// Action taken when no rule matches
+    pub default_action: Option<RuleAction>,
+    /// How the denied fragments are answered
+    pub denied_fragments: DenyResponse,
+    /// Rules checked in order, the first matching one wins
+    #[serde(rename = "rule")]
+    pub rules: Vec<FirewallRule>,
+}
+
+#[derive(Debug, Default)]
+struct FirewallState {
+    config: FirewallConfig,
+    hits: Vec<u64>,
+}
+
+/// Shared handle to the firewall of a drone
+///
+/// > Note: the handle can be cloned and kept by the controller to change the rules
+/// > while the drone is running
+#[derive(Debug, Clone, Default)]
+pub struct Firewall {
+    state: Arc<Mutex<FirewallState>>,
+}
+
+impl Firewall {
+    fn lock(&self) -> MutexGuard<'_, FirewallState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Replaces the rules, resetting the hit counters
+    pub fn set_config(&self, config: FirewallConfig) {
+        let mut state = self.lock();
+        state.hits = vec![0; config.rules.len()];
+        state.config = config;
+    }
+
+    /// Returns the current rules
+    pub fn config(&self) -> FirewallConfig {
+        self.lock().config.clone()
+    }
+
+    /// Adds a rule after the existing ones
+    pub fn add_rule(&self, rule: FirewallRule) {
+        let mut state = self.lock();
+        state.config.rules.push(rule);
+        state.hits.push(0);
+    }
+
+    /// Removes a rule and its hit counter
+    ///
+    /// #### Returns
+    /// The removed rule, or `None` if the index is out of range
+    pub fn remove_rule(&self, index: usize) -> Option<FirewallRule> {
+        let mut state = self.lock();
+        if index >= state.config.rules.len() {
+            return None;
+        }
+        state.hits.remove(index);
+        Some(state.config.rules.remove(index))
+    }
+
+    /// Returns how many packets every rule matched, in the order of the rules
+    pub fn hits(&self) -> Vec<u64> {
+        self.lock().hits.clone()
+    }
+
+    /// Checks a packet against the rules, counting the hit of the matching one
+    ///
+    /// #### Returns
+    /// `None` if the packet is allowed, the response to the denied fragments otherwise
+    pub fn check(&self, packet: &Packet) -> Option<DenyResponse> {
+        let mut state = self.lock();
+        let matching = state
+            .config
+            .rules
+            .iter()
+            .position(|rule| rule.matches(packet));
+        let action = match matching {
+            Some(index) => {
+                state.hits[index] += 1;
+                state.config.rules[index].action
+            }
+            None => state.config.default_action.unwrap_or(RuleAction::Allow),
+        };
+        match action {
+            RuleAction::Allow => None,
+            RuleAction::Deny => Some(state.config.denied_fragments),
+        }
+    }
+}
+
+impl RustBustersDrone {
+    /// Replaces the rules of the firewall
+    ///
+    /// #### Arguments
+    /// - `config`: The rules, e.g. loaded from the configuration file
+    pub fn set_firewall(&mut self, config: FirewallConfig) {
+        debug!(
+            "Drone {} - Firewall set with {} rules",
+            self.id,
+            config.rules.len()
+        );
+        self.firewall.set_config(config);
+    }
+
+    /// Returns a shared handle to the firewall, to change the rules at runtime
+    pub fn firewall(&self) -> Firewall {
+        self.firewall.clone()
+    }
+
+    /// Applies the firewall to a received packet
+    ///
+    /// #### Arguments
+    /// - `packet`: The received packet
+    ///
+    /// #### Returns
+    /// The packet if it is allowed, `None` if it was denied and has already been handled
+    ///
+    /// > Note: only the denied fragments are answered, the other packets are discarded
+    pub(crate) fn apply_firewall(&mut self, packet: Packet) -> Option<Packet> {
+        let hop_index = packet.routing_header.hop_index;
+        // Misrouted fragments are left to forward_packet
+        if matches!(packet.pack_type, PacketType::MsgFragment(_))
+            && (hop_index == 0 || packet.routing_header.hops.get(hop_index) != Some(&self.id))
+        {
+            return Some(packet);
+        }
+        let Some(response) = self.firewall.check(&packet) else {
+            return Some(packet);
+        };
+
+        info!(
+            "Drone {} - Firewall denied {:?} of session {}",
+            self.id,
+            PacketKind::of(&packet),
+            packet.session_id
+        );
+        self.stats.firewall_denied += 1;
+        let PacketType::MsgFragment(fragment) = &packet.pack_type else {
+            return None;
+        };
+        match response {
+            DenyResponse::Silent => {}
+            DenyResponse::Dropped => self.drop_fragment(packet),
+            DenyResponse::ErrorInRouting => {
+                let nack = Nack {
+                    fragment_index: fragment.fragment_index,
+                    nack_type: NackType::ErrorInRouting(
+                        packet
+                            .routing_header
+                            .hops
+                            .get(hop_index + 1)
+                            .copied()
+                            .unwrap_or(self.id),
+                    ),
+                };
+                // send_nack expects the hop_index to be already incremented
+                let mut packet = packet;
+                packet.routing_header.hop_index += 1;
+                self.send_nack(&packet, nack, true);
+            }
+        }
+        None
+    }
+}
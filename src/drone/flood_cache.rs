@@ -0,0 +1,134 @@
+use crate::drone::telemetry::TelemetryEvent;
+use crate::RustBustersDrone;
+use log::debug;
+use std::collections::{HashSet, VecDeque};
+use wg_2024::network::NodeId;
+
+/// Key of a flood: `(flood_id, initiator_id)`
+type FloodKey = (u64, NodeId);
+
+/// The floods already seen by the drone, answered with a `FloodResponse` if seen again
+#[derive(Debug, Clone, Default)]
+pub struct FloodCache {
+    /// Maximum number of floods remembered, the oldest are forgotten first
+    capacity: Option<usize>,
+    floods: HashSet<FloodKey>,
+    /// Floods in insertion order, only kept while the cache is bounded
+    order: VecDeque<FloodKey>,
+}
+
+impl PartialEq for FloodCache {
+    fn eq(&self, other: &Self) -> bool {
+        self.floods == other.floods
+    }
+}
+
+impl FloodCache {
+    /// Records a flood
+    ///
+    /// #### Returns
+    /// The floods forgotten to stay within the capacity
+    pub fn insert(&mut self, key: FloodKey) -> Vec<FloodKey> {
+        if !self.floods.insert(key) {
+            return Vec::new();
+        }
+        if self.capacity.is_some() {
+            self.order.push_back(key);
+        }
+        self.shrink()
+    }
+
+    pub fn contains(&self, key: &FloodKey) -> bool {
+        self.floods.contains(key)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &FloodKey> {
+        self.floods.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.floods.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.floods.is_empty()
+    }
+
+    /// Sets the capacity of the cache
+    ///
+    /// #### Returns
+    /// The floods forgotten to stay within the new capacity
+    ///
+    /// > Note: the floods already remembered are ordered by ID when the cache becomes bounded
+    pub fn set_capacity(&mut self, capacity: Option<usize>) -> Vec<FloodKey> {
+        if self.capacity.is_none() && capacity.is_some() {
+            let mut order: Vec<FloodKey> = self.floods.iter().copied().collect();
+            order.sort_unstable();
+            self.order = order.into();
+        } else if capacity.is_none() {
+            self.order.clear();
+        }
+        self.capacity = capacity;
+        self.shrink()
+    }
+
+    fn shrink(&mut self) -> Vec<FloodKey> {
+        let Some(capacity) = self.capacity else {
+            return Vec::new();
+        };
+        let mut evicted = Vec::new();
+        while self.floods.len() > capacity {
+            let Some(oldest) = self.order.pop_front() else {
+                break;
+            };
+            self.floods.remove(&oldest);
+            evicted.push(oldest);
+        }
+        evicted
+    }
+}
+
+impl FromIterator<FloodKey> for FloodCache {
+    fn from_iter<I: IntoIterator<Item = FloodKey>>(iter: I) -> Self {
+        Self {
+            floods: iter.into_iter().collect(),
+            ..Self::default()
+        }
+    }
+}
+
+impl RustBustersDrone {
+    /// Sets how many floods the drone remembers
+    ///
+    /// #### Arguments
+    /// - `capacity`: The maximum number of floods, or `None` to remember all of them
+    ///
+    /// > Note: a forgotten flood is spread again if it comes back
+    pub fn set_flood_cache_capacity(&mut self, capacity: Option<usize>) {
+        let evicted = self.received_floods.set_capacity(capacity);
+        debug!(
+            "Drone {} - Flood cache capacity set to {:?}",
+            self.id, capacity
+        );
+        self.note_flood_evictions(evicted);
+    }
+
+    /// Records a flood as seen, forgetting the oldest ones if the cache is full
+    pub(crate) fn remember_flood(&mut self, flood_id: u64, initiator_id: NodeId) {
+        let evicted = self.received_floods.insert((flood_id, initiator_id));
+        self.note_flood_evictions(evicted);
+    }
+
+    fn note_flood_evictions(&mut self, evicted: Vec<FloodKey>) {
+        for (flood_id, initiator_id) in evicted {
+            debug!(
+                "Drone {} - Forgot FloodRequest(flood_id={}, initiator_id={})",
+                self.id, flood_id, initiator_id
+            );
+            self.emit(TelemetryEvent::FloodCacheEvicted {
+                flood_id,
+                initiator_id,
+            });
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use crate::RustBustersDrone;
+use log::debug;
+use std::collections::HashMap;
+use wg_2024::network::NodeId;
+use wg_2024::packet::FloodRequest;
+
+/// Limits how far the flood requests handled by the drone travel
+#[derive(Debug, Clone, Default)]
+pub struct FloodScope {
+    max_path_len: Option<usize>,
+    per_initiator: HashMap<NodeId, usize>,
+}
+
+impl FloodScope {
+    /// Returns the maximum length of every initiator and the ones of single initiators, sorted by initiator
+    pub(crate) fn limits(&self) -> (Option<usize>, Vec<(NodeId, usize)>) {
+        let mut per_initiator: Vec<(NodeId, usize)> = self
+            .per_initiator
+            .iter()
+            .map(|(&initiator_id, &max)| (initiator_id, max))
+            .collect();
+        per_initiator.sort_unstable();
+        (self.max_path_len, per_initiator)
+    }
+
+    /// Returns the maximum `path_trace` length allowed for the floods of an initiator
+    pub fn max_path_len(&self, initiator_id: NodeId) -> Option<usize> {
+        self.per_initiator
+            .get(&initiator_id)
+            .copied()
+            .or(self.max_path_len)
+    }
+
+    /// Checks whether a flood request, with the drone already added to its `path_trace`,
+    /// has reached the end of its scope
+    pub fn is_exhausted(&self, flood_request: &FloodRequest) -> bool {
+        self.max_path_len(flood_request.initiator_id)
+            .is_some_and(|max| flood_request.path_trace.len() >= max)
+    }
+}
+
+impl RustBustersDrone {
+    /// Sets the maximum length of the `path_trace` of the spread flood requests
+    ///
+    /// #### Arguments
+    /// - `max_path_len`: The maximum length, including the initiator, or `None` to flood the whole network
+    ///
+    /// > Note: a drone that brings the `path_trace` to the maximum length answers with a
+    /// > `FloodResponse` instead of spreading the request, e.g. with 3 the requests of a client
+    /// > reach the drones at most 2 hops away
+    pub fn set_max_flood_path_len(&mut self, max_path_len: Option<usize>) {
+        self.flood_scope.max_path_len = max_path_len;
+        debug!(
+            "Drone {} - Max flood path length set to {:?}",
+            self.id, max_path_len
+        );
+    }
+
+    /// Sets the maximum length of the `path_trace` of the flood requests of a single initiator
+    ///
+    /// #### Arguments
+    /// - `initiator_id`: The ID of the initiator
+    /// - `max_path_len`: The maximum length, or `None` to go back to the one of every initiator
+    pub fn set_max_flood_path_len_for(
+        &mut self,
+        initiator_id: NodeId,
+        max_path_len: Option<usize>,
+    ) {
+        match max_path_len {
+            Some(max) => self.flood_scope.per_initiator.insert(initiator_id, max),
+            None => self.flood_scope.per_initiator.remove(&initiator_id),
+        };
+        debug!(
+            "Drone {} - Max flood path length of initiator {} set to {:?}",
+            self.id, initiator_id, max_path_len
+        );
+    }
+}
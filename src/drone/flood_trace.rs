@@ -0,0 +1,149 @@
+use crate::drone::report::ReportKind;
+use crate::drone::telemetry::TelemetryEvent;
+use crate::RustBustersDrone;
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+use wg_2024::network::NodeId;
+use wg_2024::packet::{FloodRequest, Packet};
+
+/// Number of quarantined flood requests kept before the oldest are discarded
+pub const MAX_QUARANTINED: usize = 64;
+
+/// What the drone does with the flood requests whose `path_trace` is inconsistent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TracePolicy {
+    /// The `path_trace` is not checked
+    #[default]
+    Trust,
+    /// The inconsistent requests are reported and discarded
+    Drop,
+    /// The inconsistent requests are reported and kept aside, see `RustBustersDrone::take_quarantined_floods`
+    Quarantine,
+}
+
+/// Inconsistency found in the `path_trace` of a flood request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum TraceViolation {
+    /// The last node of the trace, or the initiator if the trace is empty, is not a neighbor
+    UnknownSender = 0,
+    /// A node appears more than once in the trace
+    RepeatedNode = 1,
+    /// The first node of the trace is not the initiator
+    InitiatorMismatch = 2,
+}
+
+/// Checks the `path_trace` of a flood request, before the drone adds itself to it
+///
+/// #### Arguments
+/// - `flood_request`: The received flood request
+/// - `is_neighbor`: Tells whether a node is a neighbor of the drone
+///
+/// #### Returns
+/// The first inconsistency found, if any
+pub fn check_trace(
+    flood_request: &FloodRequest,
+    is_neighbor: impl Fn(NodeId) -> bool,
+) -> Option<TraceViolation> {
+    let path_trace = &flood_request.path_trace;
+    if let Some(&(first_id, _)) = path_trace.first() {
+        if first_id != flood_request.initiator_id {
+            return Some(TraceViolation::InitiatorMismatch);
+        }
+    }
+    let mut seen = HashSet::with_capacity(path_trace.len());
+    if !path_trace.iter().all(|&(id, _)| seen.insert(id)) {
+        return Some(TraceViolation::RepeatedNode);
+    }
+    let sender_id = path_trace
+        .last()
+        .map_or(flood_request.initiator_id, |&(id, _)| id);
+    if !is_neighbor(sender_id) {
+        return Some(TraceViolation::UnknownSender);
+    }
+    None
+}
+
+/// Checks of the flood requests and the ones put in quarantine
+#[derive(Debug, Clone, Default)]
+pub struct TraceGuard {
+    policy: TracePolicy,
+    quarantine: VecDeque<(TraceViolation, Packet)>,
+}
+
+impl TraceGuard {
+    pub fn policy(&self) -> TracePolicy {
+        self.policy
+    }
+}
+
+impl RustBustersDrone {
+    /// Sets what the drone does with the flood requests whose `path_trace` is inconsistent
+    ///
+    /// #### Arguments
+    /// - `policy`: The policy, `TracePolicy::Trust` to skip the checks
+    pub fn set_flood_trace_policy(&mut self, policy: TracePolicy) {
+        self.trace_guard.policy = policy;
+        debug!("Drone {} - Flood trace policy set to {:?}", self.id, policy);
+    }
+
+    /// Takes the flood requests put in quarantine, the oldest first
+    pub fn take_quarantined_floods(&mut self) -> Vec<(TraceViolation, Packet)> {
+        self.trace_guard.quarantine.drain(..).collect()
+    }
+
+    /// Checks the `path_trace` of a received flood request according to the policy
+    ///
+    /// #### Arguments
+    /// - `packet`: The packet of the flood request
+    /// - `flood_request`: The flood request
+    ///
+    /// #### Returns
+    /// Whether the request can be handled
+    ///
+    /// > Note: the inconsistent requests are reported with a `TelemetryEvent::TamperedFlood` event
+    /// > and a `ReportKind::TamperedFlood` report: `[TraceViolation, initiator_id, sender_id, flood_id as u64 LE]`
+    pub(crate) fn check_flood_trace(
+        &mut self,
+        packet: &Packet,
+        flood_request: &FloodRequest,
+    ) -> bool {
+        if self.trace_guard.policy == TracePolicy::Trust {
+            return true;
+        }
+        let Some(violation) = check_trace(flood_request, |id| self.packet_send.contains_key(&id))
+        else {
+            return true;
+        };
+
+        let sender_id = flood_request
+            .path_trace
+            .last()
+            .map_or(flood_request.initiator_id, |&(id, _)| id);
+        warn!(
+            "Drone {} - Tampered FloodRequest(flood_id={}, initiator_id={}) from {}: {:?}",
+            self.id, flood_request.flood_id, flood_request.initiator_id, sender_id, violation
+        );
+        self.stats.tampered_floods += 1;
+        let mut payload = vec![violation as u8, flood_request.initiator_id, sender_id];
+        payload.extend(flood_request.flood_id.to_le_bytes());
+        self.send_report(ReportKind::TamperedFlood, &payload);
+        self.emit(TelemetryEvent::TamperedFlood {
+            violation,
+            flood_id: flood_request.flood_id,
+            initiator_id: flood_request.initiator_id,
+            sender_id,
+        });
+
+        if self.trace_guard.policy == TracePolicy::Quarantine {
+            if self.trace_guard.quarantine.len() >= MAX_QUARANTINED {
+                self.trace_guard.quarantine.pop_front();
+            }
+            self.trace_guard
+                .quarantine
+                .push_back((violation, packet.clone()));
+        }
+        false
+    }
+}
@@ -1,5 +1,9 @@
 use super::RustBustersDrone;
-use log::{error, info, trace, warn};
+use crate::drone::middleware::{Hook, Verdict};
+#[cfg(feature = "sounds")]
+use crate::drone::sounds::sounds_feat::SoundEvent;
+use crate::drone::telemetry::TelemetryEvent;
+use log::{debug, error, info, trace, warn};
 use rand::Rng;
 use wg_2024::controller::DroneEvent;
 use wg_2024::network::NodeId;
@@ -24,15 +28,8 @@ impl RustBustersDrone {
 
         // Step "2.1": Check if the drone is running, if not send ErrorInRouting
         if !self.running {
-            if let PacketType::MsgFragment(ref frg) = packet.pack_type {
-                self.send_nack(
-                    &packet,
-                    Nack {
-                        fragment_index: frg.fragment_index,
-                        nack_type: NackType::ErrorInRouting(self.id),
-                    },
-                    allow_optimized,
-                );
+            if let PacketType::MsgFragment(_) = packet.pack_type {
+                self.handle_in_flight_fragment(&packet, allow_optimized);
                 return;
             }
         }
@@ -60,18 +57,62 @@ impl RustBustersDrone {
                 self.handle_fragment(&packet, fragment, next_hop, allow_optimized);
             }
             PacketType::Nack(nack) => {
-                if self.hunt_mode && nack.nack_type == NackType::Dropped {
+                self.note_fragment_outcome(&packet);
+                // A drop counts against the link only if the neighbor the fragment was sent to dropped it
+                let dropper = packet.routing_header.hops[0];
+                let sender = packet
+                    .routing_header
+                    .hop_index
+                    .checked_sub(2)
+                    .map(|index| packet.routing_header.hops[index]);
+                if nack.nack_type == NackType::Dropped && sender == Some(dropper) {
+                    self.packet_send.record_dropped(dropper);
+                }
+                let blamed = self.observe_nack(&packet, nack);
+                // A fragment sent again is not lost: no hunt, and the Nack stops here
+                if self.retransmit_dropped(&packet, nack) {
+                    return;
+                }
+                let reached_threshold = match (self.hunt_threshold, blamed) {
+                    (None, _) => true,
+                    (Some(threshold), Some((_, score))) => score >= threshold,
+                    (Some(_), None) => false,
+                };
+                if self.hunt_mode && nack.nack_type == NackType::Dropped && reached_threshold {
                     info!(
                         "Drone {} - Received Nack::Dropped. Packet: {:?}",
                         self.id, packet
                     );
-                    if let Err(e) = self.hunt_ghost(packet.routing_header.hops[0]) {
-                        warn!("Drone {} - Error in hunting ghost drone: {}", self.id, e);
+                    let target = packet.routing_header.hops[0];
+                    match self.hunt_ghost(target) {
+                        Ok(()) => self.emit(TelemetryEvent::HuntFired {
+                            target,
+                            score: blamed.map(|(_, score)| score),
+                        }),
+                        Err(e) => {
+                            warn!("Drone {} - Error in hunting ghost drone: {}", self.id, e);
+                        }
                     }
+                } else if self.hunt_mode && nack.nack_type == NackType::Dropped {
+                    debug!(
+                        "Drone {} - Hunt of {} suppressed: below the reputation threshold",
+                        self.id, packet.routing_header.hops[0]
+                    );
+                    self.emit(TelemetryEvent::HuntSuppressed {
+                        target: packet.routing_header.hops[0],
+                        score: blamed.map(|(_, score)| score),
+                    });
+                    #[cfg(feature = "sounds")]
+                    self.play_sound(SoundEvent::HuntSuppressed);
                 }
                 self.forward_other_packet(&mut packet);
             }
-            PacketType::Ack(_) | PacketType::FloodResponse(_) => {
+            PacketType::FloodResponse(flood_response) => {
+                self.learn_topology(&flood_response.path_trace);
+                self.forward_other_packet(&mut packet);
+            }
+            PacketType::Ack(_) => {
+                self.note_fragment_outcome(&packet);
                 self.forward_other_packet(&mut packet);
             }
             PacketType::FloodRequest(_) => {
@@ -203,11 +244,16 @@ impl RustBustersDrone {
         next_hop: NodeId,
         allow_optimized: bool,
     ) {
+        if !self.check_duplicate(packet, fragment.fragment_index, next_hop) {
+            return;
+        }
+        let mut packet = packet.clone();
+
         // Check for packet drop
         let should_drop = {
             let mut rng = rand::thread_rng();
-            rng.gen_range(1..=100) <= self.pdr
-        };
+            rng.gen_range(1..=100) <= self.current_pdr()
+        } && self.run_middlewares(Hook::Drop, &mut packet) == Verdict::Continue;
 
         if should_drop {
             info!(
@@ -215,7 +261,7 @@ impl RustBustersDrone {
                 self.id, fragment.fragment_index
             );
             self.send_nack(
-                packet,
+                &packet,
                 Nack {
                     fragment_index: fragment.fragment_index,
                     nack_type: NackType::Dropped,
@@ -223,6 +269,7 @@ impl RustBustersDrone {
                 allow_optimized,
             );
 
+            self.stats.fragments_dropped += 1;
             let mut dropped_packet = packet.clone();
             dropped_packet.routing_header.hop_index -= 1;
             // Send PacketDropped event to the controller
@@ -239,10 +286,12 @@ impl RustBustersDrone {
         }
 
         // Forward the packet to next_hop
-        if let Some(next_sender) = self.packet_send.get(&next_hop) {
-            if let Err(err) = next_sender.send(packet.clone()) {
-                self.packet_send.remove(&next_hop);
-
+        if self.run_middlewares(Hook::BeforeForward(next_hop), &mut packet) == Verdict::Veto {
+            return;
+        }
+        let packet = &packet;
+        if self.packet_send.contains_key(&next_hop) {
+            if let Err(err) = self.send_to_neighbor(next_hop, packet.clone()) {
                 self.send_nack(
                     packet,
                     Nack {
@@ -255,12 +304,10 @@ impl RustBustersDrone {
                     "Drone {} - Error in sending packet to {}: {}",
                     self.id, next_hop, err
                 );
-                warn!(
-                    "Drone {} - Removed neighbor with ID {} from packet_send due to channel closure",
-                    self.id,
-                    next_hop
-                );
             } else {
+                self.stats.fragments_forwarded += 1;
+                self.record_forwarded(packet, next_hop);
+                self.buffer_forwarded(packet, next_hop);
                 // Send PacketSent event to the controller
                 if let Err(e) = self
                     .controller_send
@@ -311,22 +358,19 @@ impl RustBustersDrone {
             }
         }
         let next_hop = packet.routing_header.hops[packet.routing_header.hop_index];
+        if self.run_middlewares(Hook::BeforeForward(next_hop), packet) == Verdict::Veto {
+            return;
+        }
 
         // Forward these packets without dropping
-        if let Some(next_sender) = self.packet_send.get(&next_hop) {
-            if let Err(e) = next_sender.send(packet.clone()) {
-                self.packet_send.remove(&next_hop);
+        if self.packet_send.contains_key(&next_hop) {
+            if let Err(e) = self.send_to_neighbor(next_hop, packet.clone()) {
                 error!(
                     "Drone {} - Error in sending Packet to {}: {}",
                     self.id, next_hop, e
                 );
-                warn!(
-                    "Drone {} - Removed neighbor with ID {} from packet_send due to channel closure",
-                    self.id,
-                    next_hop
-                );
 
-                // Take the shortcut to the controller if the neighbor is removed
+                // Take the shortcut to the controller if the neighbor is unreachable
                 self.send_to_sc(DroneEvent::ControllerShortcut(packet.clone()));
             } else {
                 info!(
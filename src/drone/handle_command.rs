@@ -1,9 +1,5 @@
 use super::RustBustersDrone;
-#[cfg(feature = "sounds")]
-use crate::drone::sounds::sounds_feat::CRASH_SOUND;
 use log::info;
-#[cfg(feature = "sounds")]
-use std::{thread::sleep, time::Duration};
 use wg_2024::controller::DroneCommand;
 
 impl RustBustersDrone {
@@ -13,7 +9,7 @@ impl RustBustersDrone {
     /// - `command`: The command to handle
     ///
     /// > Note:
-    /// > - The `Crash` command will shut down the drone
+    /// > - The `Crash` command will shut down the drone, following the crash policy
     /// > - The `AddSender` command will add a sender for the given node ID
     /// > - The `SetPacketDropRate` command will set the Packet Drop Rate to the given value
     /// > - The `RemoveSender` command will remove the sender for the given node ID
@@ -22,15 +18,10 @@ impl RustBustersDrone {
         match command {
             DroneCommand::Crash => {
                 info!("Drone {} - Received Crash command. Shutting down.", self.id);
-                #[cfg(feature = "sounds")]
-                {
-                    self.play_sound(CRASH_SOUND);
-                    sleep(Duration::from_millis(1500));
-                }
-                self.running = false;
+                self.begin_crash();
             }
             DroneCommand::AddSender(node_id, sender) => {
-                self.packet_send.insert(node_id, sender);
+                self.packet_send.insert(node_id, Box::new(sender));
                 info!("Drone {} - Added sender for node_id {}", self.id, node_id);
             }
             DroneCommand::SetPacketDropRate(new_pdr) => {
@@ -1,5 +1,7 @@
 use super::RustBustersDrone;
-use crossbeam_channel::Sender;
+use crate::drone::middleware::{Hook, Verdict};
+#[cfg(feature = "sounds")]
+use crate::drone::sounds::sounds_feat::SoundEvent;
 use log::{debug, error, info, warn};
 use wg_2024::controller::DroneEvent;
 use wg_2024::network::{NodeId, SourceRoutingHeader};
@@ -10,11 +12,19 @@ impl RustBustersDrone {
     ///
     /// #### Arguments
     /// - `packet`: The `FloodRequest` packet to be handled
-    pub(crate) fn handle_flood_request(&mut self, packet: Packet) {
+    pub(crate) fn handle_flood_request(&mut self, mut packet: Packet) {
         // If the drone has crashed, the request can be dropped
         if !self.running {
             return;
         }
+        if self.run_middlewares(Hook::Flood, &mut packet) == Verdict::Veto {
+            return;
+        }
+        if let PacketType::FloodRequest(flood_request) = &packet.pack_type {
+            if !self.check_flood_trace(&packet, flood_request) {
+                return;
+            }
+        }
 
         debug!("Drone {} - Received FloodRequest", self.id);
         if let PacketType::FloodRequest(mut flood_request) = packet.pack_type {
@@ -27,12 +37,26 @@ impl RustBustersDrone {
 
             // Add self to path_trace
             flood_request.path_trace.push((self.id, NodeType::Drone));
+            self.learn_topology(&flood_request.path_trace);
 
             if self
                 .received_floods
                 .contains(&(flood_request.flood_id, flood_request.initiator_id))
             {
                 self.send_flood_response(&flood_request, packet.session_id, sender_id);
+            } else if self.rate_limit_flood(&flood_request, packet.session_id, sender_id) {
+                debug!(
+                    "Drone {} - FloodRequest(flood_id={}, initiator_id={}) exceeded a rate limit",
+                    self.id, flood_request.flood_id, flood_request.initiator_id
+                );
+            } else if self.flood_scope.is_exhausted(&flood_request) {
+                debug!(
+                    "Drone {} - FloodRequest(flood_id={}, initiator_id={}) reached the end of its scope",
+                    self.id, flood_request.flood_id, flood_request.initiator_id
+                );
+                self.stats.scoped_floods += 1;
+                self.remember_flood(flood_request.flood_id, flood_request.initiator_id);
+                self.send_flood_response(&flood_request, packet.session_id, sender_id);
             } else {
                 self.spread_flood_request(&flood_request, packet.session_id, sender_id);
             }
@@ -90,18 +114,12 @@ impl RustBustersDrone {
             },
             session_id,
         };
-        if let Some(sender) = self.packet_send.get(&sender_id) {
-            if let Err(e) = sender.send(response_packet.clone()) {
-                self.packet_send.remove(&sender_id);
+        if self.packet_send.contains_key(&sender_id) {
+            if let Err(e) = self.send_to_neighbor(sender_id, response_packet.clone()) {
                 error!(
                     "Drone {} - Error in sending FloodResponse(flood_id={}, sender_id={}): {}",
                     self.id, flood_request.flood_id, sender_id, e
                 );
-                warn!(
-                    "Drone {} - Removed neighbor with ID {} from packet_send due to channel closure",
-                    self.id,
-                    sender_id
-                );
 
                 self.send_to_sc(DroneEvent::ControllerShortcut(response_packet.clone()));
             } else {
@@ -139,14 +157,13 @@ impl RustBustersDrone {
             "Drone {} - FloodRequest(flood_id={}, sender_id={}) is being processed",
             self.id, flood_request.flood_id, sender_id
         );
-        self.received_floods
-            .insert((flood_request.flood_id, flood_request.initiator_id));
-        // Collect neighbor IDs and Senders into a separate vector (excluding the sender)
-        let neighbors: Vec<(NodeId, Sender<Packet>)> = self
+        self.remember_flood(flood_request.flood_id, flood_request.initiator_id);
+        // Collect neighbor IDs into a separate vector (excluding the sender)
+        let neighbors: Vec<NodeId> = self
             .packet_send
-            .iter()
-            .filter(|(&neighbor_id, _)| neighbor_id != sender_id)
-            .map(|(&neighbor_id, sender)| (neighbor_id, sender.clone()))
+            .ids()
+            .into_iter()
+            .filter(|&neighbor_id| neighbor_id != sender_id)
             .collect();
 
         if neighbors.is_empty() {
@@ -159,9 +176,12 @@ impl RustBustersDrone {
             return;
         }
 
+        #[cfg(feature = "sounds")]
+        self.play_sound(SoundEvent::FloodSpread);
+
         // Forward FloodRequest to neighbors except the sender
-        for (neighbor_id, neighbor_sender) in neighbors {
-            let packet = Packet {
+        for neighbor_id in neighbors {
+            let mut packet = Packet {
                 pack_type: PacketType::FloodRequest(flood_request.clone()),
                 routing_header: SourceRoutingHeader {
                     hop_index: 1,
@@ -169,18 +189,15 @@ impl RustBustersDrone {
                 },
                 session_id,
             };
-            if let Err(e) = neighbor_sender.send(packet.clone()) {
-                // Remove the neighbor from packet_send
-                self.packet_send.remove(&neighbor_id);
+            if self.run_middlewares(Hook::BeforeForward(neighbor_id), &mut packet) == Verdict::Veto
+            {
+                continue;
+            }
+            if let Err(e) = self.send_to_neighbor(neighbor_id, packet.clone()) {
                 error!(
                     "Drone {} - Error in sending FloodRequest(flood_id={}, sender_id={}) to {}: {}",
                     self.id, flood_request.flood_id, sender_id, neighbor_id, e
                 );
-                warn!(
-                    "Drone {} - Removed neighbor with ID {} from packet_send due to channel closure",
-                    self.id,
-                    neighbor_id
-                );
             } else {
                 info!(
                     "Drone {} - Forwarded FloodRequest(flood_id={}, sender_id={}) to neighbor: {}",
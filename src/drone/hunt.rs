@@ -1,5 +1,5 @@
 #[cfg(feature = "sounds")]
-use crate::drone::sounds::sounds_feat::HUNT_SOUND;
+use crate::drone::sounds::sounds_feat::SoundEvent;
 use crate::RustBustersDrone;
 use wg_2024::controller::DroneEvent;
 use wg_2024::network::{NodeId, SourceRoutingHeader};
@@ -47,7 +47,7 @@ impl RustBustersDrone {
         // Step 3: send the packet to the SC
         if self.controller_send.send(kill_node_event).is_ok() {
             #[cfg(feature = "sounds")]
-            self.play_sound(HUNT_SOUND);
+            self.play_sound(SoundEvent::Hunt);
             Ok(())
         } else {
             Err("Error in sending Hunt Packet".to_string())
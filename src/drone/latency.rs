@@ -0,0 +1,214 @@
+use crate::drone::report::ReportKind;
+use crate::drone::scheduler::TrafficClass;
+use crate::drone::telemetry::TelemetryEvent;
+use crate::RustBustersDrone;
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use wg_2024::packet::Packet;
+
+/// Bits of the sub-buckets of a histogram: every power of two is split in 16 linear buckets,
+/// so a recorded value is off by at most 1/16
+const SUB_BUCKET_BITS: u32 = 4;
+const SUB_BUCKETS: u64 = 1 << SUB_BUCKET_BITS;
+
+const CLASSES: [TrafficClass; 3] = [
+    TrafficClass::Control,
+    TrafficClass::Flood,
+    TrafficClass::Data,
+];
+
+/// Histogram of durations with fixed log-linear buckets, in the style of HDR histograms
+///
+/// > Note: the values are recorded in microseconds, and a percentile is the highest value of its bucket
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LatencyHistogram {
+    /// Number of values per bucket, grown up to the bucket of the largest value
+    counts: Vec<u64>,
+    count: u64,
+    /// Sum of the values, in microseconds
+    sum: u64,
+    /// Largest value, in microseconds
+    max: u64,
+}
+
+impl LatencyHistogram {
+    /// Records a duration
+    pub fn record(&mut self, latency: Duration) {
+        let micros = u64::try_from(latency.as_micros()).unwrap_or(u64::MAX);
+        let index = bucket_index(micros);
+        if self.counts.len() <= index {
+            self.counts.resize(index + 1, 0);
+        }
+        self.counts[index] += 1;
+        self.count += 1;
+        self.sum = self.sum.saturating_add(micros);
+        self.max = self.max.max(micros);
+    }
+
+    /// Returns the number of recorded durations
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Returns the largest recorded duration
+    pub fn max(&self) -> Duration {
+        Duration::from_micros(self.max)
+    }
+
+    /// Returns the mean of the recorded durations
+    pub fn mean(&self) -> Duration {
+        if self.count == 0 {
+            return Duration::ZERO;
+        }
+        Duration::from_micros(self.sum / self.count)
+    }
+
+    /// Returns the duration under which a fraction of the recorded durations fall
+    ///
+    /// #### Arguments
+    /// - `quantile`: The fraction, between 0 and 1 (e.g. 0.99 for the 99th percentile)
+    pub fn percentile(&self, quantile: f64) -> Duration {
+        if self.count == 0 {
+            return Duration::ZERO;
+        }
+        let rank = ((quantile.clamp(0.0, 1.0) * self.count as f64).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (index, &count) in self.counts.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return Duration::from_micros(bucket_upper_bound(index).min(self.max));
+            }
+        }
+        self.max()
+    }
+
+    /// Returns the median, the 99th percentile and the maximum
+    pub fn tail(&self) -> (Duration, Duration, Duration) {
+        (self.percentile(0.5), self.percentile(0.99), self.max())
+    }
+}
+
+fn bucket_index(micros: u64) -> usize {
+    if micros < SUB_BUCKETS {
+        return micros as usize;
+    }
+    let shift = micros.ilog2() - SUB_BUCKET_BITS;
+    let sub_bucket = (micros >> shift) - SUB_BUCKETS;
+    ((u64::from(shift) + 1) * SUB_BUCKETS + sub_bucket) as usize
+}
+
+fn bucket_upper_bound(index: usize) -> u64 {
+    let index = index as u64;
+    if index < SUB_BUCKETS {
+        return index;
+    }
+    let shift = index / SUB_BUCKETS - 1;
+    let lower = (SUB_BUCKETS + index % SUB_BUCKETS) << shift;
+    lower.saturating_add((1 << shift) - 1)
+}
+
+/// Time from receiving a packet to sending it, or what it caused, to a neighbor, per class of the received packet
+///
+/// > Note: the time spent in the scheduler queues is included
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LatencyStats {
+    pub control: LatencyHistogram,
+    pub flood: LatencyHistogram,
+    pub data: LatencyHistogram,
+}
+
+impl LatencyStats {
+    /// Returns the histogram of a class
+    pub fn class(&self, class: TrafficClass) -> &LatencyHistogram {
+        match class {
+            TrafficClass::Control => &self.control,
+            TrafficClass::Flood => &self.flood,
+            TrafficClass::Data => &self.data,
+        }
+    }
+
+    fn class_mut(&mut self, class: TrafficClass) -> &mut LatencyHistogram {
+        match class {
+            TrafficClass::Control => &mut self.control,
+            TrafficClass::Flood => &mut self.flood,
+            TrafficClass::Data => &mut self.data,
+        }
+    }
+
+    /// Encodes the tail latencies as the payload of a `ReportKind::Latency` report
+    ///
+    /// #### Returns
+    /// For each `TrafficClass` in order, `[class, p50, p99, max]`,
+    /// with the durations in microseconds as u32 little endian
+    pub fn encode_tail(&self) -> Vec<u8> {
+        let mut payload = Vec::new();
+        for class in CLASSES {
+            let (p50, p99, max) = self.class(class).tail();
+            payload.push(class as u8);
+            for latency in [p50, p99, max] {
+                let micros = u32::try_from(latency.as_micros()).unwrap_or(u32::MAX);
+                payload.extend(micros.to_le_bytes());
+            }
+        }
+        payload
+    }
+}
+
+impl RustBustersDrone {
+    /// Records the latency of a packet sent to a neighbor,
+    /// if it is sent while handling a packet taken from the scheduler queues
+    pub(crate) fn record_latency(&mut self) {
+        if let Some((class, received)) = self.handling {
+            self.stats
+                .latency
+                .class_mut(class)
+                .record(received.elapsed());
+        }
+    }
+
+    /// Logs the tail latencies and sends the histograms on the telemetry stream, on shutdown
+    ///
+    /// > Note: the tail latencies are also sent to the controller if the controller reports are enabled
+    pub(crate) fn report_latency(&mut self) {
+        let latency = &self.stats.latency;
+        for class in CLASSES {
+            let histogram = latency.class(class);
+            if histogram.is_empty() {
+                continue;
+            }
+            let (p50, p99, max) = histogram.tail();
+            info!(
+                "Drone {} - {:?} latency over {} sends: p50 {:?}, p99 {:?}, max {:?}",
+                self.id,
+                class,
+                histogram.count(),
+                p50,
+                p99,
+                max
+            );
+        }
+        let payload = latency.encode_tail();
+        self.send_report(ReportKind::Latency, &payload);
+        self.emit(TelemetryEvent::Latency(Box::new(
+            self.stats.latency.clone(),
+        )));
+    }
+
+    /// Handles a packet taken from the scheduler queues, measuring the latency of what it sends
+    ///
+    /// #### Arguments
+    /// - `packet`: The packet to handle
+    /// - `received`: When the packet was queued
+    pub(crate) fn dispatch_timed(&mut self, packet: Packet, received: Instant) {
+        self.handling = Some((TrafficClass::of(&packet), received));
+        self.dispatch_packet(packet);
+        self.handling = None;
+    }
+}
@@ -0,0 +1,121 @@
+use crate::RustBustersDrone;
+use log::debug;
+use wg_2024::network::NodeId;
+use wg_2024::packet::Packet;
+
+/// Outcome of a middleware hook
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    /// The packet goes on to the next middleware, then to the drone
+    Continue,
+    /// The action the hook was called for does not happen, and the next middlewares are skipped
+    Veto,
+}
+
+/// Hooks called by the drone while it handles packets, to add behaviors without changing the drone
+///
+/// Every hook receives the ID of the drone and the packet, that can be inspected or modified in place.
+/// The default implementations let the packet through unchanged.
+///
+/// > Note: the drone has no builder: the middlewares are added at runtime with `RustBustersDrone::add_middleware`,
+/// > usually right after the drone is created, and run in the order they were added
+pub trait PacketMiddleware: Send {
+    /// Called when a packet is received from the network, before it is queued
+    ///
+    /// A veto discards the packet.
+    fn on_receive(&mut self, _drone_id: NodeId, _packet: &mut Packet) -> Verdict {
+        Verdict::Continue
+    }
+
+    /// Called before a packet is sent to the next hop, including the spread flood requests
+    ///
+    /// A veto discards the packet. The next hop was chosen before the hook and cannot be changed.
+    fn before_forward(
+        &mut self,
+        _drone_id: NodeId,
+        _packet: &mut Packet,
+        _next_hop: NodeId,
+    ) -> Verdict {
+        Verdict::Continue
+    }
+
+    /// Called when a fragment is about to be dropped because of the Packet Drop Rate
+    ///
+    /// A veto forwards the fragment anyway.
+    fn on_drop(&mut self, _drone_id: NodeId, _packet: &mut Packet) -> Verdict {
+        Verdict::Continue
+    }
+
+    /// Called before the drone sends a Nack it created
+    ///
+    /// A veto suppresses the Nack. A Nack whose routing header is left without a next hop is dropped.
+    fn on_nack(&mut self, _drone_id: NodeId, _packet: &mut Packet) -> Verdict {
+        Verdict::Continue
+    }
+
+    /// Called when a `FloodRequest` is handled, before the drone adds itself to the `path_trace`
+    ///
+    /// A veto ignores the request, without answering it.
+    fn on_flood(&mut self, _drone_id: NodeId, _packet: &mut Packet) -> Verdict {
+        Verdict::Continue
+    }
+}
+
+/// Point of the packet handling a middleware hook is called at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Hook {
+    Receive,
+    BeforeForward(NodeId),
+    Drop,
+    Nack,
+    Flood,
+}
+
+impl RustBustersDrone {
+    /// Adds a middleware at the end of the pipeline
+    ///
+    /// #### Arguments
+    /// - `middleware`: The middleware to add
+    pub fn add_middleware(&mut self, middleware: impl PacketMiddleware + 'static) {
+        self.middlewares.push(Box::new(middleware));
+        debug!(
+            "Drone {} - Middleware added, {} in the pipeline",
+            self.id,
+            self.middlewares.len()
+        );
+    }
+
+    /// Removes every middleware
+    pub fn clear_middlewares(&mut self) {
+        self.middlewares.clear();
+        debug!("Drone {} - Middlewares removed", self.id);
+    }
+
+    /// Runs the given hook of every middleware, in order, until one of them vetoes
+    ///
+    /// #### Arguments
+    /// - `hook`: The hook to run
+    /// - `packet`: The packet given to the middlewares
+    ///
+    /// #### Returns
+    /// `Verdict::Veto` if a middleware vetoed, `Verdict::Continue` otherwise
+    pub(crate) fn run_middlewares(&mut self, hook: Hook, packet: &mut Packet) -> Verdict {
+        for middleware in &mut self.middlewares {
+            let verdict = match hook {
+                Hook::Receive => middleware.on_receive(self.id, packet),
+                Hook::BeforeForward(next_hop) => {
+                    middleware.before_forward(self.id, packet, next_hop)
+                }
+                Hook::Drop => middleware.on_drop(self.id, packet),
+                Hook::Nack => middleware.on_nack(self.id, packet),
+                Hook::Flood => middleware.on_flood(self.id, packet),
+            };
+            if verdict == Verdict::Veto {
+                debug!("Drone {} - {:?} vetoed by a middleware", self.id, hook);
+                self.stats.middleware_vetoes += 1;
+                return Verdict::Veto;
+            }
+        }
+        Verdict::Continue
+    }
+}
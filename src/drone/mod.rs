@@ -1,18 +1,62 @@
+pub mod arq;
+pub mod battery;
+pub mod chaos;
+pub mod crash;
+pub mod dedup;
+pub mod firewall;
+pub mod flood_cache;
+pub mod flood_scope;
+pub mod flood_trace;
 pub mod forward_packet;
 pub mod handle_command;
 pub mod handle_flood;
 pub mod hunt;
+pub mod latency;
+pub mod middleware;
+pub mod neighbors;
 pub mod optimize_route;
+pub mod rate_limit;
+pub mod report;
+pub mod reputation;
+pub mod scheduler;
 pub mod send_nack;
+pub mod snapshot;
 #[cfg(feature = "sounds")]
-mod sounds;
+pub mod sonification;
+#[cfg(feature = "sounds")]
+pub mod sounds;
+pub mod stats;
+pub mod telemetry;
 mod test;
+pub mod topology;
+pub mod transport;
 
+use crate::drone::arq::RetransmitBuffer;
+use crate::drone::battery::Battery;
+use crate::drone::chaos::Chaos;
+use crate::drone::crash::CrashPolicy;
+use crate::drone::dedup::FragmentCache;
+use crate::drone::firewall::Firewall;
+use crate::drone::flood_cache::FloodCache;
+use crate::drone::flood_scope::FloodScope;
+use crate::drone::flood_trace::TraceGuard;
+use crate::drone::middleware::{Hook, PacketMiddleware, Verdict};
+use crate::drone::neighbors::NeighborTable;
+use crate::drone::rate_limit::RateLimiter;
+use crate::drone::reputation::ReputationTable;
+use crate::drone::scheduler::{PacketScheduler, TrafficClass};
+#[cfg(feature = "sounds")]
+use crate::drone::sonification::Sonifier;
 #[cfg(feature = "sounds")]
-use crate::drone::sounds::sounds_feat::SPAWN_SOUND;
-use crossbeam_channel::{select_biased, Receiver, Sender};
+use crate::drone::sounds::sounds_feat::{flush_sounds, SoundEvent, SoundSettings};
+use crate::drone::stats::DroneStats;
+use crate::drone::telemetry::{Lifecycle, RustBustersTelemetry, TelemetryEvent};
+use crate::drone::topology::TopologyMap;
+use crate::drone::transport::{PacketLink, PacketSource};
+use crossbeam_channel::{select_biased, tick, Receiver, RecvError, Sender};
 use log::{debug, info, trace, warn};
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
 use wg_2024::controller::{DroneCommand, DroneEvent};
 use wg_2024::drone::Drone;
 use wg_2024::network::NodeId;
@@ -28,13 +72,45 @@ pub struct RustBustersDrone {
     controller_recv: Receiver<DroneCommand>,
     packet_recv: Receiver<Packet>,
     pdr: u8, // Packet Drop Rate in percentage (0-100)
-    packet_send: HashMap<NodeId, Sender<Packet>>,
-    received_floods: HashSet<(u64, NodeId)>, // flood_id, initiator_id
+    packet_send: NeighborTable,
+    received_floods: FloodCache,
     optimized_routing: bool,
     running: bool,
     hunt_mode: bool,
+    scheduler: PacketScheduler,
+    rate_limiter: RateLimiter,
+    flood_scope: FloodScope,
+    crash_policy: CrashPolicy,
+    crash_deadline: Option<Instant>,
+    stats: DroneStats,
+    controller_reports: bool,
+    tick: Receiver<Instant>,
+    reputation: ReputationTable,
+    reputation_reports: Option<(Duration, Instant)>, // interval, last report
+    hunt_threshold: Option<f64>,
+    middlewares: Vec<Box<dyn PacketMiddleware>>,
+    firewall: Firewall,
+    trace_guard: TraceGuard,
+    fragment_cache: FragmentCache,
+    retransmit_buffer: RetransmitBuffer,
+    topology: TopologyMap,
+    telemetry: Option<Sender<RustBustersTelemetry>>,
+    telemetry_stats: Option<(Duration, Instant)>, // interval, last snapshot
+    battery: Option<Battery>,
+    chaos: Option<Chaos>,
+    handling: Option<(TrafficClass, Instant)>, // class and queueing time of the packet being handled
+    #[cfg(feature = "sounds")]
+    sound: SoundSettings,
+    #[cfg(feature = "sounds")]
+    sonifier: Sonifier,
 }
 
+/// Maximum number of packets moved from the receiver to the scheduler queues in one iteration
+const MAX_RECEIVE_BATCH: usize = 256;
+
+/// Interval between the periodic checks of the drone, e.g. of the silent neighbors
+const TICK_INTERVAL: Duration = Duration::from_millis(100);
+
 impl Drone for RustBustersDrone {
     /// Creates a new drone with the given parameters
     /// #### Arguments
@@ -60,81 +136,183 @@ impl Drone for RustBustersDrone {
         packet_send: HashMap<NodeId, Sender<Packet>>,
         pdr: f32,
     ) -> Self {
-        info!("Start - Initializing drone with ID {}", id);
-        let drone = Self {
+        let packet_send = packet_send
+            .into_iter()
+            .map(|(node_id, sender)| (node_id, Box::new(sender) as Box<dyn PacketLink>))
+            .collect();
+        Self::with_transport(
             id,
             controller_send,
             controller_recv,
             packet_recv,
-            pdr: (pdr * 100.0) as u8,
             packet_send,
-            received_floods: HashSet::new(),
-            optimized_routing: false,
-            running: true,
-            hunt_mode: false,
-        };
-
-        #[cfg(feature = "sounds")]
-        drone.play_sound(SPAWN_SOUND);
-
-        drone
+            pdr,
+        )
     }
 
     /// Runs the drone
     fn run(&mut self) {
         info!("Run - Starting to run drone with ID {}", self.id);
+        self.emit(TelemetryEvent::Lifecycle(Lifecycle::Started));
         #[cfg(feature = "sounds")]
-        self.play_sound(SPAWN_SOUND);
-        while self.running || !self.packet_recv.is_empty() {
-            select_biased! {
-                recv(self.controller_recv) -> command_res => {
-                    match command_res {
-                        Ok(command) => {
-                            debug!(
-                                "Drone {} - Received command: {:?}",
-                                self.id,
-                                command
-                            );
-                            self.handle_command(command);
-                        }
-                        Err(e) => {
-                            warn!(
-                                "Drone {} - Error in receiving controller DroneCommand: {}",
-                                self.id,
-                                e
-                            );
-                        }
-                    }
-                },
-                recv(self.packet_recv) -> packet_res => {
-                    match packet_res {
-                        Ok(packet) => {
-                            trace!(
-                                "Drone {} - Received packet: {:?}",
-                                self.id,
-                                packet
-                            );
-                            match packet.pack_type {
-                                PacketType::FloodRequest(_) => self.handle_flood_request(packet),
-                                _ => self.forward_packet(packet, true),
-                            }
-                        }
-                        Err(e) => {
-                            warn!(
-                                "Drone {} - Error in receiving Packet: {}",
-                                self.id,
-                                e
-                            );
-                        }
-                    }
-                },
+        self.play_sound(SoundEvent::Spawn);
+        while self.running || !self.packet_recv.is_empty() || !self.scheduler.is_empty() {
+            if self.drain_expired() {
+                break;
+            }
+            if self.scheduler.is_empty() {
+                select_biased! {
+                    recv(self.controller_recv) -> command_res => self.on_command(command_res),
+                    recv(self.packet_recv) -> packet_res => self.on_packet(packet_res),
+                    recv(self.tick) -> _ => self.on_tick(),
+                }
+            } else {
+                select_biased! {
+                    recv(self.controller_recv) -> command_res => self.on_command(command_res),
+                    recv(self.packet_recv) -> packet_res => self.on_packet(packet_res),
+                    recv(self.tick) -> _ => self.on_tick(),
+                    default => {},
+                }
+            }
+
+            // Queue what is already waiting, so that control packets can overtake fragments
+            for _ in 0..MAX_RECEIVE_BATCH {
+                let Ok(packet) = self.packet_recv.try_recv() else {
+                    break;
+                };
+                self.on_packet(Ok(packet));
+            }
+
+            if let Some((packet, received)) = self.scheduler.pop_timed() {
+                self.dispatch_timed(packet, received);
             }
         }
+        self.finish_crash();
+        self.emit(TelemetryEvent::Lifecycle(Lifecycle::Stopped));
         info!("Stop - Stopped running drone with ID {}", self.id);
     }
 }
 
 impl RustBustersDrone {
+    /// Creates a new drone that exchanges packets over the given transport
+    /// #### Arguments
+    /// - `id`: The ID of the drone
+    /// - `controller_send`: The sender to send events to the controller
+    /// - `controller_recv`: The receiver to receive commands from the controller
+    /// - `packet_source`: The source the packets from the network arrive on
+    /// - `packet_send`: The map of node IDs to links to send packets to the network
+    /// - `pdr`: The Packet Drop Rate in percentage (0-100)
+    ///
+    /// #### Returns
+    /// A new instance of `RustBustersDrone`
+    pub fn with_transport(
+        id: NodeId,
+        controller_send: Sender<DroneEvent>,
+        controller_recv: Receiver<DroneCommand>,
+        packet_source: impl PacketSource,
+        packet_send: HashMap<NodeId, Box<dyn PacketLink>>,
+        pdr: f32,
+    ) -> Self {
+        info!("Start - Initializing drone with ID {}", id);
+        Self {
+            id,
+            controller_send,
+            controller_recv,
+            packet_recv: packet_source.into_receiver(),
+            pdr: (pdr * 100.0) as u8,
+            packet_send: packet_send.into_iter().collect(),
+            received_floods: FloodCache::default(),
+            optimized_routing: false,
+            running: true,
+            hunt_mode: false,
+            scheduler: PacketScheduler::default(),
+            rate_limiter: RateLimiter::default(),
+            flood_scope: FloodScope::default(),
+            crash_policy: CrashPolicy::default(),
+            crash_deadline: None,
+            stats: DroneStats::default(),
+            controller_reports: true,
+            tick: tick(TICK_INTERVAL),
+            reputation: ReputationTable::default(),
+            reputation_reports: None,
+            hunt_threshold: None,
+            middlewares: Vec::new(),
+            firewall: Firewall::default(),
+            trace_guard: TraceGuard::default(),
+            fragment_cache: FragmentCache::default(),
+            retransmit_buffer: RetransmitBuffer::default(),
+            topology: TopologyMap::default(),
+            telemetry: None,
+            telemetry_stats: None,
+            battery: None,
+            chaos: None,
+            handling: None,
+            #[cfg(feature = "sounds")]
+            sound: SoundSettings::default(),
+            #[cfg(feature = "sounds")]
+            sonifier: Sonifier::default(),
+        }
+    }
+
+    /// Handles the result of receiving from the controller channel
+    fn on_command(&mut self, command_res: Result<DroneCommand, RecvError>) {
+        match command_res {
+            Ok(command) => {
+                debug!("Drone {} - Received command: {:?}", self.id, command);
+                self.handle_command(command);
+            }
+            Err(e) => {
+                warn!(
+                    "Drone {} - Error in receiving controller DroneCommand: {}",
+                    self.id, e
+                );
+            }
+        }
+    }
+
+    /// Handles the result of receiving from the packet channel
+    fn on_packet(&mut self, packet_res: Result<Packet, RecvError>) {
+        match packet_res {
+            Ok(mut packet) => {
+                trace!("Drone {} - Received packet: {:?}", self.id, packet);
+                self.stats.packets_received += 1;
+                self.consume_receive_energy();
+                self.note_chaos_packet();
+                self.run_chaos_at(Instant::now());
+                self.note_received(&packet);
+                if self.run_middlewares(Hook::Receive, &mut packet) == Verdict::Veto {
+                    return;
+                }
+                self.enqueue_packet(packet);
+            }
+            Err(e) => {
+                warn!("Drone {} - Error in receiving Packet: {}", self.id, e);
+            }
+        }
+    }
+
+    /// Runs the periodic checks of the drone
+    fn on_tick(&mut self) {
+        self.drain_battery_at(Instant::now());
+        self.run_chaos_at(Instant::now());
+        self.check_neighbors();
+        self.publish_reputation();
+        self.publish_telemetry_stats();
+        #[cfg(feature = "sounds")]
+        flush_sounds();
+    }
+
+    /// Handles a packet taken from the scheduler queues
+    ///
+    /// #### Arguments
+    /// - `packet`: The packet to handle
+    pub(crate) fn dispatch_packet(&mut self, packet: Packet) {
+        match packet.pack_type {
+            PacketType::FloodRequest(_) => self.handle_flood_request(packet),
+            _ => self.forward_packet(packet, true),
+        }
+    }
+
     #[allow(dead_code)]
     /// Sets the `optimized_routing` field to the given value
     /// #### Arguments
@@ -165,9 +343,4 @@ impl RustBustersDrone {
         };
         debug!("Drone {} - Hunt mode {}", self.id, hunt_mode_state);
     }
-
-    #[cfg(feature = "sounds")]
-    pub fn play_sound(&self, sound: &'static [u8]) {
-        sounds::sounds_feat::play_sound(sound);
-    }
 }
@@ -0,0 +1,368 @@
+use crate::drone::report::ReportKind;
+#[cfg(feature = "sounds")]
+use crate::drone::sonification::Timbre;
+use crate::drone::telemetry::TelemetryEvent;
+use crate::drone::transport::PacketLink;
+use crate::RustBustersDrone;
+use log::{debug, info, warn};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use wg_2024::network::NodeId;
+use wg_2024::packet::{Packet, PacketType};
+
+/// Liveness of the link to a neighbor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum LinkState {
+    /// Packets are sent and received normally
+    Up = 0,
+    /// The last sends failed, or nothing was received for longer than the silence timeout
+    Suspect = 1,
+    /// The link was dropped after too many failed sends, or the neighbor was removed
+    Down = 2,
+}
+
+/// What the drone knows about a neighbor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NeighborInfo {
+    pub state: LinkState,
+    /// When the neighbor was added
+    pub added: Instant,
+    /// Last successful send to the neighbor
+    pub last_sent: Option<Instant>,
+    /// Last packet received from the neighbor
+    pub last_received: Option<Instant>,
+    /// Failed sends since the last successful one
+    pub consecutive_failures: u32,
+    /// Failed sends since the neighbor was added
+    pub failures: u64,
+    /// Successful sends since the neighbor was added
+    pub sent: u64,
+    /// Fragments the neighbor reported as dropped with `Nack::Dropped`
+    pub dropped: u64,
+}
+
+impl NeighborInfo {
+    /// Returns the share of the packets sent to the neighbor that were lost,
+    /// either because the send failed or because the neighbor dropped them
+    pub fn drop_rate(&self) -> f64 {
+        let attempts = self.sent + self.failures;
+        if attempts == 0 {
+            return 0.0;
+        }
+        ((self.failures + self.dropped) as f64 / attempts as f64).min(1.0)
+    }
+}
+
+struct Neighbor {
+    link: Option<Box<dyn PacketLink>>,
+    info: NeighborInfo,
+}
+
+/// Links to the neighbors of the drone, with their liveness
+pub struct NeighborTable {
+    neighbors: HashMap<NodeId, Neighbor>,
+    max_failures: u32,
+    silence_timeout: Option<Duration>,
+    changes: Vec<(NodeId, LinkState)>,
+}
+
+impl Default for NeighborTable {
+    fn default() -> Self {
+        Self {
+            neighbors: HashMap::new(),
+            max_failures: 1,
+            silence_timeout: None,
+            changes: Vec::new(),
+        }
+    }
+}
+
+impl FromIterator<(NodeId, Box<dyn PacketLink>)> for NeighborTable {
+    fn from_iter<I: IntoIterator<Item = (NodeId, Box<dyn PacketLink>)>>(iter: I) -> Self {
+        let mut table = Self::default();
+        for (id, link) in iter {
+            table.insert(id, link);
+        }
+        table.changes.clear();
+        table
+    }
+}
+
+impl NeighborTable {
+    /// Adds a neighbor, or replaces its link and brings it back up
+    pub fn insert(&mut self, id: NodeId, link: Box<dyn PacketLink>) {
+        let neighbor = Neighbor {
+            link: Some(link),
+            info: NeighborInfo {
+                state: LinkState::Up,
+                added: Instant::now(),
+                last_sent: None,
+                last_received: None,
+                consecutive_failures: 0,
+                failures: 0,
+                sent: 0,
+                dropped: 0,
+            },
+        };
+        let previous = self.neighbors.insert(id, neighbor);
+        if previous.map(|n| n.info.state) != Some(LinkState::Up) {
+            self.changes.push((id, LinkState::Up));
+        }
+    }
+
+    /// Removes a neighbor and its link
+    ///
+    /// #### Returns
+    /// Whether the neighbor was known
+    pub fn remove(&mut self, id: &NodeId) -> bool {
+        match self.neighbors.remove(id) {
+            Some(neighbor) => {
+                if neighbor.info.state != LinkState::Down {
+                    self.changes.push((*id, LinkState::Down));
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Drops every link, without reporting the neighbors as down
+    pub fn clear(&mut self) {
+        self.neighbors.clear();
+    }
+
+    /// Checks whether packets can be sent to the given node
+    pub fn contains_key(&self, id: &NodeId) -> bool {
+        self.neighbors
+            .get(id)
+            .is_some_and(|neighbor| neighbor.link.is_some())
+    }
+
+    /// Returns the number of neighbors packets can be sent to
+    pub fn len(&self) -> usize {
+        self.neighbors.values().filter(|n| n.link.is_some()).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the sorted IDs of the neighbors packets can be sent to
+    pub fn ids(&self) -> Vec<NodeId> {
+        let mut ids: Vec<NodeId> = self
+            .neighbors
+            .iter()
+            .filter(|(_, neighbor)| neighbor.link.is_some())
+            .map(|(&id, _)| id)
+            .collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    /// Returns what is known about a neighbor, including the ones that are down
+    pub fn info(&self, id: NodeId) -> Option<NeighborInfo> {
+        self.neighbors.get(&id).map(|neighbor| neighbor.info)
+    }
+
+    /// Returns what is known about every neighbor, sorted by ID
+    pub fn infos(&self) -> Vec<(NodeId, NeighborInfo)> {
+        let mut infos: Vec<(NodeId, NeighborInfo)> = self
+            .neighbors
+            .iter()
+            .map(|(&id, neighbor)| (id, neighbor.info))
+            .collect();
+        infos.sort_unstable_by_key(|&(id, _)| id);
+        infos
+    }
+
+    /// Sends a packet to a neighbor, recording the outcome
+    ///
+    /// #### Errors
+    /// - If the node is not a neighbor, or its link is down
+    /// - If the send failed: after `max_failures` consecutive failures the link is dropped
+    pub fn send(&mut self, id: NodeId, packet: Packet) -> Result<(), String> {
+        let now = Instant::now();
+        let Some(neighbor) = self.neighbors.get_mut(&id) else {
+            return Err(format!("{id} is not a neighbor"));
+        };
+        let Some(link) = &neighbor.link else {
+            return Err(format!("link to {id} is down"));
+        };
+        let result = link.send_packet(packet);
+        match result {
+            Ok(()) => {
+                neighbor.info.last_sent = Some(now);
+                neighbor.info.consecutive_failures = 0;
+                neighbor.info.sent += 1;
+            }
+            Err(_) => {
+                neighbor.info.consecutive_failures += 1;
+                neighbor.info.failures += 1;
+                if neighbor.info.consecutive_failures >= self.max_failures {
+                    neighbor.link = None;
+                }
+            }
+        }
+        self.update_state(id, now);
+        result
+    }
+
+    /// Records that a neighbor dropped a fragment
+    pub fn record_dropped(&mut self, id: NodeId) {
+        if let Some(neighbor) = self.neighbors.get_mut(&id) {
+            neighbor.info.dropped += 1;
+        }
+    }
+
+    /// Records that a packet was received from a neighbor
+    pub fn record_received(&mut self, id: NodeId) {
+        let now = Instant::now();
+        if let Some(neighbor) = self.neighbors.get_mut(&id) {
+            neighbor.info.last_received = Some(now);
+            self.update_state(id, now);
+        }
+    }
+
+    /// Marks as suspect the neighbors silent for longer than the silence timeout
+    pub fn check_silence(&mut self, now: Instant) {
+        if self.silence_timeout.is_none() {
+            return;
+        }
+        let ids: Vec<NodeId> = self.neighbors.keys().copied().collect();
+        for id in ids {
+            self.update_state(id, now);
+        }
+    }
+
+    /// Takes the state changes that happened since the last call
+    pub fn take_changes(&mut self) -> Vec<(NodeId, LinkState)> {
+        std::mem::take(&mut self.changes)
+    }
+
+    fn update_state(&mut self, id: NodeId, now: Instant) {
+        let Some(neighbor) = self.neighbors.get_mut(&id) else {
+            return;
+        };
+        let silent = self.silence_timeout.is_some_and(|timeout| {
+            let last_heard = neighbor.info.last_received.unwrap_or(neighbor.info.added);
+            now.saturating_duration_since(last_heard) > timeout
+        });
+        let state = if neighbor.link.is_none() {
+            LinkState::Down
+        } else if neighbor.info.consecutive_failures > 0 || silent {
+            LinkState::Suspect
+        } else {
+            LinkState::Up
+        };
+        if state != neighbor.info.state {
+            neighbor.info.state = state;
+            self.changes.push((id, state));
+        }
+    }
+}
+
+/// Returns the node a packet was received from, if it can be told from the packet
+pub fn previous_hop(packet: &Packet) -> Option<NodeId> {
+    match &packet.pack_type {
+        PacketType::FloodRequest(flood_request) => flood_request
+            .path_trace
+            .last()
+            .map(|&(id, _)| id)
+            .or(Some(flood_request.initiator_id)),
+        _ => packet
+            .routing_header
+            .hop_index
+            .checked_sub(1)
+            .and_then(|index| packet.routing_header.hops.get(index))
+            .copied(),
+    }
+}
+
+impl RustBustersDrone {
+    /// Sets after how many consecutive failed sends the link to a neighbor is dropped
+    ///
+    /// #### Arguments
+    /// - `max_failures`: The number of failures, at least 1
+    pub fn set_neighbor_max_failures(&mut self, max_failures: u32) {
+        self.packet_send.max_failures = max_failures.max(1);
+        debug!(
+            "Drone {} - Neighbor max failures set to {}",
+            self.id, self.packet_send.max_failures
+        );
+    }
+
+    /// Sets after how long without receiving anything a neighbor becomes suspect
+    ///
+    /// #### Arguments
+    /// - `timeout`: The silence timeout, or `None` to disable the check
+    pub fn set_neighbor_silence_timeout(&mut self, timeout: Option<Duration>) {
+        self.packet_send.silence_timeout = timeout;
+        debug!(
+            "Drone {} - Neighbor silence timeout set to {:?}",
+            self.id, timeout
+        );
+    }
+
+    /// Returns what the drone knows about a neighbor
+    pub fn neighbor(&self, id: NodeId) -> Option<NeighborInfo> {
+        self.packet_send.info(id)
+    }
+
+    /// Returns what the drone knows about every neighbor, sorted by ID
+    pub fn neighbors(&self) -> Vec<(NodeId, NeighborInfo)> {
+        self.packet_send.infos()
+    }
+
+    /// Sends a packet to a neighbor, recording the outcome in the neighbor table
+    ///
+    /// #### Errors
+    /// - If the node is not a neighbor or the send failed
+    pub(crate) fn send_to_neighbor(&mut self, id: NodeId, packet: Packet) -> Result<(), String> {
+        #[cfg(feature = "sounds")]
+        let timbre = Timbre::of(&packet.pack_type);
+        if self.is_flapping(id) {
+            return Err(format!("Link to {id} is down by the chaos plan"));
+        }
+        let energy = self.send_energy(&packet.pack_type);
+        self.packet_send.send(id, packet)?;
+        self.consume_energy(energy);
+        self.record_latency();
+        #[cfg(feature = "sounds")]
+        self.sonify(timbre);
+        Ok(())
+    }
+
+    /// Records the neighbor a received packet comes from
+    pub(crate) fn note_received(&mut self, packet: &Packet) {
+        if let Some(id) = previous_hop(packet) {
+            self.packet_send.record_received(id);
+        }
+    }
+
+    /// Periodic check of the neighbors: marks the silent ones as suspect
+    /// and reports the link state changes
+    pub(crate) fn check_neighbors(&mut self) {
+        self.packet_send.check_silence(Instant::now());
+        self.report_neighbor_changes();
+    }
+
+    /// Reports the link state changes to the controller
+    ///
+    /// > Note: the changes are reported periodically rather than when they happen,
+    /// > so that the reports do not interleave with the events of the packet being handled
+    pub(crate) fn report_neighbor_changes(&mut self) {
+        for (id, state) in self.packet_send.take_changes() {
+            match state {
+                LinkState::Up => info!("Drone {} - Neighbor {} is up", self.id, id),
+                LinkState::Suspect => warn!("Drone {} - Neighbor {} is suspect", self.id, id),
+                LinkState::Down => warn!("Drone {} - Neighbor {} is down", self.id, id),
+            }
+            self.send_report(ReportKind::NeighborState, &[id, state as u8]);
+            self.emit(TelemetryEvent::NeighborState {
+                neighbor: id,
+                state,
+            });
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use super::RustBustersDrone;
+use crate::drone::telemetry::TelemetryEvent;
 use wg_2024::network::NodeId;
 
 impl RustBustersDrone {
@@ -22,6 +23,12 @@ impl RustBustersDrone {
             let mut ret_path = vec![self.id];
 
             ret_path.append(path[(path.len() - pos - 1)..].to_vec().as_mut());
+            if ret_path != path {
+                self.emit(TelemetryEvent::RouteOptimized {
+                    before: path.to_vec(),
+                    after: ret_path.clone(),
+                });
+            }
             ret_path
         } else {
             path.to_vec()
@@ -0,0 +1,366 @@
+use crate::drone::report::ReportKind;
+use crate::drone::scheduler::TrafficClass;
+#[cfg(feature = "sounds")]
+use crate::drone::sounds::sounds_feat::SoundEvent;
+use crate::drone::telemetry::TelemetryEvent;
+use crate::RustBustersDrone;
+use log::{debug, info};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::time::Instant;
+use wg_2024::network::NodeId;
+use wg_2024::packet::{FloodRequest, Packet, PacketType};
+
+/// Number of buckets kept per scope before the idle ones are discarded
+const MAX_BUCKETS: usize = 4096;
+
+/// Token bucket parameters
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RateLimit {
+    /// Tokens added every second
+    pub rate: f64,
+    /// Maximum number of tokens, i.e. the largest accepted burst
+    pub burst: u32,
+}
+
+impl RateLimit {
+    pub fn new(rate: f64, burst: u32) -> Self {
+        Self { rate, burst }
+    }
+}
+
+/// Token bucket refilled continuously at `RateLimit::rate`
+#[derive(Debug, Clone)]
+pub struct TokenBucket {
+    limit: RateLimit,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    /// Creates a full bucket
+    pub fn new(limit: RateLimit, now: Instant) -> Self {
+        Self {
+            limit,
+            tokens: f64::from(limit.burst),
+            last_refill: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.limit.rate).min(f64::from(self.limit.burst));
+        self.last_refill = now;
+    }
+
+    /// Takes a token if there is one
+    ///
+    /// #### Returns
+    /// `true` if the token was taken, `false` if the bucket is empty
+    pub fn try_take(&mut self, now: Instant) -> bool {
+        self.refill(now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn is_full(&mut self, now: Instant) -> bool {
+        self.refill(now);
+        self.tokens >= f64::from(self.limit.burst)
+    }
+}
+
+/// Buckets of one scope, one per key, sharing a default limit
+#[derive(Debug, Clone)]
+struct KeyedLimiter<K> {
+    default: Option<RateLimit>,
+    overrides: HashMap<K, RateLimit>,
+    buckets: HashMap<K, TokenBucket>,
+}
+
+impl<K> Default for KeyedLimiter<K> {
+    fn default() -> Self {
+        Self {
+            default: None,
+            overrides: HashMap::new(),
+            buckets: HashMap::new(),
+        }
+    }
+}
+
+impl<K: Copy + Eq + Hash> KeyedLimiter<K> {
+    fn set_default(&mut self, limit: Option<RateLimit>) {
+        self.default = limit;
+        self.buckets.clear();
+    }
+
+    fn set_override(&mut self, key: K, limit: Option<RateLimit>) {
+        match limit {
+            Some(limit) => self.overrides.insert(key, limit),
+            None => self.overrides.remove(&key),
+        };
+        self.buckets.remove(&key);
+    }
+
+    /// Returns the default limit and the overrides, sorted by key
+    fn limits(&self) -> (Option<RateLimit>, Vec<(K, RateLimit)>)
+    where
+        K: Ord,
+    {
+        let mut overrides: Vec<(K, RateLimit)> = self
+            .overrides
+            .iter()
+            .map(|(&key, &limit)| (key, limit))
+            .collect();
+        overrides.sort_unstable_by_key(|&(key, _)| key);
+        (self.default, overrides)
+    }
+
+    fn allow(&mut self, key: K, now: Instant) -> bool {
+        let Some(limit) = self.overrides.get(&key).copied().or(self.default) else {
+            return true;
+        };
+        if self.buckets.len() >= MAX_BUCKETS && !self.buckets.contains_key(&key) {
+            // A full bucket behaves like a new one, so it can be discarded
+            self.buckets.retain(|_, bucket| !bucket.is_full(now));
+        }
+        self.buckets
+            .entry(key)
+            .or_insert_with(|| TokenBucket::new(limit, now))
+            .try_take(now)
+    }
+}
+
+/// Scope of the rate limit a packet exceeded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitScope {
+    /// The neighbor the packet was received from
+    Neighbor(NodeId),
+    /// The session of the fragment
+    Session(u64),
+    /// The initiator of the flood
+    Initiator(NodeId),
+}
+
+impl LimitScope {
+    /// Encodes the scope as the payload of a `ReportKind::RateLimited` report
+    ///
+    /// #### Returns
+    /// `[class, scope, key...]`, where `class` is the `TrafficClass` of the packet,
+    /// `scope` is 0 for neighbors, 1 for sessions and 2 for initiators,
+    /// and `key` is the node ID or the little endian session ID
+    pub fn encode(&self, class: TrafficClass) -> Vec<u8> {
+        let mut payload = vec![class as u8];
+        match self {
+            LimitScope::Neighbor(id) => payload.extend([0, *id]),
+            LimitScope::Session(session_id) => {
+                payload.push(1);
+                payload.extend(session_id.to_le_bytes());
+            }
+            LimitScope::Initiator(id) => payload.extend([2, *id]),
+        }
+        payload
+    }
+}
+
+/// Token bucket rate limiters applied to the received packets
+#[derive(Debug, Clone, Default)]
+pub struct RateLimiter {
+    neighbors: KeyedLimiter<NodeId>,
+    sessions: KeyedLimiter<u64>,
+    initiators: KeyedLimiter<NodeId>,
+}
+
+impl RateLimiter {
+    /// Returns the neighbor limit and its overrides
+    pub(crate) fn neighbor_limits(&self) -> (Option<RateLimit>, Vec<(NodeId, RateLimit)>) {
+        self.neighbors.limits()
+    }
+
+    /// Returns the session limit
+    pub(crate) fn session_limit(&self) -> Option<RateLimit> {
+        self.sessions.default
+    }
+
+    /// Returns the initiator limit and its overrides
+    pub(crate) fn initiator_limits(&self) -> (Option<RateLimit>, Vec<(NodeId, RateLimit)>) {
+        self.initiators.limits()
+    }
+
+    /// Checks a fragment against the neighbor and session limits
+    ///
+    /// #### Returns
+    /// The scope of the exceeded limit, or `None` if the fragment is accepted
+    pub fn check_fragment(
+        &mut self,
+        neighbor: NodeId,
+        session_id: u64,
+        now: Instant,
+    ) -> Option<LimitScope> {
+        if !self.neighbors.allow(neighbor, now) {
+            return Some(LimitScope::Neighbor(neighbor));
+        }
+        if !self.sessions.allow(session_id, now) {
+            return Some(LimitScope::Session(session_id));
+        }
+        None
+    }
+
+    /// Checks a flood request against the neighbor and initiator limits
+    ///
+    /// #### Returns
+    /// The scope of the exceeded limit, or `None` if the flood request is accepted
+    pub fn check_flood(
+        &mut self,
+        neighbor: NodeId,
+        initiator: NodeId,
+        now: Instant,
+    ) -> Option<LimitScope> {
+        if !self.neighbors.allow(neighbor, now) {
+            return Some(LimitScope::Neighbor(neighbor));
+        }
+        if !self.initiators.allow(initiator, now) {
+            return Some(LimitScope::Initiator(initiator));
+        }
+        None
+    }
+}
+
+impl RustBustersDrone {
+    /// Sets the rate limit applied to the fragments and flood requests of every neighbor
+    ///
+    /// #### Arguments
+    /// - `limit`: The limit, or `None` to disable it
+    pub fn set_neighbor_rate_limit(&mut self, limit: Option<RateLimit>) {
+        self.rate_limiter.neighbors.set_default(limit);
+        debug!("Drone {} - Neighbor rate limit set to {:?}", self.id, limit);
+    }
+
+    /// Sets the rate limit of a single neighbor, overriding the one of every neighbor
+    ///
+    /// #### Arguments
+    /// - `neighbor`: The ID of the neighbor
+    /// - `limit`: The limit, or `None` to go back to the one of every neighbor
+    pub fn set_neighbor_rate_limit_for(&mut self, neighbor: NodeId, limit: Option<RateLimit>) {
+        self.rate_limiter.neighbors.set_override(neighbor, limit);
+        debug!(
+            "Drone {} - Rate limit of neighbor {} set to {:?}",
+            self.id, neighbor, limit
+        );
+    }
+
+    /// Sets the rate limit applied to the fragments of every session
+    ///
+    /// #### Arguments
+    /// - `limit`: The limit, or `None` to disable it
+    pub fn set_session_rate_limit(&mut self, limit: Option<RateLimit>) {
+        self.rate_limiter.sessions.set_default(limit);
+        debug!("Drone {} - Session rate limit set to {:?}", self.id, limit);
+    }
+
+    /// Sets the rate limit applied to the flood requests of every initiator
+    ///
+    /// #### Arguments
+    /// - `limit`: The limit, or `None` to disable it
+    pub fn set_initiator_rate_limit(&mut self, limit: Option<RateLimit>) {
+        self.rate_limiter.initiators.set_default(limit);
+        debug!(
+            "Drone {} - Initiator rate limit set to {:?}",
+            self.id, limit
+        );
+    }
+
+    /// Sets the rate limit of a single flood initiator, overriding the one of every initiator
+    ///
+    /// #### Arguments
+    /// - `initiator`: The ID of the initiator
+    /// - `limit`: The limit, or `None` to go back to the one of every initiator
+    pub fn set_initiator_rate_limit_for(&mut self, initiator: NodeId, limit: Option<RateLimit>) {
+        self.rate_limiter.initiators.set_override(initiator, limit);
+        debug!(
+            "Drone {} - Rate limit of initiator {} set to {:?}",
+            self.id, initiator, limit
+        );
+    }
+
+    /// Applies the rate limits to a received fragment
+    ///
+    /// #### Arguments
+    /// - `packet`: The received packet
+    ///
+    /// #### Returns
+    /// The packet if it is accepted, `None` if it exceeded a limit and has already been handled
+    ///
+    /// > Note: excess fragments are dropped with `Nack::Dropped`. Flood requests are let through:
+    /// > they are limited in `handle_flood_request`, once their path trace is checked and they
+    /// > are known not to be duplicates
+    pub(crate) fn apply_rate_limits(&mut self, packet: Packet) -> Option<Packet> {
+        if !matches!(packet.pack_type, PacketType::MsgFragment(_)) {
+            return Some(packet);
+        }
+        let hop_index = packet.routing_header.hop_index;
+        // Misrouted fragments are left to forward_packet
+        if hop_index == 0 || packet.routing_header.hops.get(hop_index) != Some(&self.id) {
+            return Some(packet);
+        }
+        let neighbor = packet.routing_header.hops[hop_index - 1];
+        let Some(scope) =
+            self.rate_limiter
+                .check_fragment(neighbor, packet.session_id, Instant::now())
+        else {
+            return Some(packet);
+        };
+
+        self.note_rate_limited(scope, TrafficClass::Data, packet.session_id);
+        self.stats.rate_limited_fragments += 1;
+        self.drop_fragment(packet);
+        None
+    }
+
+    /// Applies the rate limits to a checked, not yet seen flood request
+    ///
+    /// #### Arguments
+    /// - `flood_request`: The flood request, with the drone already added to its path trace
+    /// - `session_id`: The session ID of the flood request
+    /// - `sender_id`: The ID of the neighbor the flood request came from
+    ///
+    /// #### Returns
+    /// `true` if the flood request exceeded a limit and has been answered with a `FloodResponse`
+    /// instead of being spread
+    pub(crate) fn rate_limit_flood(
+        &mut self,
+        flood_request: &FloodRequest,
+        session_id: u64,
+        sender_id: NodeId,
+    ) -> bool {
+        let Some(scope) =
+            self.rate_limiter
+                .check_flood(sender_id, flood_request.initiator_id, Instant::now())
+        else {
+            return false;
+        };
+
+        self.note_rate_limited(scope, TrafficClass::Flood, session_id);
+        self.stats.rate_limited_floods += 1;
+        self.remember_flood(flood_request.flood_id, flood_request.initiator_id);
+        self.send_flood_response(flood_request, session_id, sender_id);
+        true
+    }
+
+    fn note_rate_limited(&mut self, scope: LimitScope, class: TrafficClass, session_id: u64) {
+        info!(
+            "Drone {} - Rate limit exceeded ({:?}) by packet of session {}",
+            self.id, scope, session_id
+        );
+        self.send_report(ReportKind::RateLimited, &scope.encode(class));
+        self.emit(TelemetryEvent::RateLimited { scope, session_id });
+        #[cfg(feature = "sounds")]
+        self.play_sound(SoundEvent::RateLimited);
+    }
+}
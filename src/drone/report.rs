@@ -0,0 +1,147 @@
+use crate::RustBustersDrone;
+use log::{debug, warn};
+use wg_2024::controller::DroneEvent;
+use wg_2024::network::{NodeId, SourceRoutingHeader};
+use wg_2024::packet::{Fragment, Packet, PacketType, FRAGMENT_DSIZE};
+
+/// Value of `Fragment::length` marking a report sent to the controller
+pub const REPORT_CONST: u8 = 170;
+
+/// Maximum length of the payload carried by a report
+pub const REPORT_PAYLOAD_SIZE: usize = FRAGMENT_DSIZE - 2;
+
+/// Kind of a report sent to the controller
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ReportKind {
+    /// A packet exceeded a rate limit, see `rate_limit::LimitScope::encode`
+    RateLimited = 0,
+    /// The drone stopped after a crash, see `crash::ShutdownReport`
+    Shutdown = 1,
+    /// The link state of a neighbor changed: `[neighbor_id, neighbors::LinkState]`
+    NeighborState = 2,
+    /// Ranking of the nodes blamed by the relayed Nacks, see `reputation::decode_ranking`
+    Reputation = 3,
+    /// A flood request with an inconsistent `path_trace` was discarded, see `flood_trace::TraceViolation`
+    TamperedFlood = 4,
+    /// A fragment dropped by the next hop was sent again, see `arq::ArqSettings`
+    Retransmitted = 5,
+    /// The battery level dropped by a step: `[level in percent]`, see `battery::BatterySettings`
+    Battery = 6,
+    /// The chaos plan injected a fault, see `chaos::ChaosAction::encode`
+    Chaos = 7,
+    /// Tail latencies sent on shutdown, see `latency::LatencyStats::encode_tail`
+    Latency = 8,
+}
+
+impl TryFrom<u8> for ReportKind {
+    type Error = String;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(ReportKind::RateLimited),
+            1 => Ok(ReportKind::Shutdown),
+            2 => Ok(ReportKind::NeighborState),
+            3 => Ok(ReportKind::Reputation),
+            4 => Ok(ReportKind::TamperedFlood),
+            5 => Ok(ReportKind::Retransmitted),
+            6 => Ok(ReportKind::Battery),
+            7 => Ok(ReportKind::Chaos),
+            8 => Ok(ReportKind::Latency),
+            _ => Err(format!("Unknown report kind {value}")),
+        }
+    }
+}
+
+/// Report decoded from a `DroneEvent::PacketSent`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub drone_id: NodeId,
+    pub kind: ReportKind,
+    pub payload: Vec<u8>,
+}
+
+impl Report {
+    /// Decodes the report carried by a packet, if any
+    ///
+    /// #### Arguments
+    /// - `packet`: The packet of a `DroneEvent::PacketSent`
+    ///
+    /// #### Returns
+    /// The report, or `None` if the packet is not a report
+    ///
+    /// > Note: reports are `MsgFragment`s with `fragment_index` and `total_n_fragments` set to 0
+    /// > and `length` set to `REPORT_CONST`, just like hunt packets use `PACKET_CONST`
+    pub fn parse(packet: &Packet) -> Option<Report> {
+        let PacketType::MsgFragment(fragment) = &packet.pack_type else {
+            return None;
+        };
+        if fragment.fragment_index != 0
+            || fragment.total_n_fragments != 0
+            || fragment.length != REPORT_CONST
+        {
+            return None;
+        }
+        let kind = ReportKind::try_from(fragment.data[1]).ok()?;
+        Some(Report {
+            drone_id: fragment.data[0],
+            kind,
+            payload: fragment.data[2..].to_vec(),
+        })
+    }
+}
+
+impl RustBustersDrone {
+    /// Enables or disables the reports sent to the controller as `DroneEvent::PacketSent`
+    ///
+    /// #### Arguments
+    /// - `enabled`: Whether the reports are sent, `true` by default
+    ///
+    /// > Note: the reports are not real packets, so a controller that does not filter them out
+    /// > with `Report::parse` should disable them
+    pub fn set_controller_reports(&mut self, enabled: bool) {
+        self.controller_reports = enabled;
+        debug!(
+            "Drone {} - Controller reports {}",
+            self.id,
+            if enabled { "enabled" } else { "disabled" }
+        );
+    }
+
+    /// Sends a report to the controller, if the reports are enabled
+    ///
+    /// #### Arguments
+    /// - `kind`: The kind of the report
+    /// - `payload`: The content of the report, truncated to `REPORT_PAYLOAD_SIZE` bytes
+    pub(crate) fn send_report(&mut self, kind: ReportKind, payload: &[u8]) {
+        if !self.controller_reports {
+            return;
+        }
+        if payload.len() > REPORT_PAYLOAD_SIZE {
+            warn!(
+                "Drone {} - Report {:?} truncated to {} bytes",
+                self.id, kind, REPORT_PAYLOAD_SIZE
+            );
+        }
+        let mut data = [0; FRAGMENT_DSIZE];
+        data[0] = self.id;
+        data[1] = kind as u8;
+        let len = payload.len().min(REPORT_PAYLOAD_SIZE);
+        data[2..2 + len].copy_from_slice(&payload[..len]);
+
+        let report_packet = Packet {
+            pack_type: PacketType::MsgFragment(Fragment {
+                fragment_index: 0,
+                total_n_fragments: 0,
+                length: REPORT_CONST,
+                data,
+            }),
+            routing_header: SourceRoutingHeader {
+                hop_index: 0,
+                hops: vec![],
+            },
+            session_id: 0,
+        };
+        self.send_to_sc(DroneEvent::PacketSent(report_packet));
+    }
+}
@@ -0,0 +1,273 @@
+use crate::drone::report::{ReportKind, REPORT_PAYLOAD_SIZE};
+use crate::drone::telemetry::TelemetryEvent;
+use crate::RustBustersDrone;
+use log::{debug, trace};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use wg_2024::network::NodeId;
+use wg_2024::packet::{Nack, NackType, Packet};
+
+/// Default time after which a penalty counts half
+pub const DEFAULT_HALF_LIFE: Duration = Duration::from_secs(60);
+
+/// Penalty given for every kind of Nack
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ReputationWeights {
+    /// Given to the node that dropped the fragment
+    pub dropped: f64,
+    /// Given to the node that could not be reached
+    pub error_in_routing: f64,
+    /// Given to the node that sent the fragment to the wrong recipient
+    pub unexpected_recipient: f64,
+    /// Given to the source that routed the fragment to a drone
+    pub destination_is_drone: f64,
+}
+
+impl Default for ReputationWeights {
+    fn default() -> Self {
+        Self {
+            dropped: 1.0,
+            error_in_routing: 1.0,
+            unexpected_recipient: 1.0,
+            destination_is_drone: 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Score {
+    value: f64,
+    updated: Instant,
+}
+
+/// Decaying penalty scores of the nodes blamed by the relayed Nacks: the higher, the worse
+#[derive(Debug, Clone)]
+pub struct ReputationTable {
+    scores: HashMap<NodeId, Score>,
+    weights: ReputationWeights,
+    half_life: Duration,
+}
+
+impl Default for ReputationTable {
+    fn default() -> Self {
+        Self {
+            scores: HashMap::new(),
+            weights: ReputationWeights::default(),
+            half_life: DEFAULT_HALF_LIFE,
+        }
+    }
+}
+
+impl ReputationTable {
+    /// Returns the penalty given for every kind of Nack
+    pub(crate) fn weights(&self) -> ReputationWeights {
+        self.weights
+    }
+
+    /// Returns the time after which a penalty counts half
+    pub(crate) fn half_life(&self) -> Duration {
+        self.half_life
+    }
+
+    /// Returns the node blamed by a Nack and the penalty it gets
+    ///
+    /// #### Arguments
+    /// - `nack`: The relayed Nack
+    /// - `hops`: The route of the Nack packet, starting from the node that created it
+    pub fn blame(&self, nack: &Nack, hops: &[NodeId]) -> Option<(NodeId, f64)> {
+        match nack.nack_type {
+            NackType::Dropped => hops.first().map(|&id| (id, self.weights.dropped)),
+            NackType::ErrorInRouting(id) => Some((id, self.weights.error_in_routing)),
+            NackType::UnexpectedRecipient(_) => hops
+                .get(1)
+                .map(|&id| (id, self.weights.unexpected_recipient)),
+            NackType::DestinationIsDrone => hops
+                .last()
+                .map(|&id| (id, self.weights.destination_is_drone)),
+        }
+    }
+
+    /// Adds a penalty to a node
+    pub fn penalize(&mut self, id: NodeId, penalty: f64, now: Instant) {
+        let value = self.score_at(id, now) + penalty;
+        self.scores.insert(
+            id,
+            Score {
+                value,
+                updated: now,
+            },
+        );
+    }
+
+    /// Returns the score of a node at the given time
+    pub fn score_at(&self, id: NodeId, now: Instant) -> f64 {
+        self.scores.get(&id).map_or(0.0, |score| {
+            let elapsed = now.saturating_duration_since(score.updated);
+            score.value * 0.5_f64.powf(elapsed.as_secs_f64() / self.half_life.as_secs_f64())
+        })
+    }
+
+    /// Returns the nodes with a score above `min_score`, the worst first
+    pub fn ranking_at(&self, now: Instant, min_score: f64) -> Vec<(NodeId, f64)> {
+        let mut ranking: Vec<(NodeId, f64)> = self
+            .scores
+            .keys()
+            .map(|&id| (id, self.score_at(id, now)))
+            .filter(|&(_, score)| score > min_score)
+            .collect();
+        ranking.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        ranking
+    }
+
+    /// Replaces the scores, e.g. when restoring a drone
+    pub fn set_scores(&mut self, scores: impl IntoIterator<Item = (NodeId, f64)>, now: Instant) {
+        self.scores = scores
+            .into_iter()
+            .map(|(id, value)| {
+                (
+                    id,
+                    Score {
+                        value,
+                        updated: now,
+                    },
+                )
+            })
+            .collect();
+    }
+
+    /// Discards the scores that decayed to almost nothing
+    pub fn prune(&mut self, now: Instant) {
+        let half_life = self.half_life;
+        self.scores.retain(|_, score| {
+            let elapsed = now.saturating_duration_since(score.updated);
+            score.value * 0.5_f64.powf(elapsed.as_secs_f64() / half_life.as_secs_f64()) > 0.01
+        });
+    }
+}
+
+impl RustBustersDrone {
+    /// Sets the penalty given for every kind of relayed Nack
+    ///
+    /// #### Arguments
+    /// - `weights`: The penalties
+    pub fn set_reputation_weights(&mut self, weights: ReputationWeights) {
+        self.reputation.weights = weights;
+        debug!(
+            "Drone {} - Reputation weights set to {:?}",
+            self.id, weights
+        );
+    }
+
+    /// Sets how fast the penalties are forgotten
+    ///
+    /// #### Arguments
+    /// - `half_life`: The time after which a penalty counts half
+    pub fn set_reputation_half_life(&mut self, half_life: Duration) {
+        self.reputation.half_life = half_life.max(Duration::from_millis(1));
+        debug!(
+            "Drone {} - Reputation half life set to {:?}",
+            self.id, half_life
+        );
+    }
+
+    /// Sets how often the ranking is published on the telemetry stream and to the controller
+    ///
+    /// #### Arguments
+    /// - `interval`: The interval between two reports, or `None` to not publish the ranking
+    pub fn set_reputation_reports(&mut self, interval: Option<Duration>) {
+        self.reputation_reports = interval.map(|interval| (interval, Instant::now()));
+        debug!(
+            "Drone {} - Reputation reports interval set to {:?}",
+            self.id, interval
+        );
+    }
+
+    /// Sets the score a node must reach before it is hunted
+    ///
+    /// #### Arguments
+    /// - `threshold`: The minimum score, or `None` to hunt on every `Nack::Dropped`
+    pub fn set_hunt_threshold(&mut self, threshold: Option<f64>) {
+        self.hunt_threshold = threshold;
+        debug!("Drone {} - Hunt threshold set to {:?}", self.id, threshold);
+    }
+
+    /// Returns the current score of a node: the higher, the worse
+    pub fn reputation(&self, id: NodeId) -> f64 {
+        self.reputation.score_at(id, Instant::now())
+    }
+
+    /// Returns the nodes blamed by the relayed Nacks, the worst first
+    pub fn suspects(&self) -> Vec<(NodeId, f64)> {
+        self.reputation.ranking_at(Instant::now(), 0.0)
+    }
+
+    /// Updates the scores with a relayed Nack
+    ///
+    /// #### Arguments
+    /// - `packet`: The Nack packet
+    /// - `nack`: The Nack
+    ///
+    /// #### Returns
+    /// The blamed node and its new score
+    pub(crate) fn observe_nack(&mut self, packet: &Packet, nack: &Nack) -> Option<(NodeId, f64)> {
+        let (id, penalty) = self.reputation.blame(nack, &packet.routing_header.hops)?;
+        let now = Instant::now();
+        self.reputation.penalize(id, penalty, now);
+        let score = self.reputation.score_at(id, now);
+        trace!(
+            "Drone {} - Node {} blamed for {:?}, score {:.2}",
+            self.id,
+            id,
+            nack.nack_type,
+            score
+        );
+        Some((id, score))
+    }
+
+    /// Publishes the ranking on the telemetry stream and to the controller, if due
+    ///
+    /// > Note: the payload is a list of `[node_id, score as f32 little endian]` entries, the worst first
+    pub(crate) fn publish_reputation(&mut self) {
+        let now = Instant::now();
+        self.reputation.prune(now);
+        let Some((interval, last)) = self.reputation_reports else {
+            return;
+        };
+        if now.saturating_duration_since(last) < interval {
+            return;
+        }
+        self.reputation_reports = Some((interval, now));
+
+        let ranking = self.reputation.ranking_at(now, 0.0);
+        if ranking.is_empty() {
+            return;
+        }
+        let payload: Vec<u8> = ranking
+            .iter()
+            .take(REPORT_PAYLOAD_SIZE / 5)
+            .flat_map(|&(id, score)| {
+                let mut entry = vec![id];
+                entry.extend((score as f32).to_le_bytes());
+                entry
+            })
+            .collect();
+        self.send_report(ReportKind::Reputation, &payload);
+        self.emit(TelemetryEvent::Reputation(ranking));
+    }
+}
+
+/// Decodes the payload of a `ReportKind::Reputation` report
+///
+/// #### Returns
+/// The ranking, the worst first
+pub fn decode_ranking(payload: &[u8]) -> Vec<(NodeId, f32)> {
+    payload
+        .chunks_exact(5)
+        .map(|entry| {
+            let score = f32::from_le_bytes([entry[1], entry[2], entry[3], entry[4]]);
+            (entry[0], score)
+        })
+        .take_while(|&(_, score)| score > 0.0)
+        .collect()
+}
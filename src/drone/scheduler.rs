@@ -0,0 +1,248 @@
+use super::RustBustersDrone;
+use crate::drone::stats::QueueStats;
+use log::{debug, info};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::time::Instant;
+use wg_2024::packet::{Packet, PacketType};
+
+/// Default maximum number of fragments waiting in the data queue
+pub const DEFAULT_DATA_QUEUE_CAPACITY: usize = 1024;
+
+/// Class a received packet is queued in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrafficClass {
+    /// `Ack`, `Nack` and `FloodResponse`
+    Control = 0,
+    /// `FloodRequest`
+    Flood = 1,
+    /// `MsgFragment`
+    Data = 2,
+}
+
+impl TrafficClass {
+    /// Returns the class of the given packet
+    pub fn of(packet: &Packet) -> Self {
+        match packet.pack_type {
+            PacketType::Ack(_) | PacketType::Nack(_) | PacketType::FloodResponse(_) => {
+                TrafficClass::Control
+            }
+            PacketType::FloodRequest(_) => TrafficClass::Flood,
+            PacketType::MsgFragment(_) => TrafficClass::Data,
+        }
+    }
+}
+
+/// Order in which the queued packets are handled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SchedulingPolicy {
+    /// Control packets first, then floods, then fragments
+    StrictPriority,
+    /// Weighted round robin: in every round each class handles up to its weight in packets
+    Weighted { control: u32, flood: u32, data: u32 },
+}
+
+/// Queues between the packet receiver and the forwarding logic of the drone
+#[derive(Debug)]
+pub struct PacketScheduler {
+    /// Queued packets, with when they were queued
+    queues: [VecDeque<(Packet, Instant)>; 3],
+    policy: SchedulingPolicy,
+    data_capacity: usize,
+    credits: [u32; 3],
+    stats: QueueStats,
+}
+
+impl Default for PacketScheduler {
+    fn default() -> Self {
+        Self::new(
+            SchedulingPolicy::StrictPriority,
+            DEFAULT_DATA_QUEUE_CAPACITY,
+        )
+    }
+}
+
+impl PacketScheduler {
+    /// Creates a new scheduler
+    /// #### Arguments
+    /// - `policy`: The order in which the queued packets are handled
+    /// - `data_capacity`: The maximum number of fragments waiting in the data queue
+    pub fn new(policy: SchedulingPolicy, data_capacity: usize) -> Self {
+        Self {
+            queues: Default::default(),
+            policy,
+            data_capacity,
+            credits: Self::weights(policy),
+            stats: QueueStats::default(),
+        }
+    }
+
+    fn weights(policy: SchedulingPolicy) -> [u32; 3] {
+        match policy {
+            SchedulingPolicy::StrictPriority => [0; 3],
+            SchedulingPolicy::Weighted {
+                control,
+                flood,
+                data,
+            } => [control, flood, data],
+        }
+    }
+
+    /// Returns the order in which the queued packets are handled
+    pub fn policy(&self) -> SchedulingPolicy {
+        self.policy
+    }
+
+    /// Returns the maximum number of fragments waiting in the data queue
+    pub fn data_capacity(&self) -> usize {
+        self.data_capacity
+    }
+
+    /// Sets the order in which the queued packets are handled
+    ///
+    /// #### Arguments
+    /// - `policy`: The scheduling policy
+    ///
+    /// > Note: the current round is restarted with the weights of the new policy
+    pub fn set_policy(&mut self, policy: SchedulingPolicy) {
+        self.policy = policy;
+        self.credits = Self::weights(policy);
+    }
+
+    /// Sets the maximum number of fragments waiting in the data queue
+    ///
+    /// #### Arguments
+    /// - `data_capacity`: The capacity of the data queue
+    ///
+    /// > Note: fragments already queued are kept, even beyond a smaller capacity
+    pub fn set_data_capacity(&mut self, data_capacity: usize) {
+        self.data_capacity = data_capacity;
+    }
+
+    /// Queues a packet in its class
+    ///
+    /// #### Errors
+    /// - If the packet is a fragment and the data queue is full, the packet is given back
+    pub fn push(&mut self, packet: Packet) -> Result<(), Box<Packet>> {
+        let class = TrafficClass::of(&packet);
+        if class == TrafficClass::Data && self.queues[class as usize].len() >= self.data_capacity {
+            self.stats.dropped += 1;
+            return Err(Box::new(packet));
+        }
+        self.queues[class as usize].push_back((packet, Instant::now()));
+        self.update_depths();
+        Ok(())
+    }
+
+    /// Takes the next packet to handle according to the policy
+    pub fn pop(&mut self) -> Option<Packet> {
+        self.pop_timed().map(|(packet, _)| packet)
+    }
+
+    /// Takes the next packet to handle according to the policy, with when it was queued
+    pub fn pop_timed(&mut self) -> Option<(Packet, Instant)> {
+        let packet = match self.policy {
+            SchedulingPolicy::StrictPriority => self.pop_first(),
+            SchedulingPolicy::Weighted { .. } => self.pop_weighted(),
+        };
+        self.update_depths();
+        packet
+    }
+
+    fn pop_first(&mut self) -> Option<(Packet, Instant)> {
+        self.queues.iter_mut().find_map(VecDeque::pop_front)
+    }
+
+    fn pop_weighted(&mut self) -> Option<(Packet, Instant)> {
+        if self.is_empty() {
+            return None;
+        }
+        for _ in 0..2 {
+            for class in 0..self.queues.len() {
+                if self.credits[class] > 0 && !self.queues[class].is_empty() {
+                    self.credits[class] -= 1;
+                    return self.queues[class].pop_front();
+                }
+            }
+            // Round over: every non-empty class spent its credits
+            self.credits = Self::weights(self.policy);
+        }
+        // Only classes with a zero weight are left
+        self.pop_first()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queues.iter().all(VecDeque::is_empty)
+    }
+
+    pub fn len(&self) -> usize {
+        self.queues.iter().map(VecDeque::len).sum()
+    }
+
+    /// Returns the current and peak depth of the queues
+    pub fn stats(&self) -> QueueStats {
+        self.stats
+    }
+
+    fn update_depths(&mut self) {
+        let depths = self.queues.each_ref().map(VecDeque::len);
+        self.stats.control_depth = depths[TrafficClass::Control as usize];
+        self.stats.flood_depth = depths[TrafficClass::Flood as usize];
+        self.stats.data_depth = depths[TrafficClass::Data as usize];
+        self.stats.peak_control_depth = self.stats.peak_control_depth.max(self.stats.control_depth);
+        self.stats.peak_flood_depth = self.stats.peak_flood_depth.max(self.stats.flood_depth);
+        self.stats.peak_data_depth = self.stats.peak_data_depth.max(self.stats.data_depth);
+    }
+}
+
+impl RustBustersDrone {
+    /// Sets the order in which the received packets are handled
+    ///
+    /// #### Arguments
+    /// - `policy`: The scheduling policy
+    pub fn set_scheduling_policy(&mut self, policy: SchedulingPolicy) {
+        self.scheduler.set_policy(policy);
+        debug!("Drone {} - Scheduling policy set to {:?}", self.id, policy);
+    }
+
+    /// Sets the maximum number of fragments waiting to be forwarded
+    ///
+    /// #### Arguments
+    /// - `capacity`: The capacity of the data queue
+    ///
+    /// > Note: fragments received while the queue is full are dropped with `Nack::Dropped`
+    pub fn set_data_queue_capacity(&mut self, capacity: usize) {
+        self.scheduler.set_data_capacity(capacity);
+        debug!(
+            "Drone {} - Data queue capacity set to {}",
+            self.id, capacity
+        );
+    }
+
+    /// Queues a received packet, dropping it if it is a fragment and the data queue is full
+    ///
+    /// #### Arguments
+    /// - `packet`: The received packet
+    ///
+    /// > Note: a misrouted fragment that finds the data queue full is handled right away,
+    /// > so that it is answered with `Nack::UnexpectedRecipient` rather than `Nack::Dropped`
+    pub(crate) fn enqueue_packet(&mut self, packet: Packet) {
+        let Some(packet) = self.apply_firewall(packet) else {
+            return;
+        };
+        let Some(packet) = self.apply_rate_limits(packet) else {
+            return;
+        };
+        let Err(rejected) = self.scheduler.push(packet) else {
+            return;
+        };
+        let hop_index = rejected.routing_header.hop_index;
+        // Misrouted fragments are left to forward_packet
+        if hop_index == 0 || rejected.routing_header.hops.get(hop_index) != Some(&self.id) {
+            self.forward_packet(*rejected, true);
+            return;
+        }
+        info!("Drone {} - Data queue full, dropping fragment", self.id);
+        self.drop_fragment(*rejected);
+    }
+}
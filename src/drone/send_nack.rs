@@ -1,11 +1,12 @@
 use super::RustBustersDrone;
+use crate::drone::middleware::{Hook, Verdict};
 #[cfg(feature = "sounds")]
-use crate::drone::sounds::sounds_feat::{DROP_SOUND, NACK_SOUND};
+use crate::drone::sounds::sounds_feat::SoundEvent;
 use log::{debug, error, info, trace, warn};
 use wg_2024::controller::DroneEvent;
 use wg_2024::controller::DroneEvent::ControllerShortcut;
 use wg_2024::network::{NodeId, SourceRoutingHeader};
-use wg_2024::packet::{Nack, Packet, PacketType};
+use wg_2024::packet::{Nack, NackType, Packet, PacketType};
 
 impl RustBustersDrone {
     /// Send a Nack packet to the sender of the given packet
@@ -43,43 +44,45 @@ impl RustBustersDrone {
 
         let source_routing_header = SourceRoutingHeader { hop_index: 1, hops };
 
-        let nack_packet = Packet {
+        let mut nack_packet = Packet {
             pack_type: PacketType::Nack(nack),
             routing_header: source_routing_header,
             session_id: packet.session_id,
         };
-
-        let next_hop = if nack_packet.routing_header.hops.len() > 1 {
-            nack_packet.routing_header.hops[1]
-        } else {
-            unreachable!(
-                "Drone {}: Error: hops len is 1 in nack {:?}",
-                self.id, nack_packet
-            );
+        if self.run_middlewares(Hook::Nack, &mut nack_packet) == Verdict::Veto {
+            return;
+        }
+        // A middleware may have changed the routing header
+        let routing_header = &nack_packet.routing_header;
+        let next_hop = match routing_header.hops.get(routing_header.hop_index) {
+            Some(&next_hop) if routing_header.hop_index > 0 => next_hop,
+            _ => {
+                warn!(
+                    "Drone {} - Dropping Nack left without a next hop by a middleware: {:?}",
+                    self.id, nack_packet
+                );
+                return;
+            }
         };
+        self.stats.nacks_sent += 1;
 
         #[cfg(feature = "sounds")]
         {
             use wg_2024::packet::NackType::Dropped;
             if nack_type == Dropped {
-                self.play_sound(DROP_SOUND);
+                self.play_sound(SoundEvent::Drop);
             } else {
-                self.play_sound(NACK_SOUND);
+                self.play_sound(SoundEvent::Nack);
             }
         }
 
-        if let Some(next_sender) = self.packet_send.get(&next_hop).cloned() {
-            if let Err(e) = next_sender.send(nack_packet.clone()) {
+        if self.packet_send.contains_key(&next_hop) {
+            if let Err(e) = self.send_to_neighbor(next_hop, nack_packet.clone()) {
                 error!(
                     "Drone {} - Error in sending Nack to {}: {}",
                     self.id, next_hop, e
                 );
-                self.packet_send.remove(&next_hop);
                 self.send_to_sc(ControllerShortcut(nack_packet.clone()));
-                warn!(
-                    "Drone {} - Neighbor {} has been removed from packet_send due to channel closure",
-                    self.id, next_hop
-                );
             } else {
                 info!(
                     "Drone {} - Forwarded Nack to next hop: {}",
@@ -96,4 +99,23 @@ impl RustBustersDrone {
             self.send_to_sc(ControllerShortcut(nack_packet.clone()));
         }
     }
+
+    /// Drops a received fragment, notifying the controller and the sender with `Nack::Dropped`
+    ///
+    /// #### Arguments
+    /// - `packet`: The received fragment, with the `hop_index` not yet incremented
+    pub(crate) fn drop_fragment(&mut self, mut packet: Packet) {
+        let PacketType::MsgFragment(ref fragment) = packet.pack_type else {
+            return;
+        };
+        let nack = Nack {
+            fragment_index: fragment.fragment_index,
+            nack_type: NackType::Dropped,
+        };
+        self.send_to_sc(DroneEvent::PacketDropped(packet.clone()));
+
+        // send_nack expects the hop_index to be already incremented
+        packet.routing_header.hop_index += 1;
+        self.send_nack(&packet, nack, true);
+    }
 }
@@ -0,0 +1,268 @@
+use crate::drone::arq::ArqSettings;
+use crate::drone::battery::{Battery, BatterySettings};
+use crate::drone::chaos::{Chaos, ChaosPlan};
+use crate::drone::crash::CrashPolicy;
+use crate::drone::dedup::DedupSettings;
+use crate::drone::firewall::FirewallConfig;
+use crate::drone::flood_trace::TracePolicy;
+use crate::drone::rate_limit::RateLimit;
+use crate::drone::reputation::{ReputationWeights, DEFAULT_HALF_LIFE};
+use crate::drone::scheduler::{SchedulingPolicy, DEFAULT_DATA_QUEUE_CAPACITY};
+use crate::drone::stats::DroneStats;
+use crate::drone::transport::{PacketLink, PacketSource};
+use crate::RustBustersDrone;
+use crossbeam_channel::{Receiver, Sender};
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use wg_2024::controller::{DroneCommand, DroneEvent};
+use wg_2024::network::NodeId;
+use wg_2024::packet::{NodeType, Packet};
+
+/// State of a drone that survives a restart
+///
+/// > Note: the channels, the queued packets, the retransmission buffer and the recently forwarded
+/// > fragments are not part of the state, see `DroneSettings` for the restored runtime settings
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DroneState {
+    pub id: NodeId,
+    /// Packet Drop Rate in percentage (0-100)
+    pub pdr: u8,
+    pub optimized_routing: bool,
+    pub hunt_mode: bool,
+    /// Floods already handled, as (flood_id, initiator_id)
+    pub received_floods: Vec<(u64, NodeId)>,
+    pub stats: DroneStats,
+    /// Scores of the nodes blamed by the relayed Nacks, the worst first
+    pub reputation: Vec<(NodeId, f64)>,
+    /// Nodes learned from the path traces, with their type
+    pub known_nodes: Vec<(NodeId, NodeType)>,
+    /// Links learned from the path traces, with their age in seconds
+    pub known_links: Vec<(NodeId, NodeId, u64)>,
+    pub settings: DroneSettings,
+}
+
+/// Runtime settings of a drone that survive a restart, as set with the `set_xxx` methods
+///
+/// > Note: a restored battery is full and a restored chaos plan starts over from its seed.
+/// > The neighbor health, telemetry, middleware and sound settings are not part of the
+/// > state, and are back to their defaults after a restart
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DroneSettings {
+    /// Maximum `path_trace` length of the spread flood requests, see `set_max_flood_path_len`
+    pub max_flood_path_len: Option<usize>,
+    /// Maximum `path_trace` lengths of single initiators, see `set_max_flood_path_len_for`
+    pub max_flood_path_len_per_initiator: Vec<(NodeId, usize)>,
+    pub neighbor_rate_limit: Option<RateLimit>,
+    /// Rate limits of single neighbors, see `set_neighbor_rate_limit_for`
+    pub neighbor_rate_limits: Vec<(NodeId, RateLimit)>,
+    pub session_rate_limit: Option<RateLimit>,
+    pub initiator_rate_limit: Option<RateLimit>,
+    /// Rate limits of single initiators, see `set_initiator_rate_limit_for`
+    pub initiator_rate_limits: Vec<(NodeId, RateLimit)>,
+    pub crash_policy: CrashPolicy,
+    pub scheduling_policy: SchedulingPolicy,
+    pub data_queue_capacity: usize,
+    pub firewall: FirewallConfig,
+    pub flood_trace_policy: TracePolicy,
+    pub duplicate_suppression: Option<DedupSettings>,
+    pub local_arq: Option<ArqSettings>,
+    pub battery: Option<BatterySettings>,
+    pub chaos_plan: Option<ChaosPlan>,
+    pub controller_reports: bool,
+    pub hunt_threshold: Option<f64>,
+    pub reputation_weights: ReputationWeights,
+    pub reputation_half_life: Duration,
+    /// Interval of the reputation reports, see `set_reputation_reports`
+    pub reputation_reports: Option<Duration>,
+}
+
+impl Default for DroneSettings {
+    fn default() -> Self {
+        Self {
+            max_flood_path_len: None,
+            max_flood_path_len_per_initiator: Vec::new(),
+            neighbor_rate_limit: None,
+            neighbor_rate_limits: Vec::new(),
+            session_rate_limit: None,
+            initiator_rate_limit: None,
+            initiator_rate_limits: Vec::new(),
+            crash_policy: CrashPolicy::default(),
+            scheduling_policy: SchedulingPolicy::StrictPriority,
+            data_queue_capacity: DEFAULT_DATA_QUEUE_CAPACITY,
+            firewall: FirewallConfig::default(),
+            flood_trace_policy: TracePolicy::default(),
+            duplicate_suppression: None,
+            local_arq: None,
+            battery: None,
+            chaos_plan: None,
+            controller_reports: true,
+            hunt_threshold: None,
+            reputation_weights: ReputationWeights::default(),
+            reputation_half_life: DEFAULT_HALF_LIFE,
+            reputation_reports: None,
+        }
+    }
+}
+
+/// Channels a restored drone is connected to
+pub struct DroneChannels<S: PacketSource> {
+    pub controller_send: Sender<DroneEvent>,
+    pub controller_recv: Receiver<DroneCommand>,
+    pub packet_source: S,
+    pub packet_send: HashMap<NodeId, Box<dyn PacketLink>>,
+}
+
+impl DroneChannels<Receiver<Packet>> {
+    /// Creates the channels of a drone connected with crossbeam channels, as in `Drone::new`
+    pub fn new(
+        controller_send: Sender<DroneEvent>,
+        controller_recv: Receiver<DroneCommand>,
+        packet_recv: Receiver<Packet>,
+        packet_send: HashMap<NodeId, Sender<Packet>>,
+    ) -> Self {
+        Self {
+            controller_send,
+            controller_recv,
+            packet_source: packet_recv,
+            packet_send: packet_send
+                .into_iter()
+                .map(|(node_id, sender)| (node_id, Box::new(sender) as Box<dyn PacketLink>))
+                .collect(),
+        }
+    }
+}
+
+impl RustBustersDrone {
+    /// Takes a snapshot of the state of the drone
+    ///
+    /// #### Returns
+    /// A serializable `DroneState`, that can be given to `RustBustersDrone::restore`
+    pub fn snapshot(&self) -> DroneState {
+        let mut received_floods: Vec<(u64, NodeId)> =
+            self.received_floods.iter().copied().collect();
+        received_floods.sort_unstable();
+        let known_links = self
+            .topology
+            .links_at(Instant::now())
+            .into_iter()
+            .map(|(a, b, age)| (a, b, age.as_secs()))
+            .collect();
+        DroneState {
+            id: self.id,
+            pdr: self.pdr,
+            optimized_routing: self.optimized_routing,
+            hunt_mode: self.hunt_mode,
+            received_floods,
+            stats: self.stats(),
+            reputation: self.suspects(),
+            known_nodes: self.topology.nodes(),
+            known_links,
+            settings: self.settings(),
+        }
+    }
+
+    /// Collects the runtime settings of the drone
+    fn settings(&self) -> DroneSettings {
+        let (max_flood_path_len, max_flood_path_len_per_initiator) = self.flood_scope.limits();
+        let (neighbor_rate_limit, neighbor_rate_limits) = self.rate_limiter.neighbor_limits();
+        let (initiator_rate_limit, initiator_rate_limits) = self.rate_limiter.initiator_limits();
+        DroneSettings {
+            max_flood_path_len,
+            max_flood_path_len_per_initiator,
+            neighbor_rate_limit,
+            neighbor_rate_limits,
+            session_rate_limit: self.rate_limiter.session_limit(),
+            initiator_rate_limit,
+            initiator_rate_limits,
+            crash_policy: self.crash_policy,
+            scheduling_policy: self.scheduler.policy(),
+            data_queue_capacity: self.scheduler.data_capacity(),
+            firewall: self.firewall.config(),
+            flood_trace_policy: self.trace_guard.policy(),
+            duplicate_suppression: self.fragment_cache.settings(),
+            local_arq: self.retransmit_buffer.settings(),
+            battery: self.battery.as_ref().map(Battery::settings),
+            chaos_plan: self.chaos.as_ref().map(Chaos::plan),
+            controller_reports: self.controller_reports,
+            hunt_threshold: self.hunt_threshold,
+            reputation_weights: self.reputation.weights(),
+            reputation_half_life: self.reputation.half_life(),
+            reputation_reports: self.reputation_reports.map(|(interval, _)| interval),
+        }
+    }
+
+    /// Applies the runtime settings of a snapshot with the `set_xxx` methods
+    fn apply_settings(&mut self, settings: DroneSettings) {
+        self.set_max_flood_path_len(settings.max_flood_path_len);
+        for (initiator_id, max) in settings.max_flood_path_len_per_initiator {
+            self.set_max_flood_path_len_for(initiator_id, Some(max));
+        }
+        self.set_neighbor_rate_limit(settings.neighbor_rate_limit);
+        for (neighbor, limit) in settings.neighbor_rate_limits {
+            self.set_neighbor_rate_limit_for(neighbor, Some(limit));
+        }
+        self.set_session_rate_limit(settings.session_rate_limit);
+        self.set_initiator_rate_limit(settings.initiator_rate_limit);
+        for (initiator, limit) in settings.initiator_rate_limits {
+            self.set_initiator_rate_limit_for(initiator, Some(limit));
+        }
+        self.set_crash_policy(settings.crash_policy);
+        self.set_scheduling_policy(settings.scheduling_policy);
+        self.set_data_queue_capacity(settings.data_queue_capacity);
+        self.set_firewall(settings.firewall);
+        self.set_flood_trace_policy(settings.flood_trace_policy);
+        self.set_duplicate_suppression(settings.duplicate_suppression);
+        self.set_local_arq(settings.local_arq);
+        self.set_battery(settings.battery);
+        self.set_chaos_plan(settings.chaos_plan);
+        self.set_controller_reports(settings.controller_reports);
+        self.set_hunt_threshold(settings.hunt_threshold);
+        self.set_reputation_weights(settings.reputation_weights);
+        self.set_reputation_half_life(settings.reputation_half_life);
+        self.set_reputation_reports(settings.reputation_reports);
+    }
+
+    /// Rebuilds a drone from a snapshot, as if it had been rebooted
+    ///
+    /// #### Arguments
+    /// - `state`: The state taken with `RustBustersDrone::snapshot`
+    /// - `channels`: The channels the restored drone is connected to
+    ///
+    /// #### Returns
+    /// A running drone with the caches, counters and runtime settings of the snapshot
+    pub fn restore<S: PacketSource>(state: DroneState, channels: DroneChannels<S>) -> Self {
+        let mut drone = Self::with_transport(
+            state.id,
+            channels.controller_send,
+            channels.controller_recv,
+            channels.packet_source,
+            channels.packet_send,
+            0.0,
+        );
+        drone.pdr = state.pdr.min(100);
+        drone.set_optimized_routing(state.optimized_routing);
+        drone.set_hunt_mode(state.hunt_mode);
+        drone.received_floods = state.received_floods.into_iter().collect();
+        drone.stats = state.stats;
+        let now = Instant::now();
+        drone.reputation.set_scores(state.reputation, now);
+        for (id, node_type) in state.known_nodes {
+            drone.topology.insert_node(id, node_type);
+        }
+        for (a, b, age) in state.known_links {
+            let seen = now.checked_sub(Duration::from_secs(age)).unwrap_or(now);
+            drone.topology.insert_link(a, b, seen);
+        }
+        drone.apply_settings(state.settings);
+        info!(
+            "Drone {} - Restored with {} known floods",
+            drone.id,
+            drone.received_floods.len()
+        );
+        drone
+    }
+}
@@ -0,0 +1,194 @@
+use crate::drone::sounds::sounds_feat::{play_sound, Sound, SoundEvent, SoundRequest};
+use crate::RustBustersDrone;
+use log::debug;
+use rodio::cpal::SampleRate;
+use rodio::source::{chirp, Function, SignalGenerator};
+use rodio::Source;
+use std::time::{Duration, Instant};
+use wg_2024::network::NodeId;
+use wg_2024::packet::PacketType;
+
+/// Sample rate of the synthesized tones
+const TONE_SAMPLE_RATE: u32 = 48_000;
+/// Time constant of the forwarding rate: the packets older than it weigh less than a third
+const RATE_TIME_CONSTANT: f64 = 1.0;
+/// Volume of the tones of an idle drone, so that a single packet is still heard
+const MIN_TONE_VOLUME: f32 = 0.1;
+
+/// Waveform of a tone, one per packet type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Timbre {
+    /// `MsgFragment`
+    Sine,
+    /// `Ack`
+    Triangle,
+    /// `Nack`
+    Sawtooth,
+    /// `FloodRequest`
+    Square,
+    /// `FloodResponse`: a sine sweeping up an octave
+    Sweep,
+}
+
+impl Timbre {
+    pub fn of(packet_type: &PacketType) -> Self {
+        match packet_type {
+            PacketType::MsgFragment(_) => Timbre::Sine,
+            PacketType::Ack(_) => Timbre::Triangle,
+            PacketType::Nack(_) => Timbre::Sawtooth,
+            PacketType::FloodRequest(_) => Timbre::Square,
+            PacketType::FloodResponse(_) => Timbre::Sweep,
+        }
+    }
+}
+
+/// A synthesized tone
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tone {
+    /// Frequency in Hz
+    pub frequency: f32,
+    pub timbre: Timbre,
+    pub duration: Duration,
+}
+
+impl Tone {
+    /// Returns the rodio source generating the tone
+    pub fn source(&self) -> Box<dyn Source<Item = f32> + Send> {
+        let sample_rate = SampleRate(TONE_SAMPLE_RATE);
+        let function = match self.timbre {
+            Timbre::Sine => Function::Sine,
+            Timbre::Triangle => Function::Triangle,
+            Timbre::Sawtooth => Function::Sawtooth,
+            Timbre::Square => Function::Square,
+            Timbre::Sweep => {
+                // the chirp keeps going past its duration: it is cut like the other tones
+                let sweep = chirp(
+                    sample_rate,
+                    self.frequency,
+                    self.frequency * 2.0,
+                    self.duration,
+                );
+                return Box::new(sweep.take_duration(self.duration));
+            }
+        };
+        Box::new(
+            SignalGenerator::new(sample_rate, self.frequency, function)
+                .take_duration(self.duration),
+        )
+    }
+}
+
+/// Settings of the sonification mode
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SonificationSettings {
+    /// Frequency of the drone with ID 0, the others go up a semitone per ID over two octaves
+    pub base_frequency: f32,
+    /// Length of a tone, the drone plays at most one tone at a time
+    pub tone_length: Duration,
+    /// Forwarding rate, in packets per second, played at full volume
+    pub full_rate: f64,
+}
+
+impl Default for SonificationSettings {
+    fn default() -> Self {
+        Self {
+            base_frequency: 220.0,
+            tone_length: Duration::from_millis(60),
+            full_rate: 50.0,
+        }
+    }
+}
+
+impl SonificationSettings {
+    /// Returns the pitch of a drone
+    pub fn pitch(&self, drone_id: NodeId) -> f32 {
+        let semitones = f32::from(drone_id % 24);
+        self.base_frequency * 2f32.powf(semitones / 12.0)
+    }
+
+    /// Returns the volume of the tones at a forwarding rate
+    pub fn volume(&self, rate: f64) -> f32 {
+        ((rate / self.full_rate) as f32).clamp(MIN_TONE_VOLUME, 1.0)
+    }
+}
+
+/// State of the sonification mode of a drone
+#[derive(Debug, Clone, Default)]
+pub struct Sonifier {
+    settings: Option<SonificationSettings>,
+    /// Exponentially decaying count of the forwarded packets, in packets per second
+    rate: f64,
+    last_packet: Option<Instant>,
+    last_tone: Option<Instant>,
+}
+
+impl Sonifier {
+    /// Records a forwarded packet
+    ///
+    /// #### Returns
+    /// The tone to play for it, if the previous one is over
+    pub fn observe(
+        &mut self,
+        drone_id: NodeId,
+        timbre: Timbre,
+        now: Instant,
+    ) -> Option<(Tone, f32)> {
+        let settings = self.settings?;
+        let elapsed = self.last_packet.map_or(0.0, |last| {
+            now.saturating_duration_since(last).as_secs_f64()
+        });
+        self.rate = self.rate * (-elapsed / RATE_TIME_CONSTANT).exp() + 1.0 / RATE_TIME_CONSTANT;
+        self.last_packet = Some(now);
+
+        if self
+            .last_tone
+            .is_some_and(|last| now.saturating_duration_since(last) < settings.tone_length)
+        {
+            return None;
+        }
+        self.last_tone = Some(now);
+        let tone = Tone {
+            frequency: settings.pitch(drone_id),
+            timbre,
+            duration: settings.tone_length,
+        };
+        Some((tone, settings.volume(self.rate)))
+    }
+
+    /// Returns the forwarding rate, in packets per second, as of the last packet
+    pub fn rate(&self) -> f64 {
+        self.rate
+    }
+}
+
+impl RustBustersDrone {
+    /// Enables the sonification mode: every packet sent to a neighbor plays a tone,
+    /// pitched by the drone ID and louder as the forwarding rate grows
+    ///
+    /// #### Arguments
+    /// - `settings`: The pitch, length and loudness of the tones, or `None` to disable it
+    ///
+    /// > Note: the tones are played only while the sounds of the drone are enabled, as `SoundEvent::Forward`
+    pub fn set_sonification(&mut self, settings: Option<SonificationSettings>) {
+        self.sonifier = Sonifier {
+            settings,
+            ..Sonifier::default()
+        };
+        debug!("Drone {} - Sonification set to {:?}", self.id, settings);
+    }
+
+    /// Plays the tone of a packet sent to a neighbor, if in sonification mode
+    pub(crate) fn sonify(&mut self, timbre: Timbre) {
+        if !self.sound_settings().plays(SoundEvent::Forward) {
+            return;
+        }
+        if let Some((tone, volume)) = self.sonifier.observe(self.id, timbre, Instant::now()) {
+            play_sound(SoundRequest {
+                drone_id: self.id,
+                event: SoundEvent::Forward,
+                sound: Sound::Tone(tone),
+                volume: volume * self.sound_settings().volume,
+            });
+        }
+    }
+}
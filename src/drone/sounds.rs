@@ -1,11 +1,20 @@
 #[cfg(feature = "sounds")]
 pub mod sounds_feat {
-    use crossbeam_channel::{unbounded, Receiver, Sender};
+    use crate::drone::sonification::Tone;
+    use crate::RustBustersDrone;
+    use crossbeam_channel::{unbounded, Receiver, RecvTimeoutError, Sender};
     use lazy_static::lazy_static;
-    use log::warn;
+    use log::{debug, warn};
     use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink};
+    use serde::Deserialize;
+    use std::collections::{HashMap, HashSet};
+    use std::fmt;
     use std::io::Cursor;
-    use std::sync::Mutex;
+    use std::path::{Path, PathBuf};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+    use wg_2024::network::NodeId;
 
     pub(crate) const SPAWN_SOUND: &[u8] = include_bytes!("../../sfx/spawn.mp3");
     pub(crate) const CRASH_SOUND: &[u8] = include_bytes!("../../sfx/crash.mp3");
@@ -13,73 +22,673 @@ pub mod sounds_feat {
     pub(crate) const DROP_SOUND: &[u8] = include_bytes!("../../sfx/drop.mp3");
     pub(crate) const HUNT_SOUND: &[u8] = include_bytes!("../../sfx/hunt.mp3");
 
+    /// How often the audio thread checks for the sounds that finished playing
+    const SINK_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
     lazy_static! {
-        static ref AUDIO_SYSTEM: Mutex<AudioSystem> = Mutex::new(AudioSystem::new());
+        static ref AUDIO_SYSTEM: Mutex<AudioSystem> = Mutex::new(AudioSystem::default());
+        static ref BUILTIN_PACK: SoundPack = SoundPack::builtin();
     }
 
-    pub struct AudioSystem {
+    /// Drone event a sound is played for
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+    #[serde(rename_all = "snake_case")]
+    pub enum SoundEvent {
+        /// The drone starts running
+        Spawn,
+        /// The drone receives `DroneCommand::Crash`
+        Crash,
+        /// The drone sends a `Nack` other than `Dropped`
+        Nack,
+        /// The drone drops a fragment
+        Drop,
+        /// The drone hunts a ghost drone
+        Hunt,
+        /// The drone spreads a `FloodRequest` to its neighbors
+        FloodSpread,
+        /// The drone does not hunt a ghost drone whose reputation is below the threshold
+        HuntSuppressed,
+        /// The drone limits a packet exceeding a rate limit
+        RateLimited,
+        /// The drone sends a packet to a neighbor, played as a tone in sonification mode
+        Forward,
+    }
+
+    impl SoundEvent {
+        /// Checks whether the sound of the event is never throttled
+        pub fn is_priority(self) -> bool {
+            matches!(self, SoundEvent::Crash | SoundEvent::Hunt)
+        }
+    }
+
+    /// Manifest of a sound pack
+    ///
+    /// ```toml
+    /// builtin = true # events missing below keep the built-in sound
+    ///
+    /// [sounds]
+    /// spawn = "spawn.wav" # relative to the manifest
+    /// flood_spread = "/home/me/sfx/whoosh.mp3"
+    /// ```
+    #[derive(Debug, Clone, Deserialize)]
+    pub struct SoundManifest {
+        #[serde(default = "default_builtin")]
+        pub builtin: bool,
+        #[serde(default)]
+        pub sounds: HashMap<SoundEvent, PathBuf>,
+    }
+
+    fn default_builtin() -> bool {
+        true
+    }
+
+    /// Sounds played for the drone events
+    #[derive(Clone, Default)]
+    pub struct SoundPack {
+        sounds: HashMap<SoundEvent, Arc<[u8]>>,
+    }
+
+    impl fmt::Debug for SoundPack {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_set().entries(self.sounds.keys()).finish()
+        }
+    }
+
+    impl SoundPack {
+        /// Returns the pack with the sounds compiled in the drone, silent on the other events
+        pub fn builtin() -> Self {
+            let mut pack = Self::default();
+            pack.set_sound(SoundEvent::Spawn, SPAWN_SOUND);
+            pack.set_sound(SoundEvent::Crash, CRASH_SOUND);
+            pack.set_sound(SoundEvent::Nack, NACK_SOUND);
+            pack.set_sound(SoundEvent::Drop, DROP_SOUND);
+            pack.set_sound(SoundEvent::Hunt, HUNT_SOUND);
+            pack
+        }
+
+        /// Loads a pack from a TOML manifest
+        ///
+        /// #### Arguments
+        /// - `path`: The path of the manifest, the relative paths of the sounds are resolved from its directory
+        ///
+        /// #### Errors
+        /// - If the manifest or one of its sounds cannot be read
+        /// - If the manifest is not valid, or one of its sounds cannot be decoded
+        pub fn from_manifest(path: impl AsRef<Path>) -> Result<Self, String> {
+            let path = path.as_ref();
+            let manifest = std::fs::read_to_string(path)
+                .map_err(|e| format!("Cannot read sound pack {}: {e}", path.display()))?;
+            let manifest: SoundManifest = toml::from_str(&manifest)
+                .map_err(|e| format!("Invalid sound pack {}: {e}", path.display()))?;
+            Self::from_parsed_manifest(manifest, path.parent().unwrap_or(Path::new("")))
+        }
+
+        /// Loads the sounds of a parsed manifest
+        ///
+        /// #### Arguments
+        /// - `manifest`: The manifest
+        /// - `base_dir`: The directory the relative paths of the sounds are resolved from
+        ///
+        /// #### Errors
+        /// - If one of the sounds cannot be read or decoded
+        pub fn from_parsed_manifest(
+            manifest: SoundManifest,
+            base_dir: &Path,
+        ) -> Result<Self, String> {
+            let mut pack = if manifest.builtin {
+                BUILTIN_PACK.clone()
+            } else {
+                Self::default()
+            };
+            for (event, file) in manifest.sounds {
+                let file = base_dir.join(file);
+                let data: Arc<[u8]> = std::fs::read(&file)
+                    .map_err(|e| format!("Cannot read sound {}: {e}", file.display()))?
+                    .into();
+                if Decoder::new(Cursor::new(data.clone())).is_err() {
+                    return Err(format!("Cannot decode sound {}", file.display()));
+                }
+                pack.sounds.insert(event, data);
+            }
+            Ok(pack)
+        }
+
+        /// Sets the sound of an event
+        pub fn set_sound(&mut self, event: SoundEvent, data: impl Into<Arc<[u8]>>) {
+            self.sounds.insert(event, data.into());
+        }
+
+        /// Removes the sound of an event, silencing it
+        pub fn remove_sound(&mut self, event: SoundEvent) {
+            self.sounds.remove(&event);
+        }
+
+        /// Returns the sound of an event, if any
+        pub fn sound(&self, event: SoundEvent) -> Option<Arc<[u8]>> {
+            self.sounds.get(&event).cloned()
+        }
+    }
+
+    /// Sound settings of a single drone
+    #[derive(Debug, Clone)]
+    pub struct SoundSettings {
+        /// Whether the drone plays any sound, `true` by default as with the `sounds` feature alone
+        pub enabled: bool,
+        /// Volume of the sounds of the drone, 1.0 being the original volume
+        pub volume: f32,
+        /// Events the drone stays silent on
+        pub muted_events: HashSet<SoundEvent>,
+        /// Sounds played for the events
+        pub pack: SoundPack,
+    }
+
+    impl Default for SoundSettings {
+        fn default() -> Self {
+            Self {
+                enabled: true,
+                volume: 1.0,
+                muted_events: HashSet::new(),
+                pack: BUILTIN_PACK.clone(),
+            }
+        }
+    }
+
+    impl SoundSettings {
+        /// Checks whether a sound is played for the event
+        pub fn plays(&self, event: SoundEvent) -> bool {
+            self.enabled && self.volume > 0.0 && !self.muted_events.contains(&event)
+        }
+    }
+
+    /// A sound to play
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum Sound {
+        /// An encoded audio file
+        Sample(Arc<[u8]>),
+        /// A synthesized tone, see `RustBustersDrone::set_sonification`
+        Tone(Tone),
+    }
+
+    impl Sound {
+        /// Returns the encoded audio file, if the sound is a sample
+        pub fn sample(&self) -> Option<&[u8]> {
+            match self {
+                Sound::Sample(data) => Some(data),
+                Sound::Tone(_) => None,
+            }
+        }
+
+        /// Returns the tone, if the sound is synthesized
+        pub fn tone(&self) -> Option<&Tone> {
+            match self {
+                Sound::Sample(_) => None,
+                Sound::Tone(tone) => Some(tone),
+            }
+        }
+    }
+
+    /// A sound requested by a drone
+    #[derive(Debug, Clone)]
+    pub struct SoundRequest {
+        pub drone_id: NodeId,
+        pub event: SoundEvent,
+        pub sound: Sound,
+        /// The volume of the sound, 1.0 being the original volume
+        pub volume: f32,
+    }
+
+    /// Output of the sounds requested by the drones
+    pub trait AudioBackend: Send {
+        /// Plays a sound, already scaled by the master volume
+        fn play(&mut self, request: SoundRequest);
+
+        /// Returns the number of sounds still playing, checked against `ThrottleSettings::max_concurrent`
+        fn active_sounds(&self) -> usize {
+            0
+        }
+    }
+
+    /// Backend playing the sounds on the default output device, through rodio
+    ///
+    /// > Note: the audio thread is spawned on the first sound, if there is no output device the sounds are discarded
+    #[derive(Default)]
+    pub struct RodioBackend {
         command_sender: Option<Sender<AudioCommand>>,
+        /// Sinks still playing, plus the commands not yet received by the audio thread
+        active: Arc<AtomicUsize>,
     }
 
-    impl AudioSystem {
-        fn new() -> Self {
+    impl RodioBackend {
+        fn spawn_audio_thread(active: Arc<AtomicUsize>) -> Sender<AudioCommand> {
             let (tx, rx) = unbounded::<AudioCommand>();
-            
+
             // Spawn del thread audio dedicato una sola volta
             std::thread::Builder::new()
                 .name("audio-thread".to_string())
-                .spawn(move || {
-                    if let Ok((stream, handle)) = OutputStream::try_default() {
-                        Self::audio_thread_loop(&rx, &stream, &handle);
+                .spawn(move || match OutputStream::try_default() {
+                    Ok((stream, handle)) => {
+                        Self::audio_thread_loop(&rx, &stream, &handle, &active);
                     }
+                    Err(e) => warn!("Error - No audio output device: {}", e),
                 })
                 .expect("Failed to spawn audio thread");
 
-            Self {
-                command_sender: Some(tx),
-            }
+            tx
         }
 
         fn audio_thread_loop(
             receiver: &Receiver<AudioCommand>,
             _stream: &OutputStream,
             handle: &OutputStreamHandle,
+            active: &AtomicUsize,
         ) {
-            while let Ok(cmd) = receiver.recv() {
-                match cmd {
-                    AudioCommand::PlaySound(sound_data) => {
+            let mut sinks: Vec<Sink> = Vec::new();
+            loop {
+                match receiver.recv_timeout(SINK_POLL_INTERVAL) {
+                    Ok(AudioCommand::PlaySound(sound, volume)) => {
                         if let Ok(sink) = Sink::try_new(handle) {
-                            let cursor = Cursor::new(sound_data);
-                            if let Ok(source) = Decoder::new(cursor) {
-                                sink.append(source);
-                                sink.detach();
-                            } else {
-                                warn!("Error - Failed to decode audio file");
+                            match sound {
+                                Sound::Sample(sound_data) => {
+                                    let cursor = Cursor::new(sound_data);
+                                    if let Ok(source) = Decoder::new(cursor) {
+                                        sink.append(source);
+                                    } else {
+                                        warn!("Error - Failed to decode audio file");
+                                        continue;
+                                    }
+                                }
+                                Sound::Tone(tone) => sink.append(tone.source()),
                             }
+                            sink.set_volume(volume);
+                            sinks.push(sink);
                         } else {
                             warn!("Error - Failed to create audio sink");
                         }
                     }
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+                sinks.retain(|sink| !sink.empty());
+                active.store(sinks.len() + receiver.len(), Ordering::Relaxed);
+            }
+        }
+    }
+
+    impl AudioBackend for RodioBackend {
+        fn play(&mut self, request: SoundRequest) {
+            let active = &self.active;
+            let sender = self
+                .command_sender
+                .get_or_insert_with(|| Self::spawn_audio_thread(Arc::clone(active)));
+            if let Err(e) = sender.send(AudioCommand::PlaySound(request.sound, request.volume)) {
+                warn!("Error - Failed to send audio command: {:?}", e);
+                return;
+            }
+            self.active.fetch_add(1, Ordering::Relaxed);
+        }
+
+        fn active_sounds(&self) -> usize {
+            self.active.load(Ordering::Relaxed)
+        }
+    }
+
+    /// Backend discarding every sound
+    #[derive(Debug, Clone, Copy, Default)]
+    pub struct NullBackend;
+
+    impl AudioBackend for NullBackend {
+        fn play(&mut self, _request: SoundRequest) {}
+    }
+
+    /// Backend keeping the requested sounds instead of playing them, for testing
+    ///
+    /// > Note: the clones share the same recording, so a clone can be kept to inspect it
+    /// > after the backend is passed to `set_audio_backend`
+    #[derive(Debug, Clone, Default)]
+    pub struct RecordingBackend {
+        requests: Arc<Mutex<Vec<SoundRequest>>>,
+    }
+
+    impl RecordingBackend {
+        /// Returns the sounds requested so far
+        pub fn requests(&self) -> Vec<SoundRequest> {
+            self.requests
+                .lock()
+                .map(|requests| requests.clone())
+                .unwrap_or_default()
+        }
+
+        /// Returns the sounds requested so far by a drone
+        pub fn requests_of(&self, drone_id: NodeId) -> Vec<SoundRequest> {
+            self.requests()
+                .into_iter()
+                .filter(|request| request.drone_id == drone_id)
+                .collect()
+        }
+
+        /// Clears the recording
+        pub fn clear(&self) {
+            if let Ok(mut requests) = self.requests.lock() {
+                requests.clear();
+            }
+        }
+    }
+
+    impl AudioBackend for RecordingBackend {
+        fn play(&mut self, request: SoundRequest) {
+            if let Ok(mut requests) = self.requests.lock() {
+                requests.push(request);
+            }
+        }
+    }
+
+    /// Limits on the sounds played together, to keep bursts of events from piling up
+    ///
+    /// > Note: the sounds of `SoundEvent::Crash` and `SoundEvent::Hunt` are never throttled
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct ThrottleSettings {
+        /// Maximum number of sounds playing at the same time, the requests beyond it are dropped
+        pub max_concurrent: usize,
+        /// Time after a sample is played in which the requests of the same event are coalesced:
+        /// the last of them is played once, when the cooldown ends
+        ///
+        /// > Note: the tones of the sonification mode are paced by each drone instead
+        pub cooldown: Duration,
+    }
+
+    impl Default for ThrottleSettings {
+        fn default() -> Self {
+            Self {
+                max_concurrent: 8,
+                cooldown: Duration::from_millis(150),
+            }
+        }
+    }
+
+    /// Counters of the sound requests
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+    pub struct AudioStats {
+        /// Requests passed to the backend
+        pub played: u64,
+        /// Requests received within the cooldown and coalesced into a single play at its end
+        pub coalesced: u64,
+        /// Requests dropped because too many sounds were playing
+        pub dropped: u64,
+    }
+
+    pub struct AudioSystem {
+        backend: Box<dyn AudioBackend>,
+        master_volume: f32,
+        muted: bool,
+        throttle: Option<ThrottleSettings>,
+        last_played: HashMap<SoundEvent, Instant>,
+        pending: HashMap<SoundEvent, SoundRequest>,
+        stats: AudioStats,
+    }
+
+    impl Default for AudioSystem {
+        fn default() -> Self {
+            Self::new(RodioBackend::default())
+        }
+    }
+
+    impl AudioSystem {
+        pub fn new(backend: impl AudioBackend + 'static) -> Self {
+            Self {
+                backend: Box::new(backend),
+                master_volume: 1.0,
+                muted: false,
+                throttle: Some(ThrottleSettings::default()),
+                last_played: HashMap::new(),
+                pending: HashMap::new(),
+                stats: AudioStats::default(),
+            }
+        }
+
+        /// Plays a sound, scaled by the master volume, unless every sound is muted or it is throttled
+        ///
+        /// #### Arguments
+        /// - `request`: The sound, with the volume requested by the drone
+        pub fn play_sound(&mut self, request: SoundRequest) {
+            self.play_sound_at(request, Instant::now());
+        }
+
+        pub(crate) fn play_sound_at(&mut self, request: SoundRequest, now: Instant) {
+            if self.muted {
+                return;
+            }
+            self.flush_at(now);
+            if let Some(throttle) = self.throttle {
+                let cooling_down = !request.event.is_priority()
+                    && matches!(request.sound, Sound::Sample(_))
+                    && self.last_played.get(&request.event).is_some_and(|&last| {
+                        now.saturating_duration_since(last) < throttle.cooldown
+                    });
+                if cooling_down {
+                    // the last request of the burst is played when the cooldown ends
+                    self.pending.insert(request.event, request);
+                    self.stats.coalesced += 1;
+                    return;
                 }
             }
+            self.play_throttled(request, now);
+        }
+
+        /// Plays the coalesced requests whose cooldown has ended
+        ///
+        /// > Note: the drones flush the coalesced requests on every tick, see `flush_sounds`
+        pub fn flush(&mut self) {
+            self.flush_at(Instant::now());
         }
 
-        pub fn play_sound(&self, sound_data: &'static [u8]) {
-            if let Some(sender) = &self.command_sender {
-                if let Err(e) = sender.send(AudioCommand::PlaySound(sound_data)) {
-                    warn!("Error - Failed to send audio command: {:?}", e);
+        pub(crate) fn flush_at(&mut self, now: Instant) {
+            let Some(throttle) = self.throttle else {
+                return;
+            };
+            let due: Vec<SoundEvent> = self
+                .pending
+                .keys()
+                .filter(|event| {
+                    self.last_played.get(event).is_none_or(|&last| {
+                        now.saturating_duration_since(last) >= throttle.cooldown
+                    })
+                })
+                .copied()
+                .collect();
+            for event in due {
+                if let Some(request) = self.pending.remove(&event) {
+                    self.play_throttled(request, now);
                 }
             }
         }
+
+        /// Plays a sound unless too many sounds are playing, and starts the cooldown of its event
+        fn play_throttled(&mut self, mut request: SoundRequest, now: Instant) {
+            if let Some(throttle) = self.throttle {
+                if !request.event.is_priority()
+                    && self.backend.active_sounds() >= throttle.max_concurrent
+                {
+                    self.stats.dropped += 1;
+                    debug!(
+                        "Audio - Dropped {:?} of drone {}: too many sounds playing",
+                        request.event, request.drone_id
+                    );
+                    return;
+                }
+                self.last_played.insert(request.event, now);
+            }
+            request.volume *= self.master_volume;
+            self.stats.played += 1;
+            self.backend.play(request);
+        }
+
+        /// Sets the limits on the sounds played together
+        ///
+        /// #### Arguments
+        /// - `throttle`: The limits, or `None` to play every sound
+        pub fn set_throttle(&mut self, throttle: Option<ThrottleSettings>) {
+            self.throttle = throttle;
+            self.last_played.clear();
+            self.pending.clear();
+        }
+
+        /// Returns the counters of the sound requests
+        pub fn stats(&self) -> AudioStats {
+            self.stats
+        }
+
+        /// Replaces the backend the sounds are played on
+        pub fn set_backend(&mut self, backend: impl AudioBackend + 'static) {
+            self.backend = Box::new(backend);
+        }
+
+        /// Sets the volume applied to the sounds of every drone
+        pub fn set_master_volume(&mut self, volume: f32) {
+            self.master_volume = volume.max(0.0);
+        }
+
+        /// Mutes or unmutes the sounds of every drone
+        pub fn set_muted(&mut self, muted: bool) {
+            self.muted = muted;
+            if muted {
+                self.pending.clear();
+            }
+        }
+    }
+
+    pub fn play_sound(request: SoundRequest) {
+        if let Ok(mut audio_system) = AUDIO_SYSTEM.lock() {
+            audio_system.play_sound(request);
+        }
+    }
+
+    /// Plays the coalesced requests of every drone in the process whose cooldown has ended
+    pub fn flush_sounds() {
+        if let Ok(mut audio_system) = AUDIO_SYSTEM.lock() {
+            audio_system.flush();
+        }
+    }
+
+    /// Sets the backend the sounds of every drone in the process are played on
+    ///
+    /// #### Arguments
+    /// - `backend`: The backend, by default `RodioBackend`
+    pub fn set_audio_backend(backend: impl AudioBackend + 'static) {
+        if let Ok(mut audio_system) = AUDIO_SYSTEM.lock() {
+            audio_system.set_backend(backend);
+        }
+    }
+
+    /// Sets the limits on the sounds played together by every drone in the process
+    ///
+    /// #### Arguments
+    /// - `throttle`: The limits, by default `ThrottleSettings::default()`, or `None` to play every sound
+    pub fn set_throttle(throttle: Option<ThrottleSettings>) {
+        if let Ok(mut audio_system) = AUDIO_SYSTEM.lock() {
+            audio_system.set_throttle(throttle);
+        }
+    }
+
+    /// Returns the counters of the sound requests of every drone in the process
+    pub fn audio_stats() -> AudioStats {
+        AUDIO_SYSTEM
+            .lock()
+            .map(|audio_system| audio_system.stats())
+            .unwrap_or_default()
+    }
+
+    /// Sets the volume applied to the sounds of every drone in the process
+    pub fn set_master_volume(volume: f32) {
+        if let Ok(mut audio_system) = AUDIO_SYSTEM.lock() {
+            audio_system.set_master_volume(volume);
+        }
     }
 
-    pub fn play_sound(sound_data: &'static [u8]) {
-        if let Ok(audio_system) = AUDIO_SYSTEM.lock() {
-            audio_system.play_sound(sound_data);
+    /// Mutes or unmutes every drone in the process
+    pub fn set_muted(muted: bool) {
+        if let Ok(mut audio_system) = AUDIO_SYSTEM.lock() {
+            audio_system.set_muted(muted);
         }
     }
 
     pub enum AudioCommand {
-        PlaySound(&'static [u8]),
+        PlaySound(Sound, f32),
+    }
+
+    impl RustBustersDrone {
+        /// Enables the sounds of the drone
+        pub fn enable_sound(&mut self) {
+            self.sound.enabled = true;
+            debug!("Drone {} - Sounds enabled", self.id);
+        }
+
+        /// Disables the sounds of the drone
+        pub fn disable_sound(&mut self) {
+            self.sound.enabled = false;
+            debug!("Drone {} - Sounds disabled", self.id);
+        }
+
+        /// Sets the volume of the sounds of the drone
+        ///
+        /// #### Arguments
+        /// - `volume`: The volume, 1.0 being the original volume
+        pub fn set_sound_volume(&mut self, volume: f32) {
+            self.sound.volume = volume.max(0.0);
+            debug!("Drone {} - Sound volume set to {}", self.id, volume);
+        }
+
+        /// Enables or disables the sound of a single event
+        ///
+        /// #### Arguments
+        /// - `event`: The event
+        /// - `enabled`: Whether a sound is played for the event
+        pub fn set_sound_event(&mut self, event: SoundEvent, enabled: bool) {
+            if enabled {
+                self.sound.muted_events.remove(&event);
+            } else {
+                self.sound.muted_events.insert(event);
+            }
+            debug!(
+                "Drone {} - Sound of {:?} {}",
+                self.id,
+                event,
+                if enabled { "enabled" } else { "disabled" }
+            );
+        }
+
+        /// Replaces all the sound settings of the drone
+        pub fn set_sound_settings(&mut self, settings: SoundSettings) {
+            debug!("Drone {} - Sound settings set to {:?}", self.id, settings);
+            self.sound = settings;
+        }
+
+        /// Sets the sounds played by the drone
+        ///
+        /// #### Arguments
+        /// - `pack`: The sound pack, see `SoundPack::from_manifest`
+        pub fn set_sound_pack(&mut self, pack: SoundPack) {
+            debug!("Drone {} - Sound pack set to {:?}", self.id, pack);
+            self.sound.pack = pack;
+        }
+
+        /// Returns the sound settings of the drone
+        pub fn sound_settings(&self) -> &SoundSettings {
+            &self.sound
+        }
+
+        /// Plays the sound of an event, if enabled in the settings of the drone
+        pub fn play_sound(&self, event: SoundEvent) {
+            if !self.sound.plays(event) {
+                return;
+            }
+            if let Some(sound) = self.sound.pack.sound(event) {
+                play_sound(SoundRequest {
+                    drone_id: self.id,
+                    event,
+                    sound: Sound::Sample(sound),
+                    volume: self.sound.volume,
+                });
+            }
+        }
     }
 }
@@ -0,0 +1,61 @@
+use super::RustBustersDrone;
+use crate::drone::latency::LatencyStats;
+use serde::{Deserialize, Serialize};
+
+/// Counters collected by the drone while running
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DroneStats {
+    /// Packets received from the network
+    pub packets_received: u64,
+    /// Fragments forwarded to the next hop
+    pub fragments_forwarded: u64,
+    /// Fragments dropped because of the Packet Drop Rate
+    pub fragments_dropped: u64,
+    /// Nacks created by the drone
+    pub nacks_sent: u64,
+    /// Fragments dropped because they exceeded a rate limit
+    pub rate_limited_fragments: u64,
+    /// Flood requests answered instead of spread because they exceeded a rate limit
+    pub rate_limited_floods: u64,
+    /// Flood requests answered instead of spread because they reached the end of their scope
+    pub scoped_floods: u64,
+    /// Fragments sent again after the next hop dropped them
+    pub retransmitted_fragments: u64,
+    /// Fragments already forwarded to the same next hop within the deduplication window
+    pub duplicate_fragments: u64,
+    /// Flood requests with an inconsistent `path_trace`
+    pub tampered_floods: u64,
+    /// Packets denied by the firewall
+    pub firewall_denied: u64,
+    /// Packets, drops and Nacks vetoed by a middleware
+    pub middleware_vetoes: u64,
+    /// Depth of the scheduler queues
+    pub queue: QueueStats,
+    /// Forwarding latency per traffic class
+    pub latency: LatencyStats,
+}
+
+/// Current and peak depth of the scheduler queues
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QueueStats {
+    pub control_depth: usize,
+    pub flood_depth: usize,
+    pub data_depth: usize,
+    pub peak_control_depth: usize,
+    pub peak_flood_depth: usize,
+    pub peak_data_depth: usize,
+    /// Fragments dropped because the data queue was full
+    pub dropped: u64,
+}
+
+impl RustBustersDrone {
+    /// Returns the counters collected by the drone
+    pub fn stats(&self) -> DroneStats {
+        DroneStats {
+            queue: self.scheduler.stats(),
+            ..self.stats.clone()
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use crate::drone::chaos::ChaosAction;
+use crate::drone::crash::ShutdownReport;
+use crate::drone::flood_trace::TraceViolation;
+use crate::drone::latency::LatencyStats;
+use crate::drone::neighbors::LinkState;
+use crate::drone::rate_limit::LimitScope;
+use crate::drone::stats::DroneStats;
+use crate::RustBustersDrone;
+use crossbeam_channel::{Sender, TrySendError};
+use log::{debug, trace};
+use std::time::{Duration, Instant};
+use wg_2024::network::NodeId;
+
+/// An event of the telemetry stream of a drone
+#[derive(Debug, Clone, PartialEq)]
+pub struct RustBustersTelemetry {
+    pub drone_id: NodeId,
+    pub event: TelemetryEvent,
+}
+
+/// What the telemetry stream tells the controller, beyond the `DroneEvent`s
+#[derive(Debug, Clone, PartialEq)]
+pub enum TelemetryEvent {
+    /// The link to a neighbor changed state
+    NeighborState { neighbor: NodeId, state: LinkState },
+    /// A route was shortened by the optimized routing
+    RouteOptimized {
+        before: Vec<NodeId>,
+        after: Vec<NodeId>,
+    },
+    /// A flood was forgotten to keep the flood cache within its capacity
+    FloodCacheEvicted { flood_id: u64, initiator_id: NodeId },
+    /// A packet exceeded a rate limit
+    RateLimited { scope: LimitScope, session_id: u64 },
+    /// A flood request with an inconsistent `path_trace` was discarded
+    TamperedFlood {
+        violation: TraceViolation,
+        flood_id: u64,
+        initiator_id: NodeId,
+        sender_id: NodeId,
+    },
+    /// A fragment dropped by the next hop was sent again
+    Retransmitted {
+        session_id: u64,
+        fragment_index: u64,
+        next_hop: NodeId,
+        retry: u8,
+    },
+    /// Periodic ranking of the nodes blamed by the relayed Nacks, the worst first
+    Reputation(Vec<(NodeId, f64)>),
+    /// A ghost drone was hunted after a `Nack::Dropped`
+    HuntFired {
+        target: NodeId,
+        /// Reputation of the target, if known
+        score: Option<f64>,
+    },
+    /// A `Nack::Dropped` did not trigger a hunt, because the target is below the reputation threshold
+    HuntSuppressed { target: NodeId, score: Option<f64> },
+    /// The battery level dropped by a step, in percent
+    Battery { level: u8 },
+    /// The chaos plan injected a fault
+    Chaos(ChaosAction),
+    /// Periodic snapshot of the counters of the drone
+    Stats(Box<DroneStats>),
+    /// The drone moved to another stage of its life
+    Lifecycle(Lifecycle),
+    /// The drone stopped after a crash, with its final counters
+    Shutdown(ShutdownReport),
+    /// The forwarding latency histograms of the drone, sent on shutdown
+    Latency(Box<LatencyStats>),
+}
+
+/// Stages of the life of a drone
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lifecycle {
+    /// The drone started running
+    Started,
+    /// The drone crashed, on `DroneCommand::Crash` or with an empty battery, and is draining the packets in flight
+    Crashing,
+    /// The drone stopped running
+    Stopped,
+}
+
+impl RustBustersDrone {
+    /// Sets the channel of the telemetry stream
+    ///
+    /// #### Arguments
+    /// - `telemetry_send`: The sender of the telemetry events, or `None` to not send them
+    ///
+    /// > Note: the events are never waited for: they are discarded while the channel is full
+    pub fn set_telemetry(&mut self, telemetry_send: Option<Sender<RustBustersTelemetry>>) {
+        debug!(
+            "Drone {} - Telemetry {}",
+            self.id,
+            if telemetry_send.is_some() {
+                "enabled"
+            } else {
+                "disabled"
+            }
+        );
+        self.telemetry = telemetry_send;
+    }
+
+    /// Sets how often a snapshot of the counters is sent on the telemetry stream
+    ///
+    /// #### Arguments
+    /// - `interval`: The interval between two snapshots, or `None` to not send them
+    pub fn set_telemetry_stats(&mut self, interval: Option<Duration>) {
+        self.telemetry_stats = interval.map(|interval| (interval, Instant::now()));
+        debug!(
+            "Drone {} - Telemetry stats interval set to {:?}",
+            self.id, interval
+        );
+    }
+
+    /// Sends an event on the telemetry stream, if enabled
+    pub(crate) fn emit(&self, event: TelemetryEvent) {
+        let Some(telemetry_send) = &self.telemetry else {
+            return;
+        };
+        let telemetry = RustBustersTelemetry {
+            drone_id: self.id,
+            event,
+        };
+        match telemetry_send.try_send(telemetry) {
+            Ok(()) => {}
+            Err(TrySendError::Full(telemetry)) => trace!(
+                "Drone {} - Telemetry channel full, discarded {:?}",
+                self.id,
+                telemetry.event
+            ),
+            Err(TrySendError::Disconnected(telemetry)) => trace!(
+                "Drone {} - Telemetry channel closed, discarded {:?}",
+                self.id,
+                telemetry.event
+            ),
+        }
+    }
+
+    /// Sends a snapshot of the counters on the telemetry stream if enabled and due
+    pub(crate) fn publish_telemetry_stats(&mut self) {
+        let Some((interval, last)) = self.telemetry_stats else {
+            return;
+        };
+        let now = Instant::now();
+        if now.saturating_duration_since(last) < interval {
+            return;
+        }
+        self.telemetry_stats = Some((interval, now));
+        self.emit(TelemetryEvent::Stats(Box::new(self.stats())));
+    }
+}
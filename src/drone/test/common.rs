@@ -1,9 +1,31 @@
 #![allow(unused)]
+use crate::drone::arq::RetransmitBuffer;
+use crate::drone::battery::Battery;
+use crate::drone::chaos::Chaos;
+use crate::drone::crash::CrashPolicy;
+use crate::drone::dedup::FragmentCache;
+use crate::drone::firewall::Firewall;
+use crate::drone::flood_cache::FloodCache;
+use crate::drone::flood_scope::FloodScope;
+use crate::drone::flood_trace::TraceGuard;
+use crate::drone::neighbors::NeighborTable;
+use crate::drone::rate_limit::RateLimiter;
+use crate::drone::reputation::ReputationTable;
+use crate::drone::scheduler::PacketScheduler;
+#[cfg(feature = "sounds")]
+use crate::drone::sonification::Sonifier;
+#[cfg(feature = "sounds")]
+use crate::drone::sounds::sounds_feat::{
+    set_audio_backend, set_throttle, RecordingBackend, SoundSettings,
+};
+use crate::drone::stats::DroneStats;
+use crate::drone::topology::TopologyMap;
 use crate::RustBustersDrone;
-use crossbeam_channel::{unbounded, Receiver, Sender};
-use std::collections::{HashMap, HashSet};
+use crossbeam_channel::{never, unbounded, Receiver, Sender};
+use std::collections::HashMap;
 use wg_2024::controller::DroneEvent;
-use wg_2024::network::NodeId;
+use wg_2024::network::{NodeId, SourceRoutingHeader};
+use wg_2024::packet::{FloodRequest, Fragment, NodeType, Packet, PacketType, FRAGMENT_DSIZE};
 
 pub const RB_DRONE_ID: NodeId = 10;
 pub const UNKNOWN_NODE: NodeId = 99;
@@ -12,7 +34,7 @@ pub fn setup_drone() -> (RustBustersDrone, Sender<DroneEvent>, Receiver<DroneEve
     let (controller_send, controller_recv) = unbounded();
     let (_cmd_send, cmd_recv) = unbounded();
     let (_packet_send_to_drone, packet_recv) = unbounded();
-    let packet_send = HashMap::new();
+    let packet_send = NeighborTable::default();
 
     let drone = RustBustersDrone {
         id: RB_DRONE_ID,
@@ -21,11 +43,93 @@ pub fn setup_drone() -> (RustBustersDrone, Sender<DroneEvent>, Receiver<DroneEve
         packet_recv,
         pdr: 10,
         packet_send,
-        received_floods: HashSet::default(),
+        received_floods: FloodCache::default(),
         optimized_routing: false,
         running: true,
         hunt_mode: false,
+        scheduler: PacketScheduler::default(),
+        rate_limiter: RateLimiter::default(),
+        flood_scope: FloodScope::default(),
+        crash_policy: CrashPolicy::default(),
+        crash_deadline: None,
+        stats: DroneStats::default(),
+        controller_reports: true,
+        tick: never(),
+        reputation: ReputationTable::default(),
+        reputation_reports: None,
+        hunt_threshold: None,
+        middlewares: Vec::new(),
+        firewall: Firewall::default(),
+        trace_guard: TraceGuard::default(),
+        fragment_cache: FragmentCache::default(),
+        retransmit_buffer: RetransmitBuffer::default(),
+        topology: TopologyMap::default(),
+        telemetry: None,
+        telemetry_stats: None,
+        battery: None,
+        chaos: None,
+        handling: None,
+        // Only the sound tests play sounds, on the recording backend
+        #[cfg(feature = "sounds")]
+        sound: SoundSettings {
+            enabled: false,
+            ..SoundSettings::default()
+        },
+        #[cfg(feature = "sounds")]
+        sonifier: Sonifier::default(),
     };
 
     (drone, controller_send, controller_recv)
 }
+
+/// Builds a full fragment of a 10 fragments message, received by the drone at `hops[1]`
+pub fn fragment(index: u64, session_id: u64, hops: Vec<NodeId>) -> Packet {
+    Packet {
+        pack_type: PacketType::MsgFragment(Fragment {
+            fragment_index: index,
+            total_n_fragments: 10,
+            data: [0; FRAGMENT_DSIZE],
+            length: FRAGMENT_DSIZE as u8,
+        }),
+        routing_header: SourceRoutingHeader { hop_index: 1, hops },
+        session_id,
+    }
+}
+
+/// Builds a flood request, sent by the last node of `path_trace`
+pub fn flood_request(
+    flood_id: u64,
+    initiator_id: NodeId,
+    path_trace: Vec<(NodeId, NodeType)>,
+) -> Packet {
+    Packet {
+        pack_type: PacketType::FloodRequest(FloodRequest {
+            flood_id,
+            initiator_id,
+            path_trace,
+        }),
+        routing_header: SourceRoutingHeader {
+            hop_index: 1,
+            hops: vec![],
+        },
+        session_id: 0,
+    }
+}
+
+#[cfg(feature = "sounds")]
+lazy_static::lazy_static! {
+    // The audio backend is global: the sound tests record on it each with its own drone ID,
+    // with no throttling so the requests of the tests do not coalesce
+    static ref RECORDER: RecordingBackend = {
+        let recorder = RecordingBackend::default();
+        set_audio_backend(recorder.clone());
+        set_throttle(None);
+        recorder
+    };
+}
+
+/// Returns the recording backend installed for the sound tests
+#[cfg(feature = "sounds")]
+pub fn sound_recorder() -> &'static RecordingBackend {
+    &RECORDER
+}
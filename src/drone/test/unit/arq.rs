@@ -0,0 +1,178 @@
+#[cfg(test)]
+mod retransmission {
+    use crate::arq::ArqSettings;
+    use crate::drone::test::common::{fragment, setup_drone, RB_DRONE_ID};
+    use crate::hunt::PACKET_CONST;
+    use crate::report::{Report, ReportKind};
+    use crate::telemetry::TelemetryEvent;
+    use crate::RustBustersDrone;
+    use crossbeam_channel::{unbounded, Receiver};
+    use wg_2024::controller::DroneEvent;
+    use wg_2024::network::{NodeId, SourceRoutingHeader};
+    use wg_2024::packet::{Nack, NackType, Packet, PacketType};
+
+    fn dropped_nack(index: u64, dropped_by: NodeId) -> Packet {
+        Packet {
+            pack_type: PacketType::Nack(Nack {
+                fragment_index: index,
+                nack_type: NackType::Dropped,
+            }),
+            routing_header: SourceRoutingHeader {
+                hop_index: 1,
+                hops: vec![dropped_by, RB_DRONE_ID, 1],
+            },
+            session_id: 5,
+        }
+    }
+
+    fn setup() -> (
+        RustBustersDrone,
+        Receiver<DroneEvent>,
+        Receiver<Packet>,
+        Receiver<Packet>,
+    ) {
+        let (mut drone, _, controller_recv) = setup_drone();
+        drone.pdr = 0;
+        let (neighbor_1_sender, neighbor_1_receiver) = unbounded();
+        let (neighbor_2_sender, neighbor_2_receiver) = unbounded();
+        drone.packet_send.insert(1, Box::new(neighbor_1_sender));
+        drone.packet_send.insert(2, Box::new(neighbor_2_sender));
+        (
+            drone,
+            controller_recv,
+            neighbor_1_receiver,
+            neighbor_2_receiver,
+        )
+    }
+
+    #[test]
+    fn test_disabled_by_default() {
+        let (mut drone, _, neighbor_1_receiver, neighbor_2_receiver) = setup();
+
+        drone.forward_packet(fragment(3, 5, vec![1, RB_DRONE_ID, 2, 3]), true);
+        drone.forward_packet(dropped_nack(3, 2), true);
+        assert_eq!(neighbor_2_receiver.try_iter().count(), 1);
+        assert_eq!(neighbor_1_receiver.try_iter().count(), 1);
+        assert!(drone.retransmit_buffer.is_empty());
+    }
+
+    #[test]
+    fn test_dropped_fragment_is_sent_again_before_nack() {
+        let (mut drone, controller_recv, neighbor_1_receiver, neighbor_2_receiver) = setup();
+        drone.set_local_arq(Some(ArqSettings {
+            max_retries: 1,
+            ..ArqSettings::default()
+        }));
+        let (telemetry_send, telemetry_recv) = unbounded();
+        drone.set_telemetry(Some(telemetry_send));
+
+        drone.forward_packet(fragment(3, 5, vec![1, RB_DRONE_ID, 2, 3]), true);
+        assert_eq!(neighbor_2_receiver.try_iter().count(), 1);
+        controller_recv.try_iter().for_each(drop);
+
+        // the first Nack is absorbed and the fragment sent again
+        drone.forward_packet(dropped_nack(3, 2), true);
+        let retransmitted = neighbor_2_receiver.try_recv().unwrap();
+        assert_eq!(retransmitted.routing_header.hop_index, 2);
+        assert!(neighbor_1_receiver.try_recv().is_err());
+        assert_eq!(drone.stats().retransmitted_fragments, 1);
+
+        let events: Vec<DroneEvent> = controller_recv.try_iter().collect();
+        assert!(events.iter().any(|event| matches!(
+            event,
+            DroneEvent::PacketSent(packet) if matches!(packet.pack_type, PacketType::MsgFragment(ref f) if f.fragment_index == 3)
+        )));
+        let report = events
+            .iter()
+            .find_map(|event| match event {
+                DroneEvent::PacketSent(packet) => Report::parse(packet),
+                _ => None,
+            })
+            .expect("Retransmitted report not sent");
+        assert_eq!(report.kind, ReportKind::Retransmitted);
+        assert_eq!(report.payload[..2], [2, 1]);
+        assert_eq!(report.payload[2..10], 5u64.to_le_bytes());
+        assert_eq!(report.payload[10..18], 3u64.to_le_bytes());
+        assert_eq!(
+            telemetry_recv.try_recv().map(|telemetry| telemetry.event),
+            Ok(TelemetryEvent::Retransmitted {
+                session_id: 5,
+                fragment_index: 3,
+                next_hop: 2,
+                retry: 1,
+            })
+        );
+
+        // out of retries: the Nack goes on to the source
+        drone.forward_packet(dropped_nack(3, 2), true);
+        assert!(neighbor_2_receiver.try_recv().is_err());
+        assert!(neighbor_1_receiver.try_recv().is_ok());
+        assert!(drone.retransmit_buffer.is_empty());
+    }
+
+    #[test]
+    fn test_retransmission_comes_before_hunt() {
+        let (mut drone, controller_recv, neighbor_1_receiver, neighbor_2_receiver) = setup();
+        drone.set_local_arq(Some(ArqSettings {
+            max_retries: 1,
+            ..ArqSettings::default()
+        }));
+        drone.set_hunt_mode(true);
+        let hunts = |controller_recv: &Receiver<DroneEvent>| {
+            controller_recv
+                .try_iter()
+                .filter(|event| match event {
+                    DroneEvent::PacketSent(packet) => matches!(
+                        &packet.pack_type,
+                        PacketType::MsgFragment(fragment) if fragment.length == PACKET_CONST
+                    ),
+                    _ => false,
+                })
+                .count()
+        };
+
+        drone.forward_packet(fragment(3, 5, vec![1, RB_DRONE_ID, 2, 3]), true);
+        neighbor_2_receiver.try_iter().for_each(drop);
+        controller_recv.try_iter().for_each(drop);
+
+        // sent again: nobody is hunted
+        drone.forward_packet(dropped_nack(3, 2), true);
+        assert!(neighbor_2_receiver.try_recv().is_ok());
+        assert_eq!(hunts(&controller_recv), 0);
+
+        // out of retries: the drop is final and hunted
+        drone.forward_packet(dropped_nack(3, 2), true);
+        assert!(neighbor_1_receiver.try_recv().is_ok());
+        assert_eq!(hunts(&controller_recv), 1);
+    }
+
+    #[test]
+    fn test_nack_from_further_hop_is_forwarded() {
+        let (mut drone, _, neighbor_1_receiver, neighbor_2_receiver) = setup();
+        drone.set_local_arq(Some(ArqSettings::default()));
+
+        drone.forward_packet(fragment(3, 5, vec![1, RB_DRONE_ID, 2, 3]), true);
+        neighbor_2_receiver.try_iter().for_each(drop);
+
+        // dropped by 3, after the next hop: not retransmitted here
+        drone.forward_packet(dropped_nack(3, 3), true);
+        assert!(neighbor_2_receiver.try_recv().is_err());
+        assert!(neighbor_1_receiver.try_recv().is_ok());
+    }
+
+    #[test]
+    fn test_buffer_is_bounded() {
+        let (mut drone, _, _neighbor_1_receiver, _neighbor_2_receiver) = setup();
+        drone.set_local_arq(Some(ArqSettings {
+            capacity: 2,
+            ..ArqSettings::default()
+        }));
+
+        for index in 0..4 {
+            drone.forward_packet(fragment(index, 5, vec![1, RB_DRONE_ID, 2, 3]), true);
+        }
+        assert_eq!(drone.retransmit_buffer.len(), 2);
+        assert!(drone.retransmit_buffer.retry((5, 0), 2).is_none());
+        assert!(drone.retransmit_buffer.retry((5, 3), 2).is_some());
+    }
+}
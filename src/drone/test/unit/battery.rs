@@ -0,0 +1,101 @@
+#[cfg(test)]
+mod energy {
+    use crate::battery::BatterySettings;
+    use crate::drone::test::common::{fragment, setup_drone, RB_DRONE_ID};
+    use crate::report::{Report, ReportKind};
+    use crossbeam_channel::{unbounded, Receiver};
+    use std::time::{Duration, Instant};
+    use wg_2024::controller::DroneEvent;
+    use wg_2024::packet::{Ack, Packet, PacketType};
+
+    fn settings() -> BatterySettings {
+        BatterySettings {
+            capacity: 10.0,
+            fragment_cost: 1.0,
+            control_cost: 0.5,
+            flood_cost: 0.5,
+            receive_cost: 0.0,
+            idle_drain: 0.0,
+            report_step: 10,
+        }
+    }
+
+    fn battery_reports(controller_recv: &Receiver<DroneEvent>) -> Vec<u8> {
+        controller_recv
+            .try_iter()
+            .filter_map(|event| match event {
+                DroneEvent::PacketSent(packet) => Report::parse(&packet),
+                _ => None,
+            })
+            .filter(|report| report.kind == ReportKind::Battery)
+            .map(|report| report.payload[0])
+            .collect()
+    }
+
+    #[test]
+    fn test_traffic_drains_the_battery() {
+        let (mut drone, _, controller_recv) = setup_drone();
+        drone.pdr = 0;
+        let (neighbor_2_sender, _neighbor_2_receiver) = unbounded();
+        drone.packet_send.insert(2, Box::new(neighbor_2_sender));
+        assert_eq!(drone.battery_level(), None);
+        drone.set_battery(Some(settings()));
+        assert_eq!(drone.battery_level(), Some(100.0));
+
+        drone.forward_packet(fragment(0, 3, vec![1, RB_DRONE_ID, 2]), true);
+        drone.forward_packet(fragment(1, 3, vec![1, RB_DRONE_ID, 2]), true);
+        drone.forward_packet(
+            Packet {
+                pack_type: PacketType::Ack(Ack { fragment_index: 0 }),
+                ..fragment(0, 3, vec![1, RB_DRONE_ID, 2])
+            },
+            true,
+        );
+        assert_eq!(drone.battery_level(), Some(75.0));
+        // a report every 10 percentage points
+        assert_eq!(battery_reports(&controller_recv), vec![90, 80]);
+        assert!(drone.running);
+    }
+
+    #[test]
+    fn test_empty_battery_crashes_the_drone() {
+        let (mut drone, _, controller_recv) = setup_drone();
+        drone.set_battery(Some(BatterySettings {
+            receive_cost: 4.0,
+            report_step: 50,
+            ..settings()
+        }));
+        let (_packet_send, packet_recv) = unbounded();
+        drone.packet_recv = packet_recv;
+
+        for _ in 0..2 {
+            drone.on_packet(Ok(fragment(0, 3, vec![1, RB_DRONE_ID, 2])));
+        }
+        assert_eq!(battery_reports(&controller_recv), vec![20]);
+        assert!(drone.running);
+
+        drone.on_packet(Ok(fragment(0, 3, vec![1, RB_DRONE_ID, 2])));
+        assert_eq!(drone.battery_level(), Some(0.0));
+        assert_eq!(battery_reports(&controller_recv), vec![0]);
+        assert!(!drone.running);
+        assert!(drone.crash_deadline.is_some());
+    }
+
+    #[test]
+    fn test_idle_drain() {
+        let (mut drone, _, controller_recv) = setup_drone();
+        drone.set_battery(Some(BatterySettings {
+            idle_drain: 1.0,
+            ..settings()
+        }));
+        let start = Instant::now();
+        drone.drain_battery_at(start + Duration::from_secs(5));
+        let level = drone.battery_level().unwrap();
+        assert!((40.0..=50.0).contains(&level), "{level}");
+        assert_eq!(battery_reports(&controller_recv).len(), 1);
+
+        drone.drain_battery_at(start + Duration::from_secs(20));
+        assert_eq!(drone.battery_level(), Some(0.0));
+        assert!(!drone.running);
+    }
+}
@@ -0,0 +1,174 @@
+#[cfg(test)]
+mod faults {
+    use crate::chaos::{Chaos, ChaosAction, ChaosPlan, ChaosStep, ChaosTrigger};
+    use crate::drone::test::common::{fragment, setup_drone, RB_DRONE_ID};
+    use crate::report::{Report, ReportKind};
+    use crossbeam_channel::{unbounded, Receiver};
+    use std::time::{Duration, Instant};
+    use wg_2024::controller::DroneEvent;
+    use wg_2024::packet::{NackType, Packet, PacketType};
+
+    fn plan(trigger: ChaosTrigger, action: ChaosAction) -> ChaosPlan {
+        ChaosPlan {
+            seed: 7,
+            steps: vec![ChaosStep {
+                trigger,
+                probability: 1.0,
+                action,
+            }],
+        }
+    }
+
+    fn chaos_reports(controller_recv: &Receiver<DroneEvent>) -> Vec<Vec<u8>> {
+        controller_recv
+            .try_iter()
+            .filter_map(|event| match event {
+                DroneEvent::PacketSent(packet) => Report::parse(&packet),
+                _ => None,
+            })
+            .filter(|report| report.kind == ReportKind::Chaos)
+            .map(|report| report.payload[..6].to_vec())
+            .collect()
+    }
+
+    fn nack_type(neighbor_1_receiver: &Receiver<Packet>) -> NackType {
+        match neighbor_1_receiver
+            .try_recv()
+            .expect("Nack not sent")
+            .pack_type
+        {
+            PacketType::Nack(nack) => nack.nack_type,
+            pack_type => panic!("Unexpected packet: {pack_type:?}"),
+        }
+    }
+
+    #[test]
+    fn test_crash_after_packets() {
+        let (mut drone, _, controller_recv) = setup_drone();
+        let (_packet_send, packet_recv) = unbounded();
+        drone.packet_recv = packet_recv;
+        drone.set_chaos_plan(Some(plan(
+            ChaosTrigger::AfterPackets(3),
+            ChaosAction::Crash,
+        )));
+
+        drone.on_packet(Ok(fragment(4, 9, vec![1, RB_DRONE_ID, 2])));
+        drone.on_packet(Ok(fragment(4, 9, vec![1, RB_DRONE_ID, 2])));
+        assert!(drone.running);
+        drone.on_packet(Ok(fragment(4, 9, vec![1, RB_DRONE_ID, 2])));
+        assert!(!drone.running);
+        assert!(drone.crash_deadline.is_some());
+        assert_eq!(
+            chaos_reports(&controller_recv),
+            vec![vec![0, 0, 0, 0, 0, 0]]
+        );
+    }
+
+    #[test]
+    fn test_link_flap() {
+        let (mut drone, _, controller_recv) = setup_drone();
+        drone.pdr = 0;
+        let (neighbor_1_sender, neighbor_1_receiver) = unbounded();
+        let (neighbor_2_sender, neighbor_2_receiver) = unbounded();
+        drone.packet_send.insert(1, Box::new(neighbor_1_sender));
+        drone.packet_send.insert(2, Box::new(neighbor_2_sender));
+        let flap = ChaosAction::LinkFlap {
+            neighbor: 2,
+            duration: Duration::from_secs(3600),
+        };
+        drone.set_chaos_plan(Some(plan(ChaosTrigger::AfterTime(Duration::ZERO), flap)));
+        drone.run_chaos_at(Instant::now());
+        assert_eq!(chaos_reports(&controller_recv), vec![flap.encode()]);
+
+        drone.forward_packet(fragment(4, 9, vec![1, RB_DRONE_ID, 2]), true);
+        assert!(neighbor_2_receiver.try_recv().is_err());
+        assert_eq!(nack_type(&neighbor_1_receiver), NackType::ErrorInRouting(2));
+
+        // the link is back once the plan is removed
+        drone.set_chaos_plan(None);
+        drone.forward_packet(fragment(4, 9, vec![1, RB_DRONE_ID, 2]), true);
+        assert!(neighbor_2_receiver.try_recv().is_ok());
+    }
+
+    #[test]
+    fn test_raised_pdr() {
+        let (mut drone, _, _) = setup_drone();
+        drone.pdr = 0;
+        let (neighbor_1_sender, neighbor_1_receiver) = unbounded();
+        let (neighbor_2_sender, neighbor_2_receiver) = unbounded();
+        drone.packet_send.insert(1, Box::new(neighbor_1_sender));
+        drone.packet_send.insert(2, Box::new(neighbor_2_sender));
+        drone.set_chaos_plan(Some(plan(
+            ChaosTrigger::AfterTime(Duration::ZERO),
+            ChaosAction::RaisePdr {
+                pdr: 100,
+                duration: Duration::from_secs(3600),
+            },
+        )));
+        drone.run_chaos_at(Instant::now());
+
+        drone.forward_packet(fragment(4, 9, vec![1, RB_DRONE_ID, 2]), true);
+        assert!(neighbor_2_receiver.try_recv().is_err());
+        assert_eq!(nack_type(&neighbor_1_receiver), NackType::Dropped);
+        assert_eq!(drone.pdr, 0);
+    }
+
+    #[test]
+    fn test_same_seed_same_faults() {
+        let flaps = |seed| {
+            let start = Instant::now();
+            let mut chaos = Chaos::new(
+                ChaosPlan {
+                    seed,
+                    steps: vec![ChaosStep {
+                        trigger: ChaosTrigger::Every(Duration::from_secs(1)),
+                        probability: 0.5,
+                        action: ChaosAction::LinkFlap {
+                            neighbor: 2,
+                            duration: Duration::from_millis(500),
+                        },
+                    }],
+                },
+                start,
+            );
+            (1..=64)
+                .map(|second| {
+                    let now = start + Duration::from_secs(second);
+                    !chaos.fire(now).is_empty() && chaos.is_flapping(2, now)
+                })
+                .collect::<Vec<bool>>()
+        };
+
+        let first = flaps(1);
+        assert_eq!(first, flaps(1));
+        assert_ne!(first, flaps(2));
+        let count = first.iter().filter(|&&flap| flap).count();
+        assert!((10..=54).contains(&count), "{count}");
+    }
+
+    #[test]
+    fn test_non_finite_probability_never_fires() {
+        let start = Instant::now();
+        let step = |probability| ChaosStep {
+            trigger: ChaosTrigger::AfterPackets(0),
+            probability,
+            action: ChaosAction::Crash,
+        };
+        let mut chaos = Chaos::new(
+            ChaosPlan {
+                seed: 7,
+                steps: vec![step(f64::NAN), step(f64::INFINITY), step(2.0)],
+            },
+            start,
+        );
+
+        assert_eq!(chaos.fire(start), vec![ChaosAction::Crash]);
+        let probabilities: Vec<f64> = chaos
+            .plan()
+            .steps
+            .iter()
+            .map(|step| step.probability)
+            .collect();
+        assert_eq!(probabilities, vec![0.0, 0.0, 1.0]);
+    }
+}
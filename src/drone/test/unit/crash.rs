@@ -0,0 +1,131 @@
+#[cfg(test)]
+mod shutdown {
+    use crate::crash::{CrashPolicy, InFlightPolicy, ShutdownReport};
+    use crate::drone::test::common::{fragment, setup_drone, RB_DRONE_ID};
+    use crate::report::{Report, ReportKind};
+    use crate::telemetry::TelemetryEvent;
+    use crossbeam_channel::{unbounded, TryRecvError};
+    use std::time::Duration;
+    use wg_2024::controller::{DroneCommand, DroneEvent};
+    use wg_2024::drone::Drone;
+    use wg_2024::packet::{NackType, PacketType};
+
+    #[test]
+    fn test_crash_sets_drain_deadline() {
+        let (mut drone, _, _) = setup_drone();
+        drone.set_crash_policy(CrashPolicy {
+            drain_timeout: Duration::from_secs(60),
+            ..CrashPolicy::default()
+        });
+
+        drone.handle_command(DroneCommand::Crash);
+        assert!(!drone.running);
+        assert!(drone.crash_deadline.is_some());
+        assert!(!drone.drain_expired());
+    }
+
+    #[test]
+    fn test_in_flight_fragment_shortcut() {
+        let (mut drone, _, controller_recv) = setup_drone();
+        let (neighbor_2_sender, neighbor_2_receiver) = unbounded();
+        drone.packet_send.insert(2, Box::new(neighbor_2_sender));
+        drone.set_crash_policy(CrashPolicy {
+            in_flight: InFlightPolicy::Shortcut,
+            ..CrashPolicy::default()
+        });
+        drone.handle_command(DroneCommand::Crash);
+
+        drone.forward_packet(fragment(3, 7, vec![1, RB_DRONE_ID, 2]), true);
+
+        match controller_recv.try_recv() {
+            Ok(DroneEvent::ControllerShortcut(packet)) => {
+                assert_eq!(packet.routing_header.hop_index, 2);
+                assert!(matches!(packet.pack_type, PacketType::MsgFragment(_)));
+            }
+            other => panic!("Unexpected event: {other:?}"),
+        }
+        assert!(neighbor_2_receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_run_stops_at_drain_deadline_with_final_report() {
+        let (mut drone, _, controller_recv) = setup_drone();
+        let (command_send, command_recv) = unbounded();
+        let (packet_send, packet_recv) = unbounded();
+        drone.controller_recv = command_recv;
+        drone.packet_recv = packet_recv;
+        let (neighbor_1_sender, neighbor_1_receiver) = unbounded();
+        drone.packet_send.insert(1, Box::new(neighbor_1_sender));
+        drone.set_crash_policy(CrashPolicy {
+            drain_timeout: Duration::ZERO,
+            ..CrashPolicy::default()
+        });
+
+        command_send.send(DroneCommand::Crash).unwrap();
+        for i in 0..3 {
+            packet_send
+                .send(fragment(i, 7, vec![1, RB_DRONE_ID, 2]))
+                .unwrap();
+        }
+        drone.run();
+
+        // The first fragment is handled before the deadline check
+        match neighbor_1_receiver.try_recv().map(|p| p.pack_type) {
+            Ok(PacketType::Nack(nack)) => {
+                assert_eq!(nack.fragment_index, 0);
+                assert_eq!(nack.nack_type, NackType::ErrorInRouting(RB_DRONE_ID));
+            }
+            other => panic!("Unexpected packet: {other:?}"),
+        }
+        // The sender was dropped when the drone stopped
+        assert!(matches!(
+            neighbor_1_receiver.try_recv(),
+            Err(TryRecvError::Disconnected)
+        ));
+        assert!(drone.packet_send.is_empty());
+
+        let report = controller_recv
+            .try_iter()
+            .filter_map(|event| match event {
+                DroneEvent::PacketSent(packet) => Report::parse(&packet),
+                _ => None,
+            })
+            .find(|report| report.kind == ReportKind::Shutdown)
+            .expect("Shutdown report not sent");
+        let shutdown = ShutdownReport::decode(&report.payload).unwrap();
+        assert_eq!(shutdown.packets_received, 3);
+        assert_eq!(shutdown.nacks_sent, 1);
+        assert_eq!(shutdown.abandoned, 2);
+    }
+
+    #[test]
+    fn test_final_report_on_telemetry() {
+        let (mut drone, _, _) = setup_drone();
+        let (telemetry_send, telemetry_recv) = unbounded();
+        drone.set_telemetry(Some(telemetry_send));
+        drone.begin_crash();
+        drone.finish_crash();
+
+        let shutdown = telemetry_recv
+            .try_iter()
+            .find_map(|telemetry| match telemetry.event {
+                TelemetryEvent::Shutdown(report) => Some(report),
+                _ => None,
+            })
+            .expect("Shutdown event not sent");
+        assert_eq!(shutdown, ShutdownReport::default());
+    }
+
+    #[test]
+    fn test_shutdown_report_round_trip() {
+        let report = ShutdownReport {
+            packets_received: 10,
+            fragments_forwarded: 7,
+            fragments_dropped: 2,
+            nacks_sent: 1,
+            abandoned: u64::MAX,
+        };
+        assert_eq!(ShutdownReport::decode(&report.encode()), Some(report));
+        assert_eq!(ShutdownReport::decode(&[0; 16]), None);
+    }
+}
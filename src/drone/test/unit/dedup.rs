@@ -0,0 +1,190 @@
+#[cfg(test)]
+mod duplicates {
+    use crate::dedup::{DedupSettings, DuplicatePolicy, FragmentCache};
+    use crate::drone::test::common::{fragment, setup_drone, RB_DRONE_ID};
+    use crossbeam_channel::unbounded;
+    use std::time::{Duration, Instant};
+    use wg_2024::controller::DroneEvent;
+    use wg_2024::network::SourceRoutingHeader;
+    use wg_2024::packet::{Ack, Nack, NackType, Packet, PacketType};
+
+    /// Ack of a fragment, coming back from node 2
+    fn ack(index: u64) -> Packet {
+        Packet {
+            pack_type: PacketType::Ack(Ack {
+                fragment_index: index,
+            }),
+            routing_header: SourceRoutingHeader {
+                hop_index: 1,
+                hops: vec![2, RB_DRONE_ID, 1],
+            },
+            session_id: 5,
+        }
+    }
+
+    /// Nack of a fragment dropped by node 2
+    fn dropped_nack(index: u64) -> Packet {
+        Packet {
+            pack_type: PacketType::Nack(Nack {
+                fragment_index: index,
+                nack_type: NackType::Dropped,
+            }),
+            routing_header: SourceRoutingHeader {
+                hop_index: 1,
+                hops: vec![2, RB_DRONE_ID, 1],
+            },
+            session_id: 5,
+        }
+    }
+
+    fn settings(policy: DuplicatePolicy) -> DedupSettings {
+        DedupSettings {
+            policy,
+            window: Duration::from_secs(60),
+            capacity: 16,
+        }
+    }
+
+    #[test]
+    fn test_cache_window_and_capacity() {
+        let (mut drone, _, _) = setup_drone();
+        drone.set_duplicate_suppression(Some(DedupSettings {
+            window: Duration::from_secs(1),
+            capacity: 2,
+            ..DedupSettings::default()
+        }));
+        let cache: &mut FragmentCache = &mut drone.fragment_cache;
+        let start = Instant::now();
+
+        cache.record((5, 0), 2, start);
+        assert!(cache.is_duplicate((5, 0), 2, start));
+        // another next hop is not a duplicate
+        assert!(!cache.is_duplicate((5, 0), 3, start));
+
+        // the oldest fragment is forgotten first
+        cache.record((5, 1), 2, start);
+        cache.record((5, 2), 2, start);
+        assert_eq!(cache.len(), 2);
+        assert!(!cache.is_duplicate((5, 0), 2, start));
+        assert!(cache.is_duplicate((5, 1), 2, start));
+
+        // and everything expires after the window
+        assert!(!cache.is_duplicate((5, 2), 2, start + Duration::from_secs(2)));
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_disabled_by_default() {
+        let (mut drone, _, _) = setup_drone();
+        drone.pdr = 0;
+        let (neighbor_2_sender, neighbor_2_receiver) = unbounded();
+        drone.packet_send.insert(2, Box::new(neighbor_2_sender));
+
+        drone.forward_packet(fragment(0, 5, vec![1, RB_DRONE_ID, 2]), true);
+        drone.forward_packet(fragment(0, 5, vec![1, RB_DRONE_ID, 2]), true);
+        assert_eq!(neighbor_2_receiver.try_iter().count(), 2);
+        assert!(drone.fragment_cache.is_empty());
+        assert_eq!(drone.stats().duplicate_fragments, 0);
+    }
+
+    #[test]
+    fn test_forward_policy_counts_duplicates() {
+        let (mut drone, _, _) = setup_drone();
+        drone.pdr = 0;
+        let (neighbor_2_sender, neighbor_2_receiver) = unbounded();
+        drone.packet_send.insert(2, Box::new(neighbor_2_sender));
+        drone.set_duplicate_suppression(Some(settings(DuplicatePolicy::Forward)));
+
+        drone.forward_packet(fragment(0, 5, vec![1, RB_DRONE_ID, 2]), true);
+        drone.forward_packet(fragment(0, 5, vec![1, RB_DRONE_ID, 2]), true);
+        drone.forward_packet(fragment(1, 5, vec![1, RB_DRONE_ID, 2]), true);
+        assert_eq!(neighbor_2_receiver.try_iter().count(), 3);
+        assert_eq!(drone.stats().duplicate_fragments, 1);
+    }
+
+    #[test]
+    fn test_suppress_policy_acks_delivered_fragments_through_controller() {
+        let (mut drone, _, controller_recv) = setup_drone();
+        drone.pdr = 0;
+        let (neighbor_1_sender, neighbor_1_receiver) = unbounded();
+        let (neighbor_2_sender, neighbor_2_receiver) = unbounded();
+        drone.packet_send.insert(1, Box::new(neighbor_1_sender));
+        drone.packet_send.insert(2, Box::new(neighbor_2_sender));
+        drone.set_duplicate_suppression(Some(settings(DuplicatePolicy::Suppress)));
+
+        drone.forward_packet(fragment(3, 5, vec![1, RB_DRONE_ID, 2]), true);
+        drone.forward_packet(ack(3), true);
+        assert!(neighbor_1_receiver.try_recv().is_ok());
+        controller_recv.try_iter().for_each(drop);
+        drone.forward_packet(fragment(3, 5, vec![1, RB_DRONE_ID, 2]), true);
+        assert_eq!(neighbor_2_receiver.try_iter().count(), 1);
+        assert_eq!(drone.stats().duplicate_fragments, 1);
+
+        let ack = controller_recv
+            .try_iter()
+            .find_map(|event| match event {
+                DroneEvent::ControllerShortcut(packet) => Some(packet),
+                _ => None,
+            })
+            .expect("Ack not sent to the controller");
+        assert!(matches!(
+            ack.pack_type,
+            PacketType::Ack(ack) if ack.fragment_index == 3
+        ));
+        assert_eq!(ack.routing_header.hops, vec![RB_DRONE_ID, 1]);
+        assert_eq!(ack.session_id, 5);
+    }
+
+    #[test]
+    fn test_suppress_policy_nacks_fragments_in_flight() {
+        let (mut drone, _, controller_recv) = setup_drone();
+        drone.pdr = 0;
+        let (neighbor_2_sender, neighbor_2_receiver) = unbounded();
+        drone.packet_send.insert(2, Box::new(neighbor_2_sender));
+        drone.set_duplicate_suppression(Some(settings(DuplicatePolicy::Suppress)));
+
+        drone.forward_packet(fragment(3, 5, vec![1, RB_DRONE_ID, 2]), true);
+        controller_recv.try_iter().for_each(drop);
+        drone.forward_packet(fragment(3, 5, vec![1, RB_DRONE_ID, 2]), true);
+        assert_eq!(neighbor_2_receiver.try_iter().count(), 1);
+        assert_eq!(drone.stats().duplicate_fragments, 1);
+
+        // never acknowledged before the first copy is
+        let nack = controller_recv
+            .try_iter()
+            .find_map(|event| match event {
+                DroneEvent::ControllerShortcut(packet) => Some(packet),
+                _ => None,
+            })
+            .expect("Nack not sent to the controller");
+        assert!(matches!(
+            nack.pack_type,
+            PacketType::Nack(nack) if nack.fragment_index == 3 && nack.nack_type == NackType::Dropped
+        ));
+        assert_eq!(nack.routing_header.hops, vec![RB_DRONE_ID, 1]);
+    }
+
+    #[test]
+    fn test_retransmission_after_nack_is_forwarded() {
+        let (mut drone, _, controller_recv) = setup_drone();
+        drone.pdr = 0;
+        let (neighbor_1_sender, neighbor_1_receiver) = unbounded();
+        let (neighbor_2_sender, neighbor_2_receiver) = unbounded();
+        drone.packet_send.insert(1, Box::new(neighbor_1_sender));
+        drone.packet_send.insert(2, Box::new(neighbor_2_sender));
+        drone.set_duplicate_suppression(Some(settings(DuplicatePolicy::Suppress)));
+
+        drone.forward_packet(fragment(3, 5, vec![1, RB_DRONE_ID, 2]), true);
+        drone.forward_packet(dropped_nack(3), true);
+        assert!(neighbor_1_receiver.try_recv().is_ok());
+        controller_recv.try_iter().for_each(drop);
+
+        // the client sends the fragment again: it is not a duplicate
+        drone.forward_packet(fragment(3, 5, vec![1, RB_DRONE_ID, 2]), true);
+        assert_eq!(neighbor_2_receiver.try_iter().count(), 2);
+        assert_eq!(drone.stats().duplicate_fragments, 0);
+        assert!(!controller_recv
+            .try_iter()
+            .any(|event| matches!(event, DroneEvent::ControllerShortcut(_))));
+    }
+}
@@ -0,0 +1,164 @@
+#[cfg(test)]
+mod rules {
+    use crate::drone::test::common::{fragment, setup_drone, RB_DRONE_ID};
+    use crate::firewall::{DenyResponse, FirewallConfig, FirewallRule, PacketKind, RuleAction};
+    use crossbeam_channel::unbounded;
+    use wg_2024::network::{NodeId, SourceRoutingHeader};
+    use wg_2024::packet::{Ack, FloodRequest, NackType, NodeType, Packet, PacketType};
+
+    fn deny_source(source: NodeId, denied_fragments: DenyResponse) -> FirewallConfig {
+        FirewallConfig {
+            denied_fragments,
+            rules: vec![FirewallRule {
+                source: Some(source),
+                ..FirewallRule::default()
+            }],
+            ..FirewallConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_rule_matching() {
+        let packet = fragment(3, 7, vec![1, RB_DRONE_ID, 2]);
+        let rule = |rule: FirewallRule| rule.matches(&packet);
+
+        assert!(rule(FirewallRule::default()));
+        assert!(rule(FirewallRule {
+            source: Some(1),
+            destination: Some(2),
+            previous_hop: Some(1),
+            sessions: Some((5, 7)),
+            packet_types: vec![PacketKind::Fragment],
+            ..FirewallRule::default()
+        }));
+        assert!(!rule(FirewallRule {
+            destination: Some(1),
+            ..FirewallRule::default()
+        }));
+        assert!(!rule(FirewallRule {
+            sessions: Some((8, 10)),
+            ..FirewallRule::default()
+        }));
+        assert!(!rule(FirewallRule {
+            packet_types: vec![PacketKind::Ack, PacketKind::Nack],
+            ..FirewallRule::default()
+        }));
+
+        let flood = Packet {
+            pack_type: PacketType::FloodRequest(FloodRequest {
+                flood_id: 1,
+                initiator_id: 4,
+                path_trace: vec![(4, NodeType::Client), (3, NodeType::Drone)],
+            }),
+            routing_header: SourceRoutingHeader {
+                hop_index: 0,
+                hops: vec![],
+            },
+            session_id: 0,
+        };
+        assert!(FirewallRule {
+            source: Some(4),
+            previous_hop: Some(3),
+            ..FirewallRule::default()
+        }
+        .matches(&flood));
+    }
+
+    #[test]
+    fn test_first_matching_rule_wins() {
+        let (drone, _, _) = setup_drone();
+        let firewall = drone.firewall();
+        firewall.set_config(FirewallConfig {
+            default_action: Some(RuleAction::Deny),
+            rules: vec![
+                FirewallRule {
+                    action: RuleAction::Allow,
+                    sessions: Some((0, 9)),
+                    ..FirewallRule::default()
+                },
+                FirewallRule {
+                    source: Some(1),
+                    ..FirewallRule::default()
+                },
+            ],
+            ..FirewallConfig::default()
+        });
+
+        assert_eq!(
+            firewall.check(&fragment(3, 5, vec![1, RB_DRONE_ID, 2])),
+            None
+        );
+        assert_eq!(
+            firewall.check(&fragment(3, 50, vec![1, RB_DRONE_ID, 2])),
+            Some(DenyResponse::Dropped)
+        );
+        // no rule matches: default action
+        assert!(firewall
+            .check(&fragment(3, 50, vec![3, RB_DRONE_ID, 2]))
+            .is_some());
+        assert_eq!(firewall.hits(), vec![1, 1]);
+
+        assert!(firewall.remove_rule(0).is_some());
+        assert_eq!(firewall.hits(), vec![1]);
+        assert!(firewall.remove_rule(1).is_none());
+    }
+
+    #[test]
+    fn test_denied_fragment_dropped_nack() {
+        let (mut drone, _, _) = setup_drone();
+        let (neighbor_1_sender, neighbor_1_receiver) = unbounded();
+        drone.packet_send.insert(1, Box::new(neighbor_1_sender));
+        drone.set_firewall(deny_source(1, DenyResponse::Dropped));
+
+        drone.enqueue_packet(fragment(3, 1, vec![1, RB_DRONE_ID, 2]));
+        assert!(drone.scheduler.is_empty());
+        let nack = neighbor_1_receiver.try_recv().unwrap();
+        assert!(matches!(
+            nack.pack_type,
+            PacketType::Nack(nack) if nack.nack_type == NackType::Dropped && nack.fragment_index == 3
+        ));
+        assert_eq!(drone.stats().firewall_denied, 1);
+    }
+
+    #[test]
+    fn test_denied_fragment_error_in_routing() {
+        let (mut drone, _, _) = setup_drone();
+        let (neighbor_1_sender, neighbor_1_receiver) = unbounded();
+        drone.packet_send.insert(1, Box::new(neighbor_1_sender));
+        drone.set_firewall(deny_source(1, DenyResponse::ErrorInRouting));
+
+        drone.enqueue_packet(fragment(3, 1, vec![1, RB_DRONE_ID, 2]));
+        let nack = neighbor_1_receiver.try_recv().unwrap();
+        assert!(matches!(
+            nack.pack_type,
+            PacketType::Nack(nack) if nack.nack_type == NackType::ErrorInRouting(2)
+        ));
+    }
+
+    #[test]
+    fn test_rules_changed_at_runtime() {
+        let (mut drone, _, _) = setup_drone();
+        let (neighbor_1_sender, neighbor_1_receiver) = unbounded();
+        drone.packet_send.insert(1, Box::new(neighbor_1_sender));
+        let firewall = drone.firewall();
+
+        // denied silently
+        firewall.set_config(deny_source(1, DenyResponse::Silent));
+        drone.enqueue_packet(fragment(3, 1, vec![1, RB_DRONE_ID, 2]));
+        assert!(drone.scheduler.is_empty());
+        assert!(neighbor_1_receiver.try_recv().is_err());
+
+        // the other packet types are discarded
+        let mut ack = fragment(3, 1, vec![1, RB_DRONE_ID, 2]);
+        ack.pack_type = PacketType::Ack(Ack { fragment_index: 0 });
+        drone.enqueue_packet(ack);
+        assert!(drone.scheduler.is_empty());
+        assert_eq!(firewall.hits(), vec![2]);
+
+        // allowed again once the rule is removed
+        firewall.remove_rule(0);
+        drone.enqueue_packet(fragment(3, 1, vec![1, RB_DRONE_ID, 2]));
+        assert!(!drone.scheduler.is_empty());
+        assert_eq!(drone.stats().firewall_denied, 2);
+    }
+}
@@ -0,0 +1,80 @@
+#[cfg(test)]
+mod scope {
+    use crate::drone::test::common::{flood_request, setup_drone};
+    use crossbeam_channel::unbounded;
+    use wg_2024::packet::NodeType::{Client, Drone};
+    use wg_2024::packet::PacketType;
+
+    #[test]
+    fn test_flood_answered_at_max_path_len() {
+        let (mut drone, _, _) = setup_drone();
+        let (neighbor_2_sender, neighbor_2_receiver) = unbounded();
+        let (neighbor_3_sender, neighbor_3_receiver) = unbounded();
+        drone.packet_send.insert(2, Box::new(neighbor_2_sender));
+        drone.packet_send.insert(3, Box::new(neighbor_3_sender));
+        drone.set_max_flood_path_len(Some(3));
+
+        // client 1 -> drone 2 -> this drone: the path_trace reaches 3 entries
+        drone.handle_flood_request(flood_request(1, 1, vec![(1, Client), (2, Drone)]));
+
+        let response = neighbor_2_receiver
+            .try_recv()
+            .expect("FloodResponse not sent");
+        match response.pack_type {
+            PacketType::FloodResponse(response) => {
+                assert_eq!(
+                    response.path_trace,
+                    vec![(1, Client), (2, Drone), (drone.id, Drone)]
+                );
+            }
+            _ => panic!("Unexpected packet: {:?}", response.pack_type),
+        }
+        assert!(neighbor_3_receiver.try_recv().is_err());
+        assert_eq!(drone.stats().scoped_floods, 1);
+    }
+
+    #[test]
+    fn test_flood_spread_within_scope() {
+        let (mut drone, _, _) = setup_drone();
+        let (neighbor_1_sender, _neighbor_1_receiver) = unbounded();
+        let (neighbor_3_sender, neighbor_3_receiver) = unbounded();
+        drone.packet_send.insert(1, Box::new(neighbor_1_sender));
+        drone.packet_send.insert(3, Box::new(neighbor_3_sender));
+        drone.set_max_flood_path_len(Some(3));
+
+        drone.handle_flood_request(flood_request(1, 1, vec![(1, Client)]));
+
+        match neighbor_3_receiver.try_recv() {
+            Ok(packet) => assert!(matches!(packet.pack_type, PacketType::FloodRequest(_))),
+            Err(e) => panic!("FloodRequest not spread: {e}"),
+        }
+        assert_eq!(drone.stats().scoped_floods, 0);
+    }
+
+    #[test]
+    fn test_initiator_scope_overrides_default() {
+        let (mut drone, _, _) = setup_drone();
+        let (neighbor_1_sender, neighbor_1_receiver) = unbounded();
+        let (neighbor_3_sender, neighbor_3_receiver) = unbounded();
+        drone.packet_send.insert(1, Box::new(neighbor_1_sender));
+        drone.packet_send.insert(3, Box::new(neighbor_3_sender));
+        drone.set_max_flood_path_len(Some(2));
+        drone.set_max_flood_path_len_for(1, None);
+        drone.set_max_flood_path_len_for(4, Some(5));
+
+        // initiator 1 uses the default scope
+        drone.handle_flood_request(flood_request(1, 1, vec![(1, Client)]));
+        assert!(matches!(
+            neighbor_1_receiver.try_recv().map(|p| p.pack_type),
+            Ok(PacketType::FloodResponse(_))
+        ));
+
+        // initiator 4 has a larger scope
+        drone.handle_flood_request(flood_request(1, 4, vec![(4, Client), (1, Drone)]));
+        assert!(matches!(
+            neighbor_3_receiver.try_recv().map(|p| p.pack_type),
+            Ok(PacketType::FloodRequest(_))
+        ));
+        assert_eq!(drone.stats().scoped_floods, 1);
+    }
+}
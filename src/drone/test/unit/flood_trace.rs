@@ -0,0 +1,148 @@
+#[cfg(test)]
+mod tampering {
+    use crate::drone::test::common::setup_drone;
+    use crate::flood_trace::{check_trace, TracePolicy, TraceViolation};
+    use crate::report::{Report, ReportKind};
+    use crate::telemetry::TelemetryEvent;
+    use crossbeam_channel::unbounded;
+    use wg_2024::controller::DroneEvent;
+    use wg_2024::network::{NodeId, SourceRoutingHeader};
+    use wg_2024::packet::{FloodRequest, NodeType, Packet, PacketType};
+
+    fn flood_request(initiator_id: NodeId, trace: &[NodeId]) -> FloodRequest {
+        FloodRequest {
+            flood_id: 7,
+            initiator_id,
+            path_trace: trace
+                .iter()
+                .enumerate()
+                .map(|(i, &id)| {
+                    let node_type = if i == 0 {
+                        NodeType::Client
+                    } else {
+                        NodeType::Drone
+                    };
+                    (id, node_type)
+                })
+                .collect(),
+        }
+    }
+
+    fn packet(flood_request: FloodRequest) -> Packet {
+        Packet {
+            pack_type: PacketType::FloodRequest(flood_request),
+            routing_header: SourceRoutingHeader {
+                hop_index: 0,
+                hops: vec![],
+            },
+            session_id: 0,
+        }
+    }
+
+    #[test]
+    fn test_check_trace() {
+        let neighbor = |id| id == 2;
+        assert_eq!(check_trace(&flood_request(1, &[1, 2]), neighbor), None);
+        assert_eq!(
+            check_trace(&flood_request(1, &[1, 3]), neighbor),
+            Some(TraceViolation::UnknownSender)
+        );
+        assert_eq!(
+            check_trace(&flood_request(1, &[1, 2, 1, 2]), neighbor),
+            Some(TraceViolation::RepeatedNode)
+        );
+        assert_eq!(
+            check_trace(&flood_request(5, &[1, 2]), neighbor),
+            Some(TraceViolation::InitiatorMismatch)
+        );
+        // without a trace the request comes from the initiator
+        assert_eq!(check_trace(&flood_request(2, &[]), neighbor), None);
+        assert_eq!(
+            check_trace(&flood_request(1, &[]), neighbor),
+            Some(TraceViolation::UnknownSender)
+        );
+    }
+
+    #[test]
+    fn test_trusted_trace_is_spread() {
+        let (mut drone, _, _) = setup_drone();
+        let (neighbor_2_sender, neighbor_2_receiver) = unbounded();
+        drone.packet_send.insert(2, Box::new(neighbor_2_sender));
+
+        drone.handle_flood_request(packet(flood_request(1, &[1, 3])));
+        assert!(neighbor_2_receiver.try_recv().is_ok());
+        assert_eq!(drone.stats().tampered_floods, 0);
+    }
+
+    #[test]
+    fn test_tampered_trace_is_dropped_and_reported() {
+        let (mut drone, _, controller_recv) = setup_drone();
+        let (neighbor_2_sender, neighbor_2_receiver) = unbounded();
+        let (neighbor_3_sender, neighbor_3_receiver) = unbounded();
+        drone.packet_send.insert(2, Box::new(neighbor_2_sender));
+        drone.packet_send.insert(3, Box::new(neighbor_3_sender));
+        drone.set_flood_trace_policy(TracePolicy::Drop);
+        let (telemetry_send, telemetry_recv) = unbounded();
+        drone.set_telemetry(Some(telemetry_send));
+
+        // claims to come from 4, which is not a neighbor
+        drone.handle_flood_request(packet(flood_request(1, &[1, 4])));
+        assert!(neighbor_2_receiver.try_recv().is_err());
+        assert!(neighbor_3_receiver.try_recv().is_err());
+        assert!(drone.received_floods.is_empty());
+        assert!(drone.take_quarantined_floods().is_empty());
+        assert_eq!(drone.stats().tampered_floods, 1);
+
+        let report = controller_recv
+            .try_iter()
+            .find_map(|event| match event {
+                DroneEvent::PacketSent(packet) => Report::parse(&packet),
+                _ => None,
+            })
+            .expect("TamperedFlood report not sent");
+        assert_eq!(report.kind, ReportKind::TamperedFlood);
+        assert_eq!(
+            telemetry_recv.try_recv().map(|telemetry| telemetry.event),
+            Ok(TelemetryEvent::TamperedFlood {
+                violation: TraceViolation::UnknownSender,
+                flood_id: 7,
+                initiator_id: 1,
+                sender_id: 4,
+            })
+        );
+        assert_eq!(
+            report.payload[..3],
+            [TraceViolation::UnknownSender as u8, 1, 4]
+        );
+        assert_eq!(report.payload[3..11], 7u64.to_le_bytes());
+
+        // a consistent request is still spread
+        drone.handle_flood_request(packet(flood_request(1, &[1, 2])));
+        assert!(neighbor_3_receiver.try_recv().is_ok());
+    }
+
+    #[test]
+    fn test_tampered_trace_is_quarantined() {
+        let (mut drone, _, _) = setup_drone();
+        let (neighbor_2_sender, neighbor_2_receiver) = unbounded();
+        drone.packet_send.insert(2, Box::new(neighbor_2_sender));
+        drone.set_flood_trace_policy(TracePolicy::Quarantine);
+
+        drone.handle_flood_request(packet(flood_request(1, &[1, 2, 1, 2])));
+        drone.handle_flood_request(packet(flood_request(9, &[1, 2])));
+        assert!(neighbor_2_receiver.try_recv().is_err());
+
+        let quarantined = drone.take_quarantined_floods();
+        assert_eq!(
+            quarantined
+                .iter()
+                .map(|(violation, _)| *violation)
+                .collect::<Vec<_>>(),
+            vec![
+                TraceViolation::RepeatedNode,
+                TraceViolation::InitiatorMismatch
+            ]
+        );
+        assert!(drone.take_quarantined_floods().is_empty());
+    }
+}
@@ -11,9 +11,9 @@ mod forward {
     fn test_forward_packet_invalid_hop_index() {
         let (mut drone, _, _) = setup_drone();
         let (neighbor_5_sender, neighbor_5_receiver) = unbounded();
-        drone.packet_send.insert(5, neighbor_5_sender);
+        drone.packet_send.insert(5, Box::new(neighbor_5_sender));
         let (neighbor_2_sender, _) = unbounded();
-        drone.packet_send.insert(2, neighbor_2_sender);
+        drone.packet_send.insert(2, Box::new(neighbor_2_sender));
 
         // Packet with hop_index greater than the number of hops
         let packet = Packet {
@@ -56,7 +56,7 @@ mod forward {
         let (mut drone, _controller_send, controller_recv) = setup_drone();
 
         let (neighbor_1_sender, neighbor_1_receiver) = unbounded();
-        drone.packet_send.insert(1, neighbor_1_sender);
+        drone.packet_send.insert(1, Box::new(neighbor_1_sender));
 
         let packet = Packet {
             pack_type: PacketType::MsgFragment(Fragment {
@@ -118,9 +118,9 @@ mod forward {
     fn test_forward_packet_after_crash() {
         let (mut drone, _, _) = setup_drone();
         let (neighbor_1_sender, neighbor_1_receiver) = unbounded();
-        drone.packet_send.insert(1, neighbor_1_sender);
+        drone.packet_send.insert(1, Box::new(neighbor_1_sender));
         let (neighbor_3_sender, _neighbor_3_receiver) = unbounded();
-        drone.packet_send.insert(3, neighbor_3_sender);
+        drone.packet_send.insert(3, Box::new(neighbor_3_sender));
 
         drone.running = false;
 
@@ -161,7 +161,7 @@ mod forward {
     fn test_forward_packet_when_drone_is_destination() {
         let (mut drone, _, _) = setup_drone();
         let (neighbor_1_sender, neighbor_1_receiver) = unbounded();
-        drone.packet_send.insert(1, neighbor_1_sender);
+        drone.packet_send.insert(1, Box::new(neighbor_1_sender));
 
         let packet = Packet {
             pack_type: PacketType::MsgFragment(Fragment {
@@ -203,7 +203,7 @@ mod forward {
     fn test_forward_packet_when_next_hop_is_not_neighbor() {
         let (mut drone, _, _) = setup_drone();
         let (neighbor_1_sender, neighbor_1_receiver) = unbounded();
-        drone.packet_send.insert(1, neighbor_1_sender);
+        drone.packet_send.insert(1, Box::new(neighbor_1_sender));
 
         let packet = Packet {
             pack_type: PacketType::MsgFragment(Fragment {
@@ -244,9 +244,9 @@ mod forward {
     fn test_forward_nack_without_hunt_mode() {
         let (mut drone, _, _) = setup_drone();
         let (neighbor_1_sender, _neighbor_1_receiver) = unbounded();
-        drone.packet_send.insert(1, neighbor_1_sender);
+        drone.packet_send.insert(1, Box::new(neighbor_1_sender));
         let (neighbor_3_sender, neighbor_3_receiver) = unbounded();
-        drone.packet_send.insert(3, neighbor_3_sender);
+        drone.packet_send.insert(3, Box::new(neighbor_3_sender));
 
         let packet = Packet {
             pack_type: PacketType::Nack(Nack {
@@ -286,9 +286,9 @@ mod forward {
     fn test_forward_nack_with_hunt_mode() {
         let (mut drone, _, controller_recv) = setup_drone();
         let (neighbor_2_sender, _neighbor_2_receiver) = unbounded();
-        drone.packet_send.insert(2, neighbor_2_sender);
+        drone.packet_send.insert(2, Box::new(neighbor_2_sender));
         let (neighbor_3_sender, neighbor_3_receiver) = unbounded();
-        drone.packet_send.insert(3, neighbor_3_sender);
+        drone.packet_send.insert(3, Box::new(neighbor_3_sender));
 
         drone.set_hunt_mode(true);
 
@@ -346,11 +346,11 @@ mod forward {
     fn test_forward_nack_ack_or_flood_response_without_optimized_routing() {
         let (mut drone, _, _) = setup_drone();
         let (neighbor_1_sender, _neighbor_1_receiver) = unbounded();
-        drone.packet_send.insert(1, neighbor_1_sender);
+        drone.packet_send.insert(1, Box::new(neighbor_1_sender));
         let (neighbor_3_sender, neighbor_3_receiver) = unbounded();
-        drone.packet_send.insert(3, neighbor_3_sender);
+        drone.packet_send.insert(3, Box::new(neighbor_3_sender));
         let (neighbor_4_sender, neighbor_4_receiver) = unbounded();
-        drone.packet_send.insert(4, neighbor_4_sender);
+        drone.packet_send.insert(4, Box::new(neighbor_4_sender));
 
         let packet = Packet {
             pack_type: PacketType::Ack(wg_2024::packet::Ack { fragment_index: 0 }),
@@ -384,11 +384,11 @@ mod forward {
     fn test_forward_nack_or_flood_response_with_optimized_routing() {
         let (mut drone, _, _) = setup_drone();
         let (neighbor_1_sender, _neighbor_1_receiver) = unbounded();
-        drone.packet_send.insert(1, neighbor_1_sender);
+        drone.packet_send.insert(1, Box::new(neighbor_1_sender));
         let (neighbor_3_sender, neighbor_3_receiver) = unbounded();
-        drone.packet_send.insert(3, neighbor_3_sender);
+        drone.packet_send.insert(3, Box::new(neighbor_3_sender));
         let (neighbor_4_sender, neighbor_4_receiver) = unbounded();
-        drone.packet_send.insert(4, neighbor_4_sender);
+        drone.packet_send.insert(4, Box::new(neighbor_4_sender));
 
         drone.set_optimized_routing(true);
 
@@ -428,9 +428,9 @@ mod forward {
     fn test_forward_ack_with_optimized_routing() {
         let (mut drone, _, _) = setup_drone();
         let (neighbor_1_sender, _neighbor_1_receiver) = unbounded();
-        drone.packet_send.insert(1, neighbor_1_sender);
+        drone.packet_send.insert(1, Box::new(neighbor_1_sender));
         let (neighbor_3_sender, neighbor_3_receiver) = unbounded();
-        drone.packet_send.insert(3, neighbor_3_sender);
+        drone.packet_send.insert(3, Box::new(neighbor_3_sender));
 
         drone.set_optimized_routing(true);
 
@@ -462,9 +462,9 @@ mod forward {
     fn test_forward_nack_ack_or_flood_response_when_neighbor_channel_closed() {
         let (mut drone, _, controller_recv) = setup_drone();
         let (neighbor_1_sender, _neighbor_1_receiver) = unbounded();
-        drone.packet_send.insert(1, neighbor_1_sender);
+        drone.packet_send.insert(1, Box::new(neighbor_1_sender));
         let (neighbor_3_sender, neighbor_3_receiver) = unbounded();
-        drone.packet_send.insert(3, neighbor_3_sender);
+        drone.packet_send.insert(3, Box::new(neighbor_3_sender));
 
         drop(neighbor_3_receiver);
 
@@ -504,9 +504,9 @@ mod forward {
     fn test_forward_nack_ack_or_flood_response_when_neighbor_is_removed_after_first_check() {
         let (mut drone, _, controller_recv) = setup_drone();
         let (neighbor_1_sender, _neighbor_1_receiver) = unbounded();
-        drone.packet_send.insert(1, neighbor_1_sender);
+        drone.packet_send.insert(1, Box::new(neighbor_1_sender));
         let (neighbor_3_sender, neighbor_3_receiver) = unbounded();
-        drone.packet_send.insert(3, neighbor_3_sender);
+        drone.packet_send.insert(3, Box::new(neighbor_3_sender));
 
         drop(neighbor_3_receiver);
 
@@ -547,9 +547,9 @@ mod forward {
     fn test_forward_fragment_with_100_as_pdr() {
         let (mut drone, _, controller_recv) = setup_drone();
         let (neighbor_1_sender, neighbor_1_receiver) = unbounded();
-        drone.packet_send.insert(1, neighbor_1_sender);
+        drone.packet_send.insert(1, Box::new(neighbor_1_sender));
         let (neighbor_5_sender, _neighbor_5_receiver) = unbounded();
-        drone.packet_send.insert(5, neighbor_5_sender);
+        drone.packet_send.insert(5, Box::new(neighbor_5_sender));
 
         drone.pdr = 100;
 
@@ -611,9 +611,9 @@ mod forward {
     fn test_forward_fragment_with_0_as_pdr() {
         let (mut drone, _, _) = setup_drone();
         let (neighbor_1_sender, _neighbor_1_receiver) = unbounded();
-        drone.packet_send.insert(1, neighbor_1_sender);
+        drone.packet_send.insert(1, Box::new(neighbor_1_sender));
         let (neighbor_5_sender, neighbor_5_receiver) = unbounded();
-        drone.packet_send.insert(5, neighbor_5_sender);
+        drone.packet_send.insert(5, Box::new(neighbor_5_sender));
 
         drone.pdr = 0;
 
@@ -653,9 +653,9 @@ mod forward {
         let (mut drone, _, _) = setup_drone();
         drone.pdr = 0;
         let (neighbor_1_sender, neighbor_1_receiver) = unbounded();
-        drone.packet_send.insert(1, neighbor_1_sender);
+        drone.packet_send.insert(1, Box::new(neighbor_1_sender));
         let (neighbor_3_sender, _neighbor_3_receiver) = unbounded();
-        drone.packet_send.insert(3, neighbor_3_sender);
+        drone.packet_send.insert(3, Box::new(neighbor_3_sender));
 
         drone.set_optimized_routing(true);
 
@@ -699,9 +699,9 @@ mod forward {
     fn test_forward_fragment_when_neighbor_channel_closed() {
         let (mut drone, _, _) = setup_drone();
         let (neighbor_1_sender, neighbor_1_receiver) = unbounded();
-        drone.packet_send.insert(1, neighbor_1_sender);
+        drone.packet_send.insert(1, Box::new(neighbor_1_sender));
         let (neighbor_5_sender, neighbor_5_receiver) = unbounded();
-        drone.packet_send.insert(5, neighbor_5_sender);
+        drone.packet_send.insert(5, Box::new(neighbor_5_sender));
 
         drop(neighbor_5_receiver);
 
@@ -745,7 +745,7 @@ mod forward {
     fn test_forward_fragment_when_neighbor_is_removed_after_first_check() {
         let (mut drone, _, _) = setup_drone();
         let (neighbor_1_sender, neighbor_1_receiver) = unbounded();
-        drone.packet_send.insert(1, neighbor_1_sender);
+        drone.packet_send.insert(1, Box::new(neighbor_1_sender));
 
         drone.pdr = 0;
 
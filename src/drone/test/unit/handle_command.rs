@@ -34,12 +34,7 @@ mod commands {
             },
         };
 
-        drone
-            .packet_send
-            .get(&UNKNOWN_NODE)
-            .unwrap()
-            .send(sample_packet)
-            .unwrap();
+        drone.packet_send.send(UNKNOWN_NODE, sample_packet).unwrap();
 
         if let Ok(packet) = unknown_node_receiver.recv_timeout(std::time::Duration::from_secs(1)) {
             match packet.pack_type {
@@ -59,7 +54,9 @@ mod commands {
         let (mut drone, _, _) = setup_drone();
 
         let (unknown_node_sender, _) = unbounded();
-        drone.packet_send.insert(UNKNOWN_NODE, unknown_node_sender);
+        drone
+            .packet_send
+            .insert(UNKNOWN_NODE, Box::new(unknown_node_sender));
 
         drone.handle_command(RemoveSender(UNKNOWN_NODE));
         assert_eq!(drone.packet_send.len(), 0);
@@ -45,7 +45,9 @@ mod flooding {
         let (mut drone, _, controller_recv) = setup_drone();
 
         let (neighbor_sender, _) = unbounded(); // Immediately closed channel
-        drone.packet_send.insert(UNKNOWN_NODE, neighbor_sender);
+        drone
+            .packet_send
+            .insert(UNKNOWN_NODE, Box::new(neighbor_sender));
 
         let flood_request = FloodRequest {
             flood_id: 123,
@@ -80,9 +82,9 @@ mod flooding {
         // Tests no flood request is sent to an unknown neighbor
         let (mut drone, _, controller_recv) = setup_drone();
         let (neighbor_1_sender, neighbor_1_recv) = unbounded();
-        drone.packet_send.insert(1, neighbor_1_sender);
+        drone.packet_send.insert(1, Box::new(neighbor_1_sender));
         let (neighbor_3_sender, neighbor_3_recv) = unbounded();
-        drone.packet_send.insert(3, neighbor_3_sender);
+        drone.packet_send.insert(3, Box::new(neighbor_3_sender));
 
         drone.running = false;
 
@@ -122,7 +124,7 @@ mod flooding {
         // Tests no flood request is sent to an unknown neighbor
         let (mut drone, _, controller_recv) = setup_drone();
         let (neighbor_1_sender, _neighbor_1_recv) = unbounded();
-        drone.packet_send.insert(1, neighbor_1_sender);
+        drone.packet_send.insert(1, Box::new(neighbor_1_sender));
 
         let flood_request = FloodRequest {
             flood_id: 123,
@@ -159,7 +161,9 @@ mod flooding {
 
         let (neighbor_sender, neighbor_recv) = unbounded();
         drop(neighbor_recv); // Close the receiver
-        drone.packet_send.insert(UNKNOWN_NODE, neighbor_sender);
+        drone
+            .packet_send
+            .insert(UNKNOWN_NODE, Box::new(neighbor_sender));
 
         let flood_request = FloodRequest {
             flood_id: 123,
@@ -196,8 +200,8 @@ mod flooding {
 
         let (neighbor_1_sender, neighbor_1_recv) = unbounded();
         let (neighbor_2_sender, neighbor_2_recv) = unbounded();
-        drone.packet_send.insert(1, neighbor_1_sender);
-        drone.packet_send.insert(2, neighbor_2_sender);
+        drone.packet_send.insert(1, Box::new(neighbor_1_sender));
+        drone.packet_send.insert(2, Box::new(neighbor_2_sender));
 
         let flood_request = FloodRequest {
             flood_id: 123,
@@ -236,7 +240,7 @@ mod flooding {
         let (mut drone, _, controller_recv) = setup_drone();
 
         let (sender, receiver) = unbounded();
-        drone.packet_send.insert(1, sender);
+        drone.packet_send.insert(1, Box::new(sender));
 
         let packet = Packet {
             pack_type: PacketType::FloodRequest(FloodRequest {
@@ -290,11 +294,11 @@ mod flooding {
 
         // Add neighbor 1
         let (sender, receiver) = unbounded();
-        drone.packet_send.insert(1, sender);
+        drone.packet_send.insert(1, Box::new(sender));
 
         // Add neighbor 2
         let (sender2, _receiver2) = unbounded();
-        drone.packet_send.insert(2, sender2);
+        drone.packet_send.insert(2, Box::new(sender2));
 
         // Simulate a flood that has already been received
         drone.received_floods.insert((123, 1));
@@ -353,7 +357,7 @@ mod flooding {
 
         // Add neighbor 1
         let (sender, receiver) = unbounded();
-        drone.packet_send.insert(1, sender);
+        drone.packet_send.insert(1, Box::new(sender));
 
         // Create a FloodRequest with an empty path_trace
         let packet = Packet {
@@ -436,7 +440,7 @@ mod flooding {
         let (mut drone, _, controller_recv) = setup_drone();
 
         let (sender, _receiver) = unbounded();
-        drone.packet_send.insert(1, sender);
+        drone.packet_send.insert(1, Box::new(sender));
         drone.received_floods.insert((123, 1));
 
         let packet = Packet {
@@ -0,0 +1,120 @@
+#[cfg(test)]
+mod histograms {
+    use crate::drone::test::common::{setup_drone, RB_DRONE_ID};
+    use crate::latency::LatencyHistogram;
+    use crate::report::{Report, ReportKind};
+    use crate::scheduler::TrafficClass;
+    use crate::telemetry::TelemetryEvent;
+    use crossbeam_channel::unbounded;
+    use std::thread::sleep;
+    use std::time::Duration;
+    use wg_2024::controller::DroneEvent;
+    use wg_2024::network::SourceRoutingHeader;
+    use wg_2024::packet::{FloodRequest, Fragment, NodeType, Packet, PacketType, FRAGMENT_DSIZE};
+
+    #[test]
+    fn test_percentiles() {
+        let mut histogram = LatencyHistogram::default();
+        assert_eq!(histogram.percentile(0.99), Duration::ZERO);
+        for micros in 1..=1000 {
+            histogram.record(Duration::from_micros(micros));
+        }
+        assert_eq!(histogram.count(), 1000);
+        assert_eq!(histogram.max(), Duration::from_micros(1000));
+        assert_eq!(histogram.mean(), Duration::from_micros(500));
+
+        // a percentile is off by at most 1/16
+        let (p50, p99, max) = histogram.tail();
+        assert!((500..=532).contains(&p50.as_micros()), "{p50:?}");
+        assert!((990..=1000).contains(&p99.as_micros()), "{p99:?}");
+        assert_eq!(max, Duration::from_micros(1000));
+        assert_eq!(histogram.percentile(0.0), Duration::from_micros(1));
+    }
+
+    #[test]
+    fn test_small_values_are_exact() {
+        let mut histogram = LatencyHistogram::default();
+        for micros in [3, 15, 16, 17] {
+            histogram.record(Duration::from_micros(micros));
+        }
+        assert_eq!(histogram.percentile(0.25), Duration::from_micros(3));
+        assert_eq!(histogram.percentile(0.5), Duration::from_micros(15));
+        assert_eq!(histogram.percentile(0.75), Duration::from_micros(16));
+        assert_eq!(histogram.percentile(1.0), Duration::from_micros(17));
+    }
+
+    #[test]
+    fn test_forwarding_latency_per_class() {
+        let (mut drone, _, controller_recv) = setup_drone();
+        let (telemetry_send, telemetry_recv) = unbounded();
+        drone.set_telemetry(Some(telemetry_send));
+        drone.pdr = 0;
+        let (neighbor_1_sender, _neighbor_1_receiver) = unbounded();
+        let (neighbor_2_sender, _neighbor_2_receiver) = unbounded();
+        let (neighbor_3_sender, _neighbor_3_receiver) = unbounded();
+        drone.packet_send.insert(1, Box::new(neighbor_1_sender));
+        drone.packet_send.insert(2, Box::new(neighbor_2_sender));
+        drone.packet_send.insert(3, Box::new(neighbor_3_sender));
+
+        drone.enqueue_packet(Packet {
+            pack_type: PacketType::MsgFragment(Fragment {
+                fragment_index: 0,
+                total_n_fragments: 1,
+                data: [0; FRAGMENT_DSIZE],
+                length: FRAGMENT_DSIZE as u8,
+            }),
+            routing_header: SourceRoutingHeader {
+                hop_index: 1,
+                hops: vec![1, RB_DRONE_ID, 2],
+            },
+            session_id: 1,
+        });
+        drone.enqueue_packet(Packet {
+            pack_type: PacketType::FloodRequest(FloodRequest {
+                flood_id: 1,
+                initiator_id: 1,
+                path_trace: vec![(1, NodeType::Client)],
+            }),
+            routing_header: SourceRoutingHeader {
+                hop_index: 1,
+                hops: vec![],
+            },
+            session_id: 2,
+        });
+        // time spent in the queues counts
+        sleep(Duration::from_millis(5));
+        while let Some((packet, received)) = drone.scheduler.pop_timed() {
+            drone.dispatch_timed(packet, received);
+        }
+
+        let latency = drone.stats().latency;
+        assert_eq!(latency.data.count(), 1);
+        // spread to neighbors 2 and 3
+        assert_eq!(latency.flood.count(), 2);
+        assert!(latency.control.is_empty());
+        assert!(latency.data.max() >= Duration::from_millis(5));
+
+        // the histograms are sent on the telemetry stream on shutdown
+        drone.finish_crash();
+        assert!(
+            telemetry_recv
+                .try_iter()
+                .any(|telemetry| telemetry.event
+                    == TelemetryEvent::Latency(Box::new(latency.clone())))
+        );
+
+        // and the tail latencies are reported to the controller
+        let report = controller_recv
+            .try_iter()
+            .filter_map(|event| match event {
+                DroneEvent::PacketSent(packet) => Report::parse(&packet),
+                _ => None,
+            })
+            .find(|report| report.kind == ReportKind::Latency)
+            .expect("Latency report not sent");
+        let data = &report.payload[26..39];
+        assert_eq!(data[0], TrafficClass::Data as u8);
+        let max = u32::from_le_bytes(data[9..13].try_into().unwrap());
+        assert_eq!(u128::from(max), latency.data.max().as_micros());
+    }
+}
@@ -0,0 +1,166 @@
+#[cfg(test)]
+mod pipeline {
+    use crate::drone::test::common::{fragment, setup_drone, RB_DRONE_ID};
+    use crate::middleware::{PacketMiddleware, Verdict};
+    use crossbeam_channel::unbounded;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use wg_2024::controller::DroneEvent;
+    use wg_2024::network::{NodeId, SourceRoutingHeader};
+    use wg_2024::packet::{FloodRequest, NodeType, Packet, PacketType};
+
+    /// Counts its calls and vetoes every hook
+    struct Veto(Arc<AtomicUsize>);
+
+    impl PacketMiddleware for Veto {
+        fn on_receive(&mut self, _drone_id: NodeId, _packet: &mut Packet) -> Verdict {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Verdict::Veto
+        }
+
+        fn on_drop(&mut self, _drone_id: NodeId, _packet: &mut Packet) -> Verdict {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Verdict::Veto
+        }
+
+        fn on_nack(&mut self, _drone_id: NodeId, _packet: &mut Packet) -> Verdict {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Verdict::Veto
+        }
+
+        fn on_flood(&mut self, _drone_id: NodeId, _packet: &mut Packet) -> Verdict {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Verdict::Veto
+        }
+    }
+
+    /// Tags the forwarded packets with its own session ID
+    struct Tag(u64);
+
+    impl PacketMiddleware for Tag {
+        fn before_forward(
+            &mut self,
+            drone_id: NodeId,
+            packet: &mut Packet,
+            _next_hop: NodeId,
+        ) -> Verdict {
+            assert_eq!(drone_id, RB_DRONE_ID);
+            packet.session_id = self.0;
+            Verdict::Continue
+        }
+    }
+
+    /// Cuts the routing header of the Nacks
+    struct Truncate;
+
+    impl PacketMiddleware for Truncate {
+        fn on_nack(&mut self, _drone_id: NodeId, packet: &mut Packet) -> Verdict {
+            packet.routing_header.hops.truncate(1);
+            Verdict::Continue
+        }
+    }
+
+    #[test]
+    fn test_before_forward_mutates_packet() {
+        let (mut drone, _, _) = setup_drone();
+        drone.pdr = 0;
+        let (neighbor_2_sender, neighbor_2_receiver) = unbounded();
+        drone.packet_send.insert(2, Box::new(neighbor_2_sender));
+        drone.add_middleware(Tag(42));
+
+        drone.forward_packet(fragment(0, 1, vec![1, RB_DRONE_ID, 2]), true);
+        assert_eq!(neighbor_2_receiver.try_recv().unwrap().session_id, 42);
+    }
+
+    #[test]
+    fn test_middlewares_run_in_order_until_veto() {
+        let (mut drone, _, _) = setup_drone();
+        let first = Arc::new(AtomicUsize::new(0));
+        let second = Arc::new(AtomicUsize::new(0));
+        drone.add_middleware(Veto(first.clone()));
+        drone.add_middleware(Veto(second.clone()));
+
+        drone.on_packet(Ok(fragment(0, 1, vec![1, RB_DRONE_ID, 2])));
+        assert!(drone.scheduler.is_empty());
+        assert_eq!(first.load(Ordering::SeqCst), 1);
+        assert_eq!(second.load(Ordering::SeqCst), 0);
+        assert_eq!(drone.stats().middleware_vetoes, 1);
+
+        drone.clear_middlewares();
+        drone.on_packet(Ok(fragment(0, 1, vec![1, RB_DRONE_ID, 2])));
+        assert!(!drone.scheduler.is_empty());
+    }
+
+    #[test]
+    fn test_vetoed_drop_forwards_fragment() {
+        let (mut drone, _, controller_recv) = setup_drone();
+        drone.pdr = 100;
+        let (neighbor_1_sender, neighbor_1_receiver) = unbounded();
+        let (neighbor_2_sender, neighbor_2_receiver) = unbounded();
+        drone.packet_send.insert(1, Box::new(neighbor_1_sender));
+        drone.packet_send.insert(2, Box::new(neighbor_2_sender));
+        drone.add_middleware(Veto(Arc::default()));
+
+        drone.forward_packet(fragment(0, 1, vec![1, RB_DRONE_ID, 2]), true);
+        assert!(neighbor_2_receiver.try_recv().is_ok());
+        assert!(neighbor_1_receiver.try_recv().is_err());
+        assert!(!controller_recv
+            .try_iter()
+            .any(|event| matches!(event, DroneEvent::PacketDropped(_))));
+        assert_eq!(drone.stats().fragments_dropped, 0);
+    }
+
+    #[test]
+    fn test_vetoed_nack_is_not_sent() {
+        let (mut drone, _, _) = setup_drone();
+        let (neighbor_1_sender, neighbor_1_receiver) = unbounded();
+        drone.packet_send.insert(1, Box::new(neighbor_1_sender));
+        drone.add_middleware(Veto(Arc::default()));
+
+        // the next hop is not a neighbor: the ErrorInRouting Nack is vetoed
+        drone.forward_packet(fragment(0, 1, vec![1, RB_DRONE_ID, 3]), true);
+        assert!(neighbor_1_receiver.try_recv().is_err());
+        assert_eq!(drone.stats().nacks_sent, 0);
+    }
+
+    #[test]
+    fn test_nack_without_next_hop_is_dropped() {
+        let (mut drone, _, controller_recv) = setup_drone();
+        let (neighbor_1_sender, neighbor_1_receiver) = unbounded();
+        drone.packet_send.insert(1, Box::new(neighbor_1_sender));
+        drone.add_middleware(Truncate);
+
+        drone.forward_packet(fragment(0, 1, vec![1, RB_DRONE_ID, 3]), true);
+        assert!(neighbor_1_receiver.try_recv().is_err());
+        assert!(!controller_recv
+            .try_iter()
+            .any(|event| matches!(event, DroneEvent::ControllerShortcut(_))));
+        assert_eq!(drone.stats().nacks_sent, 0);
+    }
+
+    #[test]
+    fn test_vetoed_flood_is_ignored() {
+        let (mut drone, _, _) = setup_drone();
+        let (neighbor_1_sender, neighbor_1_receiver) = unbounded();
+        let (neighbor_2_sender, neighbor_2_receiver) = unbounded();
+        drone.packet_send.insert(1, Box::new(neighbor_1_sender));
+        drone.packet_send.insert(2, Box::new(neighbor_2_sender));
+        drone.add_middleware(Veto(Arc::default()));
+
+        drone.handle_flood_request(Packet {
+            pack_type: PacketType::FloodRequest(FloodRequest {
+                flood_id: 1,
+                initiator_id: 1,
+                path_trace: vec![(1, NodeType::Client)],
+            }),
+            routing_header: SourceRoutingHeader {
+                hop_index: 1,
+                hops: vec![1, RB_DRONE_ID],
+            },
+            session_id: 0,
+        });
+        assert!(neighbor_1_receiver.try_recv().is_err());
+        assert!(neighbor_2_receiver.try_recv().is_err());
+        assert!(drone.received_floods.is_empty());
+    }
+}
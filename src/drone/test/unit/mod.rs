@@ -1,6 +1,26 @@
+mod arq;
+mod battery;
+mod chaos;
+mod crash;
+mod dedup;
+mod firewall;
+mod flood_scope;
+mod flood_trace;
 mod forward_packet;
 mod handle_command;
 mod handle_flood;
 mod hunt_mode;
+mod latency;
+mod middleware;
+mod neighbors;
 mod optimize_route;
+mod rate_limit;
+mod reputation;
+mod scheduler;
 mod send_nack;
+mod snapshot;
+mod sonification;
+mod sounds;
+mod telemetry;
+mod topology;
+mod transport;
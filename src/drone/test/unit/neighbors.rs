@@ -0,0 +1,168 @@
+#[cfg(test)]
+mod liveness {
+    use crate::drone::test::common::{setup_drone, RB_DRONE_ID};
+    use crate::neighbors::{previous_hop, LinkState};
+    use crate::report::{Report, ReportKind};
+    use crate::transport::PacketLink;
+    use crossbeam_channel::{unbounded, Receiver};
+    use std::time::Duration;
+    use wg_2024::controller::{DroneCommand, DroneEvent};
+    use wg_2024::network::SourceRoutingHeader;
+    use wg_2024::packet::NodeType::Client;
+    use wg_2024::packet::{Ack, FloodRequest, Packet, PacketType};
+
+    struct FailingLink;
+
+    impl PacketLink for FailingLink {
+        fn send_packet(&self, _packet: Packet) -> Result<(), String> {
+            Err("unreachable".to_string())
+        }
+    }
+
+    fn ack(hops: Vec<u8>) -> Packet {
+        Packet {
+            pack_type: PacketType::Ack(Ack { fragment_index: 0 }),
+            routing_header: SourceRoutingHeader { hop_index: 1, hops },
+            session_id: 0,
+        }
+    }
+
+    fn neighbor_reports(controller_recv: &Receiver<DroneEvent>) -> Vec<Vec<u8>> {
+        controller_recv
+            .try_iter()
+            .filter_map(|event| match event {
+                DroneEvent::PacketSent(packet) => Report::parse(&packet),
+                _ => None,
+            })
+            .filter(|report| report.kind == ReportKind::NeighborState)
+            .map(|report| report.payload[..2].to_vec())
+            .collect()
+    }
+
+    #[test]
+    fn test_closed_link_goes_down() {
+        let (mut drone, _, controller_recv) = setup_drone();
+        let (neighbor_1_sender, neighbor_1_receiver) = unbounded();
+        drone.packet_send.insert(1, Box::new(neighbor_1_sender));
+        drop(neighbor_1_receiver);
+
+        assert!(drone
+            .send_to_neighbor(1, ack(vec![RB_DRONE_ID, 1]))
+            .is_err());
+        assert!(!drone.packet_send.contains_key(&1));
+        assert!(drone.packet_send.is_empty());
+
+        let info = drone.neighbor(1).unwrap();
+        assert_eq!(info.state, LinkState::Down);
+        assert_eq!(info.failures, 1);
+
+        drone.check_neighbors();
+        assert_eq!(
+            neighbor_reports(&controller_recv),
+            vec![vec![1, LinkState::Up as u8], vec![1, LinkState::Down as u8]]
+        );
+    }
+
+    #[test]
+    fn test_failures_make_link_suspect_before_down() {
+        let (mut drone, _, _) = setup_drone();
+        drone.set_neighbor_max_failures(3);
+        drone.packet_send.insert(2, Box::new(FailingLink));
+
+        assert!(drone
+            .send_to_neighbor(2, ack(vec![RB_DRONE_ID, 2]))
+            .is_err());
+        assert_eq!(drone.neighbor(2).unwrap().state, LinkState::Suspect);
+        assert!(drone.packet_send.contains_key(&2));
+
+        assert!(drone
+            .send_to_neighbor(2, ack(vec![RB_DRONE_ID, 2]))
+            .is_err());
+        assert!(drone
+            .send_to_neighbor(2, ack(vec![RB_DRONE_ID, 2]))
+            .is_err());
+        let info = drone.neighbor(2).unwrap();
+        assert_eq!(info.state, LinkState::Down);
+        assert_eq!(info.consecutive_failures, 3);
+        assert!(!drone.packet_send.contains_key(&2));
+    }
+
+    #[test]
+    fn test_silent_neighbor_becomes_suspect() {
+        let (mut drone, _, controller_recv) = setup_drone();
+        let (neighbor_1_sender, _neighbor_1_receiver) = unbounded();
+        drone.packet_send.insert(1, Box::new(neighbor_1_sender));
+        drone.set_neighbor_silence_timeout(Some(Duration::ZERO));
+        std::thread::sleep(Duration::from_millis(5));
+
+        drone.check_neighbors();
+        assert_eq!(drone.neighbor(1).unwrap().state, LinkState::Suspect);
+
+        // a packet from the neighbor brings it back up
+        drone.set_neighbor_silence_timeout(Some(Duration::from_secs(60)));
+        drone.note_received(&ack(vec![1, RB_DRONE_ID, 2]));
+        let info = drone.neighbor(1).unwrap();
+        assert_eq!(info.state, LinkState::Up);
+        assert!(info.last_received.is_some());
+
+        drone.check_neighbors();
+        assert_eq!(
+            neighbor_reports(&controller_recv),
+            vec![
+                vec![1, LinkState::Up as u8],
+                vec![1, LinkState::Suspect as u8],
+                vec![1, LinkState::Up as u8]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_remove_sender_reports_down() {
+        let (mut drone, _, controller_recv) = setup_drone();
+        let (neighbor_1_sender, _neighbor_1_receiver) = unbounded();
+        drone.handle_command(DroneCommand::AddSender(1, neighbor_1_sender));
+        drone.handle_command(DroneCommand::RemoveSender(1));
+        assert!(drone.neighbor(1).is_none());
+
+        drone.check_neighbors();
+        assert_eq!(
+            neighbor_reports(&controller_recv),
+            vec![vec![1, LinkState::Up as u8], vec![1, LinkState::Down as u8]]
+        );
+    }
+
+    #[test]
+    fn test_reports_can_be_disabled() {
+        let (mut drone, _, controller_recv) = setup_drone();
+        drone.set_controller_reports(false);
+        let (neighbor_1_sender, _neighbor_1_receiver) = unbounded();
+        drone.handle_command(DroneCommand::AddSender(1, neighbor_1_sender));
+        drone.handle_command(DroneCommand::RemoveSender(1));
+
+        drone.check_neighbors();
+        assert!(controller_recv.try_iter().next().is_none());
+    }
+
+    #[test]
+    fn test_previous_hop() {
+        assert_eq!(previous_hop(&ack(vec![1, RB_DRONE_ID, 2])), Some(1));
+
+        let mut first_hop = ack(vec![1, RB_DRONE_ID]);
+        first_hop.routing_header.hop_index = 0;
+        assert_eq!(previous_hop(&first_hop), None);
+
+        let flood = Packet {
+            pack_type: PacketType::FloodRequest(FloodRequest {
+                flood_id: 1,
+                initiator_id: 5,
+                path_trace: vec![(5, Client)],
+            }),
+            routing_header: SourceRoutingHeader {
+                hop_index: 0,
+                hops: vec![],
+            },
+            session_id: 0,
+        };
+        assert_eq!(previous_hop(&flood), Some(5));
+    }
+}
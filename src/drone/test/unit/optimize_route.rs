@@ -8,9 +8,9 @@ mod route {
     fn test_optimize_route() {
         let (mut drone, _, _) = setup_drone();
         let (neighbor_2_sender, _check_recv) = unbounded();
-        drone.packet_send.insert(2, neighbor_2_sender);
+        drone.packet_send.insert(2, Box::new(neighbor_2_sender));
         let (neighbor_3_sender, _check_recv) = unbounded();
-        drone.packet_send.insert(3, neighbor_3_sender);
+        drone.packet_send.insert(3, Box::new(neighbor_3_sender));
 
         let path: Vec<NodeId> = vec![];
         assert_eq!(drone.optimize_route(&path), vec![]);
@@ -0,0 +1,153 @@
+#[cfg(test)]
+mod limits {
+    use crate::drone::test::common::{flood_request, fragment, setup_drone, RB_DRONE_ID};
+    use crate::flood_trace::TracePolicy;
+    use crate::rate_limit::{LimitScope, RateLimit, TokenBucket};
+    use crate::report::{Report, ReportKind};
+    use crate::scheduler::TrafficClass;
+    use crossbeam_channel::{unbounded, Receiver};
+    use std::time::{Duration, Instant};
+    use wg_2024::controller::DroneEvent;
+    use wg_2024::packet::{NackType, NodeType, PacketType};
+
+    fn reports(controller_recv: &Receiver<DroneEvent>) -> Vec<Report> {
+        controller_recv
+            .try_iter()
+            .filter_map(|event| match event {
+                DroneEvent::PacketSent(packet) => Report::parse(&packet),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_token_bucket_refills_over_time() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(RateLimit::new(2.0, 2), start);
+        assert!(bucket.try_take(start));
+        assert!(bucket.try_take(start));
+        assert!(!bucket.try_take(start));
+        // 2 tokens per second: one token after 500ms
+        assert!(bucket.try_take(start + Duration::from_millis(500)));
+        assert!(!bucket.try_take(start + Duration::from_millis(500)));
+        // the burst caps the refill
+        assert!(bucket.try_take(start + Duration::from_secs(10)));
+        assert!(bucket.try_take(start + Duration::from_secs(10)));
+        assert!(!bucket.try_take(start + Duration::from_secs(10)));
+    }
+
+    #[test]
+    fn test_excess_fragments_are_dropped_and_reported() {
+        let (mut drone, _, controller_recv) = setup_drone();
+        let (neighbor_1_sender, neighbor_1_receiver) = unbounded();
+        drone.packet_send.insert(1, Box::new(neighbor_1_sender));
+        drone.set_session_rate_limit(Some(RateLimit::new(0.0, 2)));
+
+        for i in 0..3 {
+            drone.enqueue_packet(fragment(i, 7, vec![1, RB_DRONE_ID, 2]));
+        }
+        // another session has its own bucket
+        drone.enqueue_packet(fragment(0, 8, vec![1, RB_DRONE_ID, 2]));
+
+        let nack = neighbor_1_receiver.try_recv().expect("Nack not sent");
+        match nack.pack_type {
+            PacketType::Nack(nack) => {
+                assert_eq!(nack.fragment_index, 2);
+                assert_eq!(nack.nack_type, NackType::Dropped);
+            }
+            _ => panic!("Unexpected packet: {:?}", nack.pack_type),
+        }
+        assert!(neighbor_1_receiver.try_recv().is_err());
+
+        let reports = reports(&controller_recv);
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].drone_id, RB_DRONE_ID);
+        assert_eq!(reports[0].kind, ReportKind::RateLimited);
+        let expected = LimitScope::Session(7).encode(TrafficClass::Data);
+        assert_eq!(&reports[0].payload[..expected.len()], expected.as_slice());
+
+        let stats = drone.stats();
+        assert_eq!(stats.rate_limited_fragments, 1);
+        assert_eq!(stats.queue.data_depth, 3);
+    }
+
+    #[test]
+    fn test_neighbor_override_replaces_default_limit() {
+        let (mut drone, _, _) = setup_drone();
+        let (neighbor_1_sender, _neighbor_1_receiver) = unbounded();
+        drone.packet_send.insert(1, Box::new(neighbor_1_sender));
+        drone.set_neighbor_rate_limit(Some(RateLimit::new(0.0, 1)));
+        drone.set_neighbor_rate_limit_for(1, Some(RateLimit::new(0.0, 5)));
+
+        for i in 0..5 {
+            drone.enqueue_packet(fragment(i, 7, vec![1, RB_DRONE_ID, 2]));
+        }
+        assert_eq!(drone.stats().rate_limited_fragments, 0);
+
+        drone.enqueue_packet(fragment(5, 7, vec![1, RB_DRONE_ID, 2]));
+        assert_eq!(drone.stats().rate_limited_fragments, 1);
+    }
+
+    #[test]
+    fn test_excess_floods_are_answered() {
+        let (mut drone, _, controller_recv) = setup_drone();
+        let (neighbor_1_sender, neighbor_1_receiver) = unbounded();
+        let (neighbor_2_sender, neighbor_2_receiver) = unbounded();
+        drone.packet_send.insert(1, Box::new(neighbor_1_sender));
+        drone.packet_send.insert(2, Box::new(neighbor_2_sender));
+        drone.set_initiator_rate_limit(Some(RateLimit::new(0.0, 1)));
+
+        drone.handle_flood_request(flood_request(1, 1, vec![(1, NodeType::Client)]));
+        assert!(neighbor_2_receiver.try_recv().is_ok());
+
+        drone.handle_flood_request(flood_request(2, 1, vec![(1, NodeType::Client)]));
+        let response = neighbor_1_receiver
+            .try_recv()
+            .expect("FloodResponse not sent");
+        match response.pack_type {
+            PacketType::FloodResponse(response) => {
+                assert_eq!(response.flood_id, 2);
+                assert_eq!(
+                    response.path_trace,
+                    vec![(1, NodeType::Client), (RB_DRONE_ID, NodeType::Drone)]
+                );
+            }
+            _ => panic!("Unexpected packet: {:?}", response.pack_type),
+        }
+        assert!(neighbor_2_receiver.try_recv().is_err());
+        assert!(drone.received_floods.contains(&(2, 1)));
+
+        // a duplicate is answered without taking a token
+        drone.handle_flood_request(flood_request(1, 1, vec![(1, NodeType::Client)]));
+        assert!(neighbor_1_receiver.try_recv().is_ok());
+
+        let reports = reports(&controller_recv);
+        assert_eq!(reports.len(), 1);
+        let expected = LimitScope::Initiator(1).encode(TrafficClass::Flood);
+        assert_eq!(&reports[0].payload[..expected.len()], expected.as_slice());
+        assert_eq!(drone.stats().rate_limited_floods, 1);
+    }
+
+    #[test]
+    fn test_tampered_floods_take_no_token() {
+        let (mut drone, _, _) = setup_drone();
+        let (neighbor_1_sender, _neighbor_1_receiver) = unbounded();
+        let (neighbor_2_sender, neighbor_2_receiver) = unbounded();
+        drone.packet_send.insert(1, Box::new(neighbor_1_sender));
+        drone.packet_send.insert(2, Box::new(neighbor_2_sender));
+        drone.set_flood_trace_policy(TracePolicy::Drop);
+        drone.set_initiator_rate_limit(Some(RateLimit::new(0.0, 1)));
+
+        // claims to come from 3, which is not a neighbor
+        let mut tampered = flood_request(1, 1, vec![(1, NodeType::Client)]);
+        if let PacketType::FloodRequest(flood_request) = &mut tampered.pack_type {
+            flood_request.path_trace.push((3, NodeType::Drone));
+        }
+        drone.handle_flood_request(tampered);
+        assert_eq!(drone.stats().rate_limited_floods, 0);
+
+        drone.handle_flood_request(flood_request(2, 1, vec![(1, NodeType::Client)]));
+        assert!(neighbor_2_receiver.try_recv().is_ok());
+        assert_eq!(drone.stats().rate_limited_floods, 0);
+    }
+}
@@ -0,0 +1,156 @@
+#[cfg(test)]
+mod scores {
+    use crate::drone::test::common::{setup_drone, RB_DRONE_ID};
+    use crate::hunt::PACKET_CONST;
+    use crate::report::{Report, ReportKind};
+    use crate::reputation::{decode_ranking, ReputationTable};
+    use crate::telemetry::TelemetryEvent;
+    use crossbeam_channel::{unbounded, Receiver};
+    use std::time::{Duration, Instant};
+    use wg_2024::controller::DroneEvent;
+    use wg_2024::network::SourceRoutingHeader;
+    use wg_2024::packet::{Nack, NackType, Packet, PacketType};
+
+    fn nack(nack_type: NackType) -> Nack {
+        Nack {
+            fragment_index: 0,
+            nack_type,
+        }
+    }
+
+    fn nack_packet(nack_type: NackType) -> Packet {
+        Packet {
+            pack_type: PacketType::Nack(nack(nack_type)),
+            routing_header: SourceRoutingHeader {
+                hop_index: 1,
+                hops: vec![5, RB_DRONE_ID, 1],
+            },
+            session_id: 3,
+        }
+    }
+
+    fn hunts(controller_recv: &Receiver<DroneEvent>) -> usize {
+        controller_recv
+            .try_iter()
+            .filter(|event| match event {
+                DroneEvent::PacketSent(packet) => matches!(
+                    &packet.pack_type,
+                    PacketType::MsgFragment(fragment) if fragment.length == PACKET_CONST
+                ),
+                _ => false,
+            })
+            .count()
+    }
+
+    #[test]
+    fn test_blame_by_nack_type() {
+        let table = ReputationTable::default();
+        let hops = [5, 6, RB_DRONE_ID, 1];
+        assert_eq!(table.blame(&nack(NackType::Dropped), &hops), Some((5, 1.0)));
+        assert_eq!(
+            table.blame(&nack(NackType::ErrorInRouting(8)), &hops),
+            Some((8, 1.0))
+        );
+        assert_eq!(
+            table.blame(&nack(NackType::UnexpectedRecipient(5)), &hops),
+            Some((6, 1.0))
+        );
+        assert_eq!(
+            table.blame(&nack(NackType::DestinationIsDrone), &hops),
+            Some((1, 1.0))
+        );
+    }
+
+    #[test]
+    fn test_scores_decay() {
+        let mut table = ReputationTable::default();
+        let start = Instant::now();
+        table.penalize(5, 4.0, start);
+        table.penalize(6, 1.0, start);
+
+        let later = start + Duration::from_secs(60);
+        assert!((table.score_at(5, later) - 2.0).abs() < 1e-9);
+        assert_eq!(
+            table
+                .ranking_at(later, 0.0)
+                .iter()
+                .map(|&(id, _)| id)
+                .collect::<Vec<_>>(),
+            vec![5, 6]
+        );
+        assert_eq!(table.ranking_at(later, 1.0).len(), 1);
+    }
+
+    #[test]
+    fn test_relayed_nacks_rank_suspects() {
+        let (mut drone, _, _) = setup_drone();
+        let (neighbor_1_sender, neighbor_1_receiver) = unbounded();
+        drone.packet_send.insert(1, Box::new(neighbor_1_sender));
+
+        drone.forward_packet(nack_packet(NackType::Dropped), true);
+        drone.forward_packet(nack_packet(NackType::Dropped), true);
+        drone.forward_packet(nack_packet(NackType::ErrorInRouting(7)), true);
+        assert_eq!(neighbor_1_receiver.try_iter().count(), 3);
+
+        let suspects = drone.suspects();
+        assert_eq!(suspects.len(), 2);
+        assert_eq!(suspects[0].0, 5);
+        assert!(suspects[0].1 > 1.9);
+        assert_eq!(suspects[1].0, 7);
+        assert!(drone.reputation(9) == 0.0);
+
+        // the scores survive a snapshot
+        let state = drone.snapshot();
+        assert_eq!(state.reputation.len(), 2);
+        assert_eq!(state.reputation[0].0, 5);
+    }
+
+    #[test]
+    fn test_hunt_threshold() {
+        let (mut drone, _, controller_recv) = setup_drone();
+        let (neighbor_1_sender, _neighbor_1_receiver) = unbounded();
+        drone.packet_send.insert(1, Box::new(neighbor_1_sender));
+        drone.set_hunt_mode(true);
+        drone.set_hunt_threshold(Some(1.5));
+
+        drone.forward_packet(nack_packet(NackType::Dropped), true);
+        assert_eq!(hunts(&controller_recv), 0);
+
+        drone.forward_packet(nack_packet(NackType::Dropped), true);
+        assert_eq!(hunts(&controller_recv), 1);
+    }
+
+    #[test]
+    fn test_ranking_published_to_controller() {
+        let (mut drone, _, controller_recv) = setup_drone();
+        let (neighbor_1_sender, _neighbor_1_receiver) = unbounded();
+        drone.packet_send.insert(1, Box::new(neighbor_1_sender));
+        drone.forward_packet(nack_packet(NackType::Dropped), true);
+        controller_recv.try_iter().for_each(drop);
+
+        let (telemetry_send, telemetry_recv) = unbounded();
+        drone.set_telemetry(Some(telemetry_send));
+        drone.set_reputation_reports(Some(Duration::ZERO));
+        drone.publish_reputation();
+
+        match telemetry_recv.try_recv().map(|telemetry| telemetry.event) {
+            Ok(TelemetryEvent::Reputation(ranking)) => {
+                assert_eq!(ranking.len(), 1);
+                assert_eq!(ranking[0].0, 5);
+            }
+            other => panic!("Unexpected telemetry: {other:?}"),
+        }
+
+        let report = controller_recv
+            .try_iter()
+            .find_map(|event| match event {
+                DroneEvent::PacketSent(packet) => Report::parse(&packet),
+                _ => None,
+            })
+            .expect("Reputation report not sent");
+        assert_eq!(report.kind, ReportKind::Reputation);
+        let ranking = decode_ranking(&report.payload);
+        assert_eq!(ranking.len(), 1);
+        assert_eq!(ranking[0].0, 5);
+    }
+}
@@ -0,0 +1,181 @@
+#[cfg(test)]
+mod queueing {
+    use crate::drone::test::common::{flood_request, fragment, setup_drone, RB_DRONE_ID};
+    use crate::scheduler::{PacketScheduler, SchedulingPolicy, TrafficClass};
+    use crossbeam_channel::unbounded;
+    use wg_2024::controller::DroneEvent;
+    use wg_2024::network::SourceRoutingHeader;
+    use wg_2024::packet::{Ack, NackType, Packet, PacketType};
+
+    fn ack(index: u64) -> Packet {
+        Packet {
+            pack_type: PacketType::Ack(Ack {
+                fragment_index: index,
+            }),
+            routing_header: SourceRoutingHeader {
+                hop_index: 1,
+                hops: vec![2, RB_DRONE_ID, 1],
+            },
+            session_id: 7,
+        }
+    }
+
+    #[test]
+    fn test_strict_priority_serves_control_first() {
+        let mut scheduler = PacketScheduler::new(SchedulingPolicy::StrictPriority, 10);
+        scheduler
+            .push(fragment(0, 7, vec![1, RB_DRONE_ID, 2]))
+            .unwrap();
+        scheduler.push(flood_request(1, 1, vec![])).unwrap();
+        scheduler.push(ack(0)).unwrap();
+
+        let classes: Vec<TrafficClass> = std::iter::from_fn(|| scheduler.pop())
+            .map(|p| TrafficClass::of(&p))
+            .collect();
+        assert_eq!(
+            classes,
+            vec![
+                TrafficClass::Control,
+                TrafficClass::Flood,
+                TrafficClass::Data
+            ]
+        );
+        assert!(scheduler.is_empty());
+    }
+
+    #[test]
+    fn test_weighted_policy_interleaves_classes() {
+        let mut scheduler = PacketScheduler::new(
+            SchedulingPolicy::Weighted {
+                control: 1,
+                flood: 1,
+                data: 2,
+            },
+            10,
+        );
+        for i in 0..4 {
+            scheduler
+                .push(fragment(i, 7, vec![1, RB_DRONE_ID, 2]))
+                .unwrap();
+        }
+        for i in 0..2 {
+            scheduler.push(ack(i)).unwrap();
+        }
+
+        let classes: Vec<TrafficClass> = std::iter::from_fn(|| scheduler.pop())
+            .map(|p| TrafficClass::of(&p))
+            .collect();
+        assert_eq!(
+            classes,
+            vec![
+                TrafficClass::Control,
+                TrafficClass::Data,
+                TrafficClass::Data,
+                TrafficClass::Control,
+                TrafficClass::Data,
+                TrafficClass::Data,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_data_queue_is_bounded() {
+        let mut scheduler = PacketScheduler::new(SchedulingPolicy::StrictPriority, 2);
+        scheduler
+            .push(fragment(0, 7, vec![1, RB_DRONE_ID, 2]))
+            .unwrap();
+        scheduler
+            .push(fragment(1, 7, vec![1, RB_DRONE_ID, 2]))
+            .unwrap();
+        assert!(scheduler
+            .push(fragment(2, 7, vec![1, RB_DRONE_ID, 2]))
+            .is_err());
+        // control packets are never rejected
+        scheduler.push(ack(0)).unwrap();
+
+        let stats = scheduler.stats();
+        assert_eq!(stats.data_depth, 2);
+        assert_eq!(stats.control_depth, 1);
+        assert_eq!(stats.dropped, 1);
+
+        scheduler.pop();
+        scheduler.pop();
+        let stats = scheduler.stats();
+        assert_eq!(stats.data_depth, 1);
+        assert_eq!(stats.control_depth, 0);
+        assert_eq!(stats.peak_data_depth, 2);
+    }
+
+    #[test]
+    fn test_full_data_queue_sends_dropped_nack() {
+        let (mut drone, _, controller_recv) = setup_drone();
+        let (neighbor_1_sender, neighbor_1_receiver) = unbounded();
+        drone.packet_send.insert(1, Box::new(neighbor_1_sender));
+        drone.set_data_queue_capacity(1);
+
+        drone.enqueue_packet(fragment(0, 7, vec![1, RB_DRONE_ID, 2]));
+        drone.enqueue_packet(fragment(1, 7, vec![1, RB_DRONE_ID, 2]));
+
+        if let Ok(packet) = neighbor_1_receiver.recv_timeout(std::time::Duration::from_secs(1)) {
+            match packet.pack_type {
+                PacketType::Nack(nack) => {
+                    assert_eq!(nack.fragment_index, 1);
+                    assert_eq!(nack.nack_type, NackType::Dropped);
+                    assert_eq!(packet.routing_header.hops, vec![RB_DRONE_ID, 1]);
+                }
+                _ => panic!("Unexpected packet: {:?}", packet.pack_type),
+            }
+        } else {
+            panic!("Timeout: no packet received");
+        }
+
+        match controller_recv.recv_timeout(std::time::Duration::from_secs(1)) {
+            Ok(DroneEvent::PacketDropped(packet)) => assert_eq!(packet.session_id, 7),
+            other => panic!("Unexpected event: {other:?}"),
+        }
+
+        let stats = drone.stats();
+        assert_eq!(stats.queue.data_depth, 1);
+        assert_eq!(stats.queue.dropped, 1);
+    }
+
+    #[test]
+    fn test_full_data_queue_keeps_misrouted_nack() {
+        let (mut drone, _, _) = setup_drone();
+        let (neighbor_1_sender, neighbor_1_receiver) = unbounded();
+        drone.packet_send.insert(1, Box::new(neighbor_1_sender));
+        drone.set_data_queue_capacity(1);
+
+        drone.enqueue_packet(fragment(0, 7, vec![1, RB_DRONE_ID, 2]));
+        // meant for drone 3
+        drone.enqueue_packet(fragment(1, 7, vec![1, 3, 2]));
+
+        match neighbor_1_receiver
+            .try_recv()
+            .map(|packet| packet.pack_type)
+        {
+            Ok(PacketType::Nack(nack)) => {
+                assert_eq!(nack.fragment_index, 1);
+                assert_eq!(nack.nack_type, NackType::UnexpectedRecipient(RB_DRONE_ID));
+            }
+            other => panic!("Unexpected packet: {other:?}"),
+        }
+        assert_eq!(drone.stats().queue.data_depth, 1);
+    }
+
+    #[test]
+    fn test_dispatch_forwards_queued_packets() {
+        let (mut drone, _, _) = setup_drone();
+        let (neighbor_1_sender, neighbor_1_receiver) = unbounded();
+        drone.packet_send.insert(1, Box::new(neighbor_1_sender));
+
+        drone.enqueue_packet(ack(3));
+        let packet = drone.scheduler.pop().unwrap();
+        drone.dispatch_packet(packet);
+
+        match neighbor_1_receiver.recv_timeout(std::time::Duration::from_secs(1)) {
+            Ok(packet) => assert_eq!(packet.routing_header.hop_index, 2),
+            Err(e) => panic!("Timeout: no packet received: {e}"),
+        }
+    }
+}
@@ -11,9 +11,9 @@ mod nack {
         // Setup
         let (mut drone, _, _) = setup_drone();
         let (neighbor_2_sender, _) = unbounded();
-        drone.packet_send.insert(2, neighbor_2_sender); // Nodo 2 come "neighbor"
+        drone.packet_send.insert(2, Box::new(neighbor_2_sender)); // Nodo 2 come "neighbor"
         let (neighbor_5_sender, check_recv) = unbounded();
-        drone.packet_send.insert(5, neighbor_5_sender); // Nodo 5 come "neighbor"
+        drone.packet_send.insert(5, Box::new(neighbor_5_sender)); // Nodo 5 come "neighbor"
 
         let packet = Packet {
             pack_type: PacketType::MsgFragment(Fragment {
@@ -53,9 +53,9 @@ mod nack {
         // Setup
         let (mut drone, _, _) = setup_drone();
         let (neighbor_2_sender, check_recv) = unbounded();
-        drone.packet_send.insert(2, neighbor_2_sender); // Nodo 2 come "neighbor"
+        drone.packet_send.insert(2, Box::new(neighbor_2_sender)); // Nodo 2 come "neighbor"
         let (neighbor_5_sender, _) = unbounded();
-        drone.packet_send.insert(5, neighbor_5_sender); // Nodo 5 come "neighbor"
+        drone.packet_send.insert(5, Box::new(neighbor_5_sender)); // Nodo 5 come "neighbor"
 
         let packet = Packet {
             pack_type: PacketType::MsgFragment(Fragment {
@@ -87,7 +87,7 @@ mod nack {
         // Setup
         let (mut drone, _, _) = setup_drone();
         let (neighbor_2_sender, check_recv) = unbounded();
-        drone.packet_send.insert(2, neighbor_2_sender); // Nodo 2 come "neighbor"
+        drone.packet_send.insert(2, Box::new(neighbor_2_sender)); // Nodo 2 come "neighbor"
 
         let packet = Packet {
             pack_type: PacketType::MsgFragment(Fragment {
@@ -163,10 +163,10 @@ mod nack {
         // Setup
         let (mut drone, _, controller_recv) = setup_drone();
         let (neighbor_2_sender, _) = unbounded();
-        drone.packet_send.insert(2, neighbor_2_sender); // Nodo 2 come "neighbor"
+        drone.packet_send.insert(2, Box::new(neighbor_2_sender)); // Nodo 2 come "neighbor"
         let (neighbor_5_sender, neighbor_5_recv) = unbounded();
         drop(neighbor_5_recv); // Nodo 5 come "neighbor"
-        drone.packet_send.insert(5, neighbor_5_sender); // Nodo 5 come "neighbor"
+        drone.packet_send.insert(5, Box::new(neighbor_5_sender)); // Nodo 5 come "neighbor"
 
         let packet = Packet {
             pack_type: PacketType::MsgFragment(Fragment {
@@ -212,11 +212,11 @@ mod nack {
         // Setup
         let (mut drone, _, _) = setup_drone();
         let (neighbor_2_sender, _) = unbounded();
-        drone.packet_send.insert(2, neighbor_2_sender); // Nodo 2 come "neighbor"
+        drone.packet_send.insert(2, Box::new(neighbor_2_sender)); // Nodo 2 come "neighbor"
         let (neighbor_5_sender, check_recv) = unbounded();
-        drone.packet_send.insert(5, neighbor_5_sender); // Nodo 5 come "neighbor"
+        drone.packet_send.insert(5, Box::new(neighbor_5_sender)); // Nodo 5 come "neighbor"
         let (neighbor_3_sender, _) = unbounded();
-        drone.packet_send.insert(3, neighbor_3_sender); // Nodo 5 come "neighbor"
+        drone.packet_send.insert(3, Box::new(neighbor_3_sender)); // Nodo 5 come "neighbor"
 
         drone.set_optimized_routing(true);
 
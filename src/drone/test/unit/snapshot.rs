@@ -0,0 +1,117 @@
+#[cfg(test)]
+mod restart {
+    use crate::arq::ArqSettings;
+    use crate::chaos::ChaosPlan;
+    use crate::crash::{CrashPolicy, InFlightPolicy};
+    use crate::dedup::{DedupSettings, DuplicatePolicy};
+    use crate::drone::test::common::{flood_request, setup_drone, RB_DRONE_ID};
+    use crate::flood_trace::TracePolicy;
+    use crate::rate_limit::RateLimit;
+    use crate::scheduler::SchedulingPolicy;
+    use crate::snapshot::{DroneChannels, DroneSettings, DroneState};
+    use crate::RustBustersDrone;
+    use crossbeam_channel::unbounded;
+    use std::collections::HashMap;
+    use std::time::Duration;
+    use wg_2024::packet::NodeType::Client;
+    use wg_2024::packet::PacketType;
+
+    #[test]
+    fn test_snapshot_contains_caches_and_flags() {
+        let (mut drone, _, _) = setup_drone();
+        drone.set_hunt_mode(true);
+        drone.received_floods.insert((4, 1));
+        drone.received_floods.insert((2, 1));
+        drone.stats.packets_received = 12;
+
+        let state = drone.snapshot();
+        assert_eq!(state.id, RB_DRONE_ID);
+        assert_eq!(state.pdr, 10);
+        assert!(state.hunt_mode);
+        assert!(!state.optimized_routing);
+        assert_eq!(state.received_floods, vec![(2, 1), (4, 1)]);
+        assert_eq!(state.stats.packets_received, 12);
+    }
+
+    #[test]
+    fn test_restore_keeps_flood_cache() {
+        let (mut drone, _, _) = setup_drone();
+        let (neighbor_1_sender, _neighbor_1_receiver) = unbounded();
+        drone.packet_send.insert(1, Box::new(neighbor_1_sender));
+        drone.handle_flood_request(flood_request(9, 1, vec![(1, Client)]));
+
+        let bytes = bincode::serialize(&drone.snapshot()).unwrap();
+        let state: DroneState = bincode::deserialize(&bytes).unwrap();
+
+        let (controller_send, _controller_recv) = unbounded();
+        let (_command_send, command_recv) = unbounded();
+        let (_packet_send, packet_recv) = unbounded();
+        let (neighbor_1_sender, neighbor_1_receiver) = unbounded();
+        let (neighbor_2_sender, neighbor_2_receiver) = unbounded();
+        let mut restored = RustBustersDrone::restore(
+            state,
+            DroneChannels::new(
+                controller_send,
+                command_recv,
+                packet_recv,
+                HashMap::from([(1, neighbor_1_sender), (2, neighbor_2_sender)]),
+            ),
+        );
+        assert_eq!(restored.pdr, 10);
+        assert!(restored.running);
+
+        // The flood was already handled before the restart: it is answered, not spread
+        restored.handle_flood_request(flood_request(9, 1, vec![(1, Client)]));
+        assert!(matches!(
+            neighbor_1_receiver.try_recv().map(|p| p.pack_type),
+            Ok(PacketType::FloodResponse(_))
+        ));
+        assert!(neighbor_2_receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_restore_keeps_runtime_settings() {
+        let (mut drone, _, _) = setup_drone();
+        drone.set_max_flood_path_len_for(3, Some(4));
+        drone.set_neighbor_rate_limit_for(1, Some(RateLimit::new(5.0, 10)));
+        drone.set_crash_policy(CrashPolicy {
+            drain_timeout: Duration::from_millis(250),
+            in_flight: InFlightPolicy::Shortcut,
+            close_neighbors: false,
+        });
+        drone.set_scheduling_policy(SchedulingPolicy::Weighted {
+            control: 4,
+            flood: 2,
+            data: 1,
+        });
+        drone.set_data_queue_capacity(16);
+        drone.set_flood_trace_policy(TracePolicy::Quarantine);
+        drone.set_duplicate_suppression(Some(DedupSettings {
+            policy: DuplicatePolicy::Suppress,
+            ..DedupSettings::default()
+        }));
+        drone.set_local_arq(Some(ArqSettings::default()));
+        drone.set_chaos_plan(Some(ChaosPlan {
+            seed: 3,
+            steps: Vec::new(),
+        }));
+        drone.set_hunt_threshold(Some(2.5));
+        drone.set_reputation_half_life(Duration::from_secs(30));
+        drone.set_reputation_reports(Some(Duration::from_secs(5)));
+        let settings = drone.snapshot().settings;
+        assert_ne!(settings, DroneSettings::default());
+
+        let bytes = bincode::serialize(&drone.snapshot()).unwrap();
+        let state: DroneState = bincode::deserialize(&bytes).unwrap();
+        let (controller_send, _controller_recv) = unbounded();
+        let (_command_send, command_recv) = unbounded();
+        let (_packet_send, packet_recv) = unbounded();
+        let restored = RustBustersDrone::restore(
+            state,
+            DroneChannels::new(controller_send, command_recv, packet_recv, HashMap::new()),
+        );
+        assert_eq!(restored.snapshot().settings, settings);
+        assert_eq!(restored.crash_policy.in_flight, InFlightPolicy::Shortcut);
+        assert_eq!(restored.scheduler.data_capacity(), 16);
+    }
+}
@@ -0,0 +1,117 @@
+#[cfg(all(test, feature = "sounds"))]
+mod tones {
+    use crate::drone::test::common::{fragment, setup_drone, sound_recorder, RB_DRONE_ID};
+    use crate::sonification::{SonificationSettings, Sonifier, Timbre, Tone};
+    use crate::sounds::sounds_feat::{Sound, SoundEvent};
+    use crossbeam_channel::unbounded;
+    use std::collections::HashSet;
+    use std::time::{Duration, Instant};
+    use wg_2024::packet::{Ack, FloodRequest, FloodResponse, Nack, NackType, PacketType};
+
+    #[test]
+    fn test_timbre_per_packet_type() {
+        let packet_types = [
+            fragment(0, 7, vec![1, RB_DRONE_ID, 2]).pack_type,
+            PacketType::Ack(Ack { fragment_index: 0 }),
+            PacketType::Nack(Nack {
+                fragment_index: 0,
+                nack_type: NackType::Dropped,
+            }),
+            PacketType::FloodRequest(FloodRequest {
+                flood_id: 1,
+                initiator_id: 1,
+                path_trace: vec![],
+            }),
+            PacketType::FloodResponse(FloodResponse {
+                flood_id: 1,
+                path_trace: vec![],
+            }),
+        ];
+        let timbres: HashSet<Timbre> = packet_types.iter().map(Timbre::of).collect();
+        assert_eq!(timbres.len(), packet_types.len());
+    }
+
+    #[test]
+    fn test_pitch_from_drone_id() {
+        let settings = SonificationSettings::default();
+        assert!((settings.pitch(0) - settings.base_frequency).abs() < 0.01);
+        assert!((settings.pitch(12) - 2.0 * settings.base_frequency).abs() < 0.01);
+        assert!(settings.pitch(1) > settings.pitch(0));
+    }
+
+    #[test]
+    fn test_volume_follows_forwarding_rate() {
+        let mut sonifier = Sonifier::default();
+        let start = Instant::now();
+        assert!(sonifier.observe(1, Timbre::Sine, start).is_none());
+
+        let settings = SonificationSettings {
+            tone_length: Duration::from_millis(50),
+            full_rate: 100.0,
+            ..SonificationSettings::default()
+        };
+        let (mut drone, _, _) = setup_drone();
+        drone.set_sonification(Some(settings));
+        let sonifier = &mut drone.sonifier;
+
+        let (_, first_volume) = sonifier.observe(1, Timbre::Sine, start).unwrap();
+        // 200 packets in 200ms: one tone every 50ms, louder and louder
+        let mut volumes = vec![first_volume];
+        for i in 1..200 {
+            let now = start + Duration::from_millis(i);
+            if let Some((tone, volume)) = sonifier.observe(1, Timbre::Sine, now) {
+                assert_eq!(tone.duration, settings.tone_length);
+                volumes.push(volume);
+            }
+        }
+        assert_eq!(volumes.len(), 4);
+        assert!(volumes.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(sonifier.rate() > 100.0);
+    }
+
+    #[test]
+    fn test_forwarded_packet_is_recorded_as_tone() {
+        let drone_id = 111;
+        let (mut drone, _, _) = setup_drone();
+        drone.id = drone_id;
+        drone.pdr = 0;
+        let (neighbor_2_sender, _neighbor_2_receiver) = unbounded();
+        drone.packet_send.insert(2, Box::new(neighbor_2_sender));
+        drone.enable_sound();
+
+        // sounds enabled, but no sonification
+        drone.forward_packet(fragment(0, 7, vec![1, drone_id, 2]), true);
+        assert!(sound_recorder().requests_of(drone_id).is_empty());
+
+        drone.set_sonification(Some(SonificationSettings::default()));
+        drone.forward_packet(fragment(0, 7, vec![1, drone_id, 2]), true);
+        let requests = sound_recorder().requests_of(drone_id);
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].event, SoundEvent::Forward);
+        let Sound::Tone(tone) = requests[0].sound else {
+            panic!("Expected a tone, got {:?}", requests[0].sound);
+        };
+        assert_eq!(tone.timbre, Timbre::Sine);
+        assert!((tone.frequency - SonificationSettings::default().pitch(drone_id)).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_tone_source_lasts_the_tone() {
+        for timbre in [
+            Timbre::Sine,
+            Timbre::Triangle,
+            Timbre::Sawtooth,
+            Timbre::Square,
+            Timbre::Sweep,
+        ] {
+            let tone = Tone {
+                frequency: 440.0,
+                timbre,
+                duration: Duration::from_millis(10),
+            };
+            let samples: Vec<f32> = tone.source().collect();
+            assert!((470..=490).contains(&samples.len()), "{timbre:?}");
+            assert!(samples.iter().any(|&sample| sample.abs() > 0.5));
+        }
+    }
+}
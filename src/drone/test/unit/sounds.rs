@@ -0,0 +1,377 @@
+#[cfg(all(test, feature = "sounds"))]
+mod packs {
+    use crate::drone::test::common::setup_drone;
+    use crate::sounds::sounds_feat::{SoundEvent, SoundManifest, SoundPack, SoundSettings};
+    use std::path::PathBuf;
+
+    const SFX_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/sfx");
+
+    fn manifest_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("rustbusters-sounds-{name}"));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_builtin_pack_is_default() {
+        let (drone, _, _) = setup_drone();
+        let pack = &drone.sound_settings().pack;
+        assert!(pack.sound(SoundEvent::Hunt).is_some());
+        assert!(pack.sound(SoundEvent::FloodSpread).is_none());
+    }
+
+    #[test]
+    fn test_settings_play_every_event_by_default() {
+        let mut settings = SoundSettings::default();
+        assert!(settings.enabled);
+        assert!(settings.plays(SoundEvent::Spawn));
+
+        settings.muted_events.insert(SoundEvent::Spawn);
+        assert!(!settings.plays(SoundEvent::Spawn));
+        assert!(settings.plays(SoundEvent::Crash));
+
+        settings.volume = 0.0;
+        assert!(!settings.plays(SoundEvent::Crash));
+    }
+
+    #[test]
+    fn test_manifest_defaults_to_builtin() {
+        let manifest: SoundManifest = toml::from_str("").unwrap();
+        assert!(manifest.builtin);
+        assert!(manifest.sounds.is_empty());
+
+        let dir = manifest_dir("empty");
+        let pack = SoundPack::from_parsed_manifest(manifest, &dir).unwrap();
+        let builtin = SoundPack::builtin();
+        assert_eq!(
+            pack.sound(SoundEvent::Nack),
+            builtin.sound(SoundEvent::Nack)
+        );
+
+        let manifest: SoundManifest = toml::from_str("builtin = false").unwrap();
+        let pack = SoundPack::from_parsed_manifest(manifest, &dir).unwrap();
+        assert!(pack.sound(SoundEvent::Nack).is_none());
+    }
+
+    #[test]
+    fn test_manifest_events() {
+        let manifest: SoundManifest = toml::from_str(
+            r#"
+            builtin = false
+
+            [sounds]
+            flood_spread = "whoosh.mp3"
+            hunt_suppressed = "meh.mp3"
+            rate_limited = "slow_down.mp3"
+            "#,
+        )
+        .unwrap();
+        assert!(!manifest.builtin);
+        assert_eq!(manifest.sounds.len(), 3);
+        assert_eq!(
+            manifest.sounds[&SoundEvent::HuntSuppressed],
+            PathBuf::from("meh.mp3")
+        );
+        assert!(toml::from_str::<SoundManifest>("[sounds]\nunknown = \"a.mp3\"").is_err());
+    }
+
+    #[test]
+    fn test_load_manifest_relative_to_its_directory() {
+        let dir = manifest_dir("relative");
+        std::fs::copy(format!("{SFX_DIR}/drop.mp3"), dir.join("quack.mp3")).unwrap();
+        let manifest = dir.join("pack.toml");
+        std::fs::write(
+            &manifest,
+            format!("[sounds]\nspawn = \"quack.mp3\"\nrate_limited = \"{SFX_DIR}/nack.mp3\"\n"),
+        )
+        .unwrap();
+
+        let pack = SoundPack::from_manifest(&manifest).unwrap();
+        let builtin = SoundPack::builtin();
+        assert_eq!(
+            pack.sound(SoundEvent::Spawn),
+            builtin.sound(SoundEvent::Drop)
+        );
+        assert_eq!(
+            pack.sound(SoundEvent::RateLimited),
+            builtin.sound(SoundEvent::Nack)
+        );
+        // events missing from the manifest keep the built-in sound
+        assert_eq!(
+            pack.sound(SoundEvent::Crash),
+            builtin.sound(SoundEvent::Crash)
+        );
+        assert!(pack.sound(SoundEvent::FloodSpread).is_none());
+    }
+
+    #[test]
+    fn test_invalid_sounds_are_rejected() {
+        let dir = manifest_dir("invalid");
+        std::fs::write(dir.join("noise.mp3"), "not a sound").unwrap();
+        let manifest = dir.join("pack.toml");
+
+        std::fs::write(&manifest, "[sounds]\ncrash = \"noise.mp3\"\n").unwrap();
+        assert!(SoundPack::from_manifest(&manifest).is_err());
+
+        std::fs::write(&manifest, "[sounds]\ncrash = \"missing.mp3\"\n").unwrap();
+        assert!(SoundPack::from_manifest(&manifest).is_err());
+    }
+}
+
+#[cfg(all(test, feature = "sounds"))]
+mod backends {
+    use crate::drone::test::common::{fragment, setup_drone, sound_recorder};
+    use crate::sounds::sounds_feat::{
+        AudioSystem, RecordingBackend, Sound, SoundEvent, SoundPack, SoundRequest, DROP_SOUND,
+        NACK_SOUND,
+    };
+    use crate::RustBustersDrone;
+    use crossbeam_channel::unbounded;
+    use std::sync::Arc;
+    use wg_2024::network::NodeId;
+    use wg_2024::packet::{Nack, NackType};
+
+    fn setup(drone_id: NodeId) -> (RustBustersDrone, &'static RecordingBackend) {
+        let (mut drone, _, _) = setup_drone();
+        drone.id = drone_id;
+        drone.enable_sound();
+        let (neighbor_5_sender, _) = unbounded();
+        drone.packet_send.insert(5, Box::new(neighbor_5_sender));
+        (drone, sound_recorder())
+    }
+
+    fn send_nack(drone: &mut RustBustersDrone, nack_type: NackType) {
+        let mut packet = fragment(0, 123, vec![5, drone.id, 2]);
+        packet.routing_header.hop_index = 2;
+        let nack = Nack {
+            fragment_index: 0,
+            nack_type,
+        };
+        drone.send_nack(&packet, nack, false);
+    }
+
+    #[test]
+    fn test_dropped_nack_plays_drop_sound() {
+        let (mut drone, recorder) = setup(101);
+
+        send_nack(&mut drone, NackType::Dropped);
+        let requests = recorder.requests_of(101);
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].event, SoundEvent::Drop);
+        assert_eq!(requests[0].sound.sample(), Some(DROP_SOUND));
+    }
+
+    #[test]
+    fn test_other_nacks_play_nack_sound() {
+        let (mut drone, recorder) = setup(102);
+
+        send_nack(&mut drone, NackType::ErrorInRouting(2));
+        send_nack(&mut drone, NackType::DestinationIsDrone);
+        send_nack(&mut drone, NackType::UnexpectedRecipient(3));
+        let requests = recorder.requests_of(102);
+        assert_eq!(requests.len(), 3);
+        for request in requests {
+            assert_eq!(request.event, SoundEvent::Nack);
+            assert_eq!(request.sound.sample(), Some(NACK_SOUND));
+        }
+    }
+
+    #[test]
+    fn test_drone_settings_are_applied() {
+        let (mut drone, recorder) = setup(103);
+        drone.set_sound_volume(0.5);
+        drone.set_sound_event(SoundEvent::Drop, false);
+
+        send_nack(&mut drone, NackType::Dropped);
+        send_nack(&mut drone, NackType::DestinationIsDrone);
+        let requests = recorder.requests_of(103);
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].event, SoundEvent::Nack);
+        assert!((requests[0].volume - 0.5).abs() < f32::EPSILON);
+
+        drone.disable_sound();
+        send_nack(&mut drone, NackType::DestinationIsDrone);
+        assert_eq!(recorder.requests_of(103).len(), 1);
+    }
+
+    #[test]
+    fn test_event_enabled_again_and_drone_pack() {
+        let (mut drone, recorder) = setup(104);
+        drone.set_sound_event(SoundEvent::Drop, false);
+        drone.set_sound_event(SoundEvent::Drop, true);
+        let mut pack = SoundPack::builtin();
+        pack.set_sound(SoundEvent::Drop, NACK_SOUND);
+        pack.remove_sound(SoundEvent::Nack);
+        drone.set_sound_pack(pack);
+
+        send_nack(&mut drone, NackType::Dropped);
+        // an event without a sound in the pack stays silent
+        send_nack(&mut drone, NackType::DestinationIsDrone);
+        let requests = recorder.requests_of(104);
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].event, SoundEvent::Drop);
+        assert_eq!(requests[0].sound.sample(), Some(NACK_SOUND));
+    }
+
+    #[test]
+    fn test_master_volume_and_mute() {
+        let recorder = RecordingBackend::default();
+        let mut audio_system = AudioSystem::new(recorder.clone());
+        let request = SoundRequest {
+            drone_id: 1,
+            event: SoundEvent::Spawn,
+            sound: Sound::Sample(Arc::from(DROP_SOUND)),
+            volume: 0.5,
+        };
+
+        audio_system.set_master_volume(0.5);
+        audio_system.play_sound(request.clone());
+        audio_system.set_muted(true);
+        audio_system.play_sound(request);
+        let requests = recorder.requests();
+        assert_eq!(requests.len(), 1);
+        assert!((requests[0].volume - 0.25).abs() < f32::EPSILON);
+    }
+}
+
+#[cfg(all(test, feature = "sounds"))]
+mod throttling {
+    use crate::sounds::sounds_feat::{
+        AudioBackend, AudioStats, AudioSystem, RecordingBackend, Sound, SoundEvent, SoundRequest,
+        ThrottleSettings, NACK_SOUND,
+    };
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    /// Records the requests, with a settable number of sounds playing
+    #[derive(Clone, Default)]
+    struct BusyBackend {
+        recorder: RecordingBackend,
+        active: Arc<AtomicUsize>,
+    }
+
+    impl AudioBackend for BusyBackend {
+        fn play(&mut self, request: SoundRequest) {
+            self.recorder.play(request);
+        }
+
+        fn active_sounds(&self) -> usize {
+            self.active.load(Ordering::Relaxed)
+        }
+    }
+
+    fn request(event: SoundEvent) -> SoundRequest {
+        SoundRequest {
+            drone_id: 1,
+            event,
+            sound: Sound::Sample(Arc::from(NACK_SOUND)),
+            volume: 1.0,
+        }
+    }
+
+    fn setup() -> (AudioSystem, BusyBackend) {
+        let backend = BusyBackend::default();
+        let mut audio_system = AudioSystem::new(backend.clone());
+        audio_system.set_throttle(Some(ThrottleSettings {
+            max_concurrent: 2,
+            cooldown: Duration::from_millis(100),
+        }));
+        (audio_system, backend)
+    }
+
+    #[test]
+    fn test_burst_is_coalesced() {
+        let (mut audio_system, backend) = setup();
+        let start = Instant::now();
+
+        for i in 0..10 {
+            audio_system.play_sound_at(request(SoundEvent::Nack), start + Duration::from_millis(i));
+        }
+        // another event is not coalesced
+        audio_system.play_sound_at(request(SoundEvent::Drop), start);
+        // the burst is played once when the cooldown ends
+        audio_system.flush_at(start + Duration::from_millis(50));
+        audio_system.flush_at(start + Duration::from_millis(150));
+        audio_system.flush_at(start + Duration::from_millis(300));
+
+        let events: Vec<SoundEvent> = backend
+            .recorder
+            .requests()
+            .iter()
+            .map(|request| request.event)
+            .collect();
+        assert_eq!(
+            events,
+            vec![SoundEvent::Nack, SoundEvent::Drop, SoundEvent::Nack]
+        );
+        assert_eq!(
+            audio_system.stats(),
+            AudioStats {
+                played: 3,
+                coalesced: 9,
+                dropped: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_coalesced_play_starts_a_new_cooldown() {
+        let (mut audio_system, backend) = setup();
+        let start = Instant::now();
+
+        audio_system.play_sound_at(request(SoundEvent::Nack), start);
+        audio_system.play_sound_at(request(SoundEvent::Nack), start);
+        // the coalesced request is flushed before the new one, which falls in its cooldown
+        audio_system.play_sound_at(
+            request(SoundEvent::Nack),
+            start + Duration::from_millis(120),
+        );
+        assert_eq!(backend.recorder.requests().len(), 2);
+
+        audio_system.flush_at(start + Duration::from_millis(200));
+        assert_eq!(backend.recorder.requests().len(), 2);
+        audio_system.flush_at(start + Duration::from_millis(220));
+        assert_eq!(backend.recorder.requests().len(), 3);
+        assert_eq!(
+            audio_system.stats(),
+            AudioStats {
+                played: 3,
+                coalesced: 2,
+                dropped: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_concurrent_sounds_are_limited() {
+        let (mut audio_system, backend) = setup();
+        let now = Instant::now();
+
+        backend.active.store(2, Ordering::Relaxed);
+        audio_system.play_sound_at(request(SoundEvent::Nack), now);
+        audio_system.play_sound_at(request(SoundEvent::Spawn), now);
+        assert!(backend.recorder.requests().is_empty());
+        assert_eq!(audio_system.stats().dropped, 2);
+
+        // dropped requests do not start the cooldown
+        backend.active.store(1, Ordering::Relaxed);
+        audio_system.play_sound_at(request(SoundEvent::Nack), now);
+        assert_eq!(backend.recorder.requests().len(), 1);
+    }
+
+    #[test]
+    fn test_crash_and_hunt_are_never_dropped() {
+        let (mut audio_system, backend) = setup();
+        let now = Instant::now();
+
+        backend.active.store(10, Ordering::Relaxed);
+        for _ in 0..3 {
+            audio_system.play_sound_at(request(SoundEvent::Crash), now);
+            audio_system.play_sound_at(request(SoundEvent::Hunt), now);
+        }
+        assert_eq!(backend.recorder.requests().len(), 6);
+        assert_eq!(audio_system.stats().dropped, 0);
+        assert_eq!(audio_system.stats().coalesced, 0);
+    }
+}
@@ -0,0 +1,147 @@
+#[cfg(test)]
+mod stream {
+    use crate::drone::test::common::{flood_request, setup_drone, RB_DRONE_ID};
+    use crate::neighbors::LinkState;
+    use crate::rate_limit::{LimitScope, RateLimit};
+    use crate::telemetry::{Lifecycle, RustBustersTelemetry, TelemetryEvent};
+    use crossbeam_channel::{bounded, unbounded, Receiver};
+    use std::time::Duration;
+    use wg_2024::controller::DroneCommand;
+    use wg_2024::packet::NodeType;
+
+    fn events(telemetry_recv: &Receiver<RustBustersTelemetry>) -> Vec<TelemetryEvent> {
+        telemetry_recv
+            .try_iter()
+            .map(|telemetry| {
+                assert_eq!(telemetry.drone_id, RB_DRONE_ID);
+                telemetry.event
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_neighbor_and_lifecycle_events() {
+        let (mut drone, _, _) = setup_drone();
+        let (telemetry_send, telemetry_recv) = unbounded();
+        drone.set_telemetry(Some(telemetry_send));
+        let (neighbor_1_sender, _neighbor_1_receiver) = unbounded();
+        drone.handle_command(DroneCommand::AddSender(1, neighbor_1_sender));
+        drone.handle_command(DroneCommand::RemoveSender(1));
+        drone.check_neighbors();
+        drone.begin_crash();
+
+        assert_eq!(
+            events(&telemetry_recv),
+            vec![
+                TelemetryEvent::NeighborState {
+                    neighbor: 1,
+                    state: LinkState::Up
+                },
+                TelemetryEvent::NeighborState {
+                    neighbor: 1,
+                    state: LinkState::Down
+                },
+                TelemetryEvent::Lifecycle(Lifecycle::Crashing),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_route_optimization_and_rate_limit_events() {
+        let (mut drone, _, _) = setup_drone();
+        let (telemetry_send, telemetry_recv) = unbounded();
+        drone.set_telemetry(Some(telemetry_send));
+        let (neighbor_1_sender, _neighbor_1_receiver) = unbounded();
+        let (neighbor_2_sender, _neighbor_2_receiver) = unbounded();
+        drone.packet_send.insert(1, Box::new(neighbor_1_sender));
+        drone.packet_send.insert(2, Box::new(neighbor_2_sender));
+
+        // a route that cannot be shortened is not reported
+        drone.optimize_route(&[RB_DRONE_ID, 2, 4]);
+        drone.optimize_route(&[RB_DRONE_ID, 3, 2, 4]);
+        drone.set_initiator_rate_limit(Some(RateLimit::new(0.0, 1)));
+        drone.handle_flood_request(flood_request(1, 1, vec![(1, NodeType::Client)]));
+        drone.handle_flood_request(flood_request(2, 1, vec![(1, NodeType::Client)]));
+
+        assert_eq!(
+            events(&telemetry_recv),
+            vec![
+                TelemetryEvent::RouteOptimized {
+                    before: vec![RB_DRONE_ID, 3, 2, 4],
+                    after: vec![RB_DRONE_ID, 2, 4],
+                },
+                TelemetryEvent::RateLimited {
+                    scope: LimitScope::Initiator(1),
+                    session_id: 0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_flood_cache_evictions() {
+        let (mut drone, _, _) = setup_drone();
+        let (telemetry_send, telemetry_recv) = unbounded();
+        drone.set_telemetry(Some(telemetry_send));
+        for flood_id in [3, 1, 2] {
+            drone.handle_flood_request(flood_request(flood_id, 1, vec![(1, NodeType::Client)]));
+        }
+        assert!(events(&telemetry_recv).is_empty());
+
+        // the floods remembered before are forgotten by ID, the new ones by arrival
+        drone.set_flood_cache_capacity(Some(2));
+        drone.handle_flood_request(flood_request(0, 1, vec![(1, NodeType::Client)]));
+        assert_eq!(
+            events(&telemetry_recv),
+            vec![
+                TelemetryEvent::FloodCacheEvicted {
+                    flood_id: 1,
+                    initiator_id: 1
+                },
+                TelemetryEvent::FloodCacheEvicted {
+                    flood_id: 2,
+                    initiator_id: 1
+                },
+            ]
+        );
+        assert_eq!(drone.received_floods.len(), 2);
+        assert!(drone.received_floods.contains(&(0, 1)));
+        assert!(!drone.received_floods.contains(&(1, 1)));
+    }
+
+    #[test]
+    fn test_periodic_stats() {
+        let (mut drone, _, _) = setup_drone();
+        let (telemetry_send, telemetry_recv) = unbounded();
+        drone.set_telemetry(Some(telemetry_send));
+        drone.publish_telemetry_stats();
+        assert!(events(&telemetry_recv).is_empty());
+
+        drone.set_telemetry_stats(Some(Duration::ZERO));
+        drone.stats.nacks_sent = 4;
+        drone.publish_telemetry_stats();
+        let events = events(&telemetry_recv);
+        assert_eq!(events.len(), 1);
+        let TelemetryEvent::Stats(stats) = &events[0] else {
+            panic!("Expected stats, got {:?}", events[0]);
+        };
+        assert_eq!(**stats, drone.stats());
+    }
+
+    #[test]
+    fn test_telemetry_never_blocks_nor_reaches_the_controller() {
+        let (mut drone, _, controller_recv) = setup_drone();
+        let (telemetry_send, telemetry_recv) = bounded(1);
+        drone.set_telemetry(Some(telemetry_send));
+        let (neighbor_2_sender, _neighbor_2_receiver) = unbounded();
+        drone.packet_send.insert(2, Box::new(neighbor_2_sender));
+
+        drone.optimize_route(&[RB_DRONE_ID, 3, 2, 4]);
+        drone.optimize_route(&[RB_DRONE_ID, 5, 2, 4]);
+        assert_eq!(telemetry_recv.len(), 1);
+        assert!(controller_recv.try_iter().next().is_none());
+
+        drop(telemetry_recv);
+        drone.optimize_route(&[RB_DRONE_ID, 3, 2, 4]);
+    }
+}
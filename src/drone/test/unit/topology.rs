@@ -0,0 +1,177 @@
+#[cfg(test)]
+mod dot {
+    use crate::drone::test::common::{flood_request, setup_drone, RB_DRONE_ID};
+    use crate::neighbors::LinkState;
+    use crate::snapshot::{DroneChannels, DroneState};
+    use crate::topology::{LinkStatus, NetworkView};
+    use crate::RustBustersDrone;
+    use crossbeam_channel::unbounded;
+    use std::collections::HashMap;
+    use wg_2024::network::SourceRoutingHeader;
+    use wg_2024::packet::NodeType::{Client, Drone, Server};
+    use wg_2024::packet::{FloodResponse, Nack, NackType, Packet, PacketType};
+
+    #[test]
+    fn test_topology_learned_from_path_traces() {
+        let (mut drone, _, _) = setup_drone();
+        let (neighbor_2_sender, _neighbor_2_receiver) = unbounded();
+        let (neighbor_3_sender, _neighbor_3_receiver) = unbounded();
+        drone.packet_send.insert(2, Box::new(neighbor_2_sender));
+        drone.packet_send.insert(3, Box::new(neighbor_3_sender));
+
+        drone.handle_flood_request(flood_request(1, 1, vec![(1, Client), (2, Drone)]));
+        // the response of another flood, passing back through the drone
+        drone.forward_packet(
+            Packet {
+                pack_type: PacketType::FloodResponse(FloodResponse {
+                    flood_id: 5,
+                    path_trace: vec![
+                        (1, Client),
+                        (2, Drone),
+                        (RB_DRONE_ID, Drone),
+                        (3, Drone),
+                        (4, Server),
+                    ],
+                }),
+                routing_header: SourceRoutingHeader {
+                    hop_index: 1,
+                    hops: vec![3, RB_DRONE_ID, 2, 1],
+                },
+                session_id: 0,
+            },
+            true,
+        );
+
+        let view = drone.network_view();
+        assert_eq!(view.nodes[&4], Some(Server));
+        assert_eq!(view.links[&(1, 2)], LinkStatus::Fresh);
+        assert_eq!(view.links[&(3, 4)], LinkStatus::Fresh);
+        // the links to the neighbors are drawn with their state
+        assert_eq!(
+            view.links[&(2, RB_DRONE_ID)],
+            LinkStatus::Neighbor(LinkState::Up)
+        );
+        assert_eq!(view.links.len(), 4);
+
+        let dot = drone.export_dot();
+        assert!(dot.starts_with("graph network {\n"));
+        assert!(dot.contains("    1 [shape=box];\n"));
+        assert!(dot.contains("    4 [shape=octagon];\n"));
+        assert!(dot.contains(&format!(
+            "    {RB_DRONE_ID} [shape=ellipse, style=\"filled,bold\", fillcolor=gold];\n"
+        )));
+        assert!(dot.contains("    1 -- 2 [color=black];\n"));
+        assert!(dot.contains(&format!(
+            "    3 -- {RB_DRONE_ID} [color=darkgreen, penwidth=2];\n"
+        )));
+        assert!(dot.ends_with("}\n"));
+    }
+
+    #[test]
+    fn test_merge_views() {
+        let mut first = NetworkView::default();
+        first.observers.insert(10);
+        first.add_node(10, Some(Drone));
+        first.add_link(10, 2, LinkStatus::Neighbor(LinkState::Up));
+        first.add_link(2, 3, LinkStatus::Stale);
+
+        let mut second = NetworkView::default();
+        second.observers.insert(3);
+        second.add_node(3, Some(Drone));
+        second.add_link(3, 2, LinkStatus::Neighbor(LinkState::Down));
+        second.add_link(3, 4, LinkStatus::Fresh);
+
+        let merged = NetworkView::merge([first, second]);
+        assert_eq!(merged.observers.len(), 2);
+        assert_eq!(merged.nodes[&3], Some(Drone));
+        assert_eq!(merged.nodes[&2], None);
+        // the state seen by a neighbor beats the path trace
+        assert_eq!(merged.links[&(2, 3)], LinkStatus::Neighbor(LinkState::Down));
+        assert_eq!(merged.links[&(2, 10)], LinkStatus::Neighbor(LinkState::Up));
+        assert_eq!(merged.links.len(), 3);
+
+        let dot = merged.to_dot();
+        assert!(dot.contains("    2 [shape=circle, style=dashed];\n"));
+        assert!(dot.contains("    2 -- 3 [color=red, penwidth=2, style=dashed];\n"));
+        assert_eq!(dot.matches("fillcolor=gold").count(), 2);
+    }
+
+    #[test]
+    fn test_lossy_links_are_drawn_with_drop_rate() {
+        let (mut drone, _, _) = setup_drone();
+        let (neighbor_2_sender, _neighbor_2_receiver) = unbounded();
+        let (neighbor_3_sender, _neighbor_3_receiver) = unbounded();
+        drone.packet_send.insert(2, Box::new(neighbor_2_sender));
+        drone.packet_send.insert(3, Box::new(neighbor_3_sender));
+        for _ in 0..4 {
+            drone
+                .send_to_neighbor(2, flood_request(1, 1, vec![(1, Client), (2, Drone)]))
+                .unwrap();
+            drone
+                .send_to_neighbor(3, flood_request(1, 1, vec![(1, Client), (2, Drone)]))
+                .unwrap();
+        }
+        let dropped_nack = |hops| Packet {
+            pack_type: PacketType::Nack(Nack {
+                fragment_index: 0,
+                nack_type: NackType::Dropped,
+            }),
+            routing_header: SourceRoutingHeader { hop_index: 1, hops },
+            session_id: 0,
+        };
+        // neighbor 2 drops one of the fragments sent through it
+        drone.forward_packet(dropped_nack(vec![2, RB_DRONE_ID, 1]), true);
+        // a drop further away is not the fault of the link
+        let mut relayed = dropped_nack(vec![4, 3, RB_DRONE_ID, 1]);
+        relayed.routing_header.hop_index = 2;
+        drone.forward_packet(relayed, true);
+
+        assert_eq!(drone.neighbor(2).unwrap().dropped, 1);
+        let view = drone.network_view();
+        assert_eq!(view.drop_rates[&(2, RB_DRONE_ID)], 0.25);
+        assert_eq!(view.drop_rates[&(3, RB_DRONE_ID)], 0.0);
+
+        let dot = view.to_dot();
+        assert!(dot.contains(&format!(
+            "    2 -- {RB_DRONE_ID} [color=purple, penwidth=2, label=\"25% dropped\"];\n"
+        )));
+        assert!(dot.contains(&format!(
+            "    3 -- {RB_DRONE_ID} [color=darkgreen, penwidth=2];\n"
+        )));
+
+        // the highest drop rate wins when the views are merged
+        let mut other = NetworkView::default();
+        other.add_link(2, RB_DRONE_ID, LinkStatus::Neighbor(LinkState::Up));
+        other.add_drop_rate(2, RB_DRONE_ID, 0.5);
+        let merged = NetworkView::merge([view, other]);
+        assert_eq!(merged.drop_rates[&(2, RB_DRONE_ID)], 0.5);
+    }
+
+    #[test]
+    fn test_snapshot_keeps_topology() {
+        let (mut drone, _, _) = setup_drone();
+        let (neighbor_2_sender, _neighbor_2_receiver) = unbounded();
+        drone.packet_send.insert(2, Box::new(neighbor_2_sender));
+        drone.handle_flood_request(flood_request(1, 1, vec![(1, Client), (2, Drone)]));
+
+        let state = drone.snapshot();
+        assert_eq!(
+            state.known_nodes,
+            vec![(1, Client), (2, Drone), (RB_DRONE_ID, Drone)]
+        );
+        assert_eq!(state.known_links.len(), 2);
+
+        let bytes = bincode::serialize(&state).unwrap();
+        let state: DroneState = bincode::deserialize(&bytes).unwrap();
+        let (controller_send, _controller_recv) = unbounded();
+        let (_command_send, command_recv) = unbounded();
+        let (_packet_send, packet_recv) = unbounded();
+        let restored = RustBustersDrone::restore(
+            state,
+            DroneChannels::new(controller_send, command_recv, packet_recv, HashMap::new()),
+        );
+        let view = restored.network_view();
+        assert_eq!(view.nodes[&1], Some(Client));
+        assert_eq!(view.links[&(1, 2)], LinkStatus::Fresh);
+    }
+}
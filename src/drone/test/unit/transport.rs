@@ -0,0 +1,136 @@
+#[cfg(test)]
+mod links {
+    use crate::drone::test::common::{setup_drone, RB_DRONE_ID};
+    use crate::transport::{PacketLink, PacketSource};
+    use crate::RustBustersDrone;
+    use crossbeam_channel::unbounded;
+    use std::collections::HashMap;
+    use wg_2024::network::SourceRoutingHeader;
+    use wg_2024::packet::{Ack, Packet, PacketType};
+
+    fn ack_packet(hops: Vec<u8>) -> Packet {
+        Packet {
+            pack_type: PacketType::Ack(Ack { fragment_index: 7 }),
+            routing_header: SourceRoutingHeader { hop_index: 1, hops },
+            session_id: 42,
+        }
+    }
+
+    #[test]
+    fn test_crossbeam_link_and_source() {
+        let (sender, receiver) = unbounded();
+        let receiver = receiver.into_receiver();
+
+        sender.send_packet(ack_packet(vec![1, 2])).unwrap();
+
+        let packet = receiver
+            .recv_timeout(std::time::Duration::from_secs(1))
+            .expect("Timeout: no packet received");
+        assert_eq!(packet.session_id, 42);
+
+        drop(receiver);
+        assert!(sender.send_packet(ack_packet(vec![1, 2])).is_err());
+    }
+
+    #[test]
+    fn test_drone_with_transport_forwards_over_custom_link() {
+        let (controller_send, _controller_recv) = unbounded();
+        let (_cmd_send, cmd_recv) = unbounded();
+        let (_packet_send, packet_recv) = unbounded::<Packet>();
+        let (neighbor_sender, neighbor_recv) = unbounded();
+
+        let mut links: HashMap<u8, Box<dyn PacketLink>> = HashMap::new();
+        links.insert(2, Box::new(neighbor_sender));
+
+        let mut drone = RustBustersDrone::with_transport(
+            RB_DRONE_ID,
+            controller_send,
+            cmd_recv,
+            packet_recv,
+            links,
+            0.0,
+        );
+
+        drone.forward_packet(ack_packet(vec![1, RB_DRONE_ID, 2]), false);
+
+        let packet = neighbor_recv
+            .recv_timeout(std::time::Duration::from_secs(1))
+            .expect("Timeout: no packet received");
+        assert_eq!(packet.routing_header.hop_index, 2);
+    }
+
+    #[test]
+    fn test_closed_link_is_removed() {
+        let (mut drone, _, _) = setup_drone();
+        let (neighbor_sender, neighbor_recv) = unbounded();
+        drop(neighbor_recv);
+        drone.packet_send.insert(2, Box::new(neighbor_sender));
+
+        drone.forward_packet(ack_packet(vec![1, RB_DRONE_ID, 2]), false);
+
+        assert!(!drone.packet_send.contains_key(&2));
+    }
+
+    #[cfg(all(unix, feature = "socket"))]
+    #[test]
+    fn test_unix_socket_round_trip() {
+        use crate::transport::unix_socket::{UnixPacketLink, UnixPacketSource};
+
+        let path = std::env::temp_dir().join(format!("rb-drone-test-{}.sock", std::process::id()));
+        let receiver = UnixPacketSource::bind(&path).unwrap().into_receiver();
+        let link = UnixPacketLink::connect(&path).unwrap();
+
+        link.send_packet(ack_packet(vec![1, RB_DRONE_ID, 2]))
+            .unwrap();
+
+        let packet = receiver
+            .recv_timeout(std::time::Duration::from_secs(1))
+            .expect("Timeout: no packet received");
+        assert_eq!(packet.session_id, 42);
+        assert_eq!(packet.routing_header.hops, vec![1, RB_DRONE_ID, 2]);
+        match packet.pack_type {
+            PacketType::Ack(ack) => assert_eq!(ack.fragment_index, 7),
+            _ => panic!("Unexpected packet: {:?}", packet.pack_type),
+        }
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(all(unix, feature = "socket"))]
+    #[test]
+    fn test_unix_socket_connect_without_listener() {
+        use crate::transport::unix_socket::UnixPacketLink;
+
+        let path = std::env::temp_dir().join("rb-drone-test-missing.sock");
+        assert!(UnixPacketLink::connect(path).is_err());
+    }
+
+    #[cfg(all(unix, feature = "socket"))]
+    #[test]
+    fn test_unix_socket_remote_command_and_event() {
+        use crate::transport::unix_socket::{RemoteCommand, RemoteEvent, UnixLink, UnixSource};
+        use wg_2024::controller::DroneEvent;
+
+        let path =
+            std::env::temp_dir().join(format!("rb-drone-test-ctl-{}.sock", std::process::id()));
+        let commands = UnixSource::<RemoteCommand>::bind(&path)
+            .unwrap()
+            .into_channel();
+        let link = UnixLink::<RemoteCommand>::connect(&path).unwrap();
+
+        link.send(&RemoteCommand::SetPacketDropRate(0.5)).unwrap();
+
+        match commands.recv_timeout(std::time::Duration::from_secs(1)) {
+            Ok(RemoteCommand::SetPacketDropRate(pdr)) => assert!((pdr - 0.5).abs() < f32::EPSILON),
+            other => panic!("Unexpected command: {other:?}"),
+        }
+
+        let event = RemoteEvent::from(DroneEvent::PacketDropped(ack_packet(vec![1, 2])));
+        match DroneEvent::from(event) {
+            DroneEvent::PacketDropped(packet) => assert_eq!(packet.session_id, 42),
+            other => panic!("Unexpected event: {other:?}"),
+        }
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
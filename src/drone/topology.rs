@@ -0,0 +1,262 @@
+use crate::drone::neighbors::LinkState;
+use crate::RustBustersDrone;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+use wg_2024::network::NodeId;
+use wg_2024::packet::NodeType;
+
+/// Age under which a link learned from a path trace is drawn as fresh
+const FRESH_LINK_AGE: Duration = Duration::from_secs(30);
+
+/// Drop rate from which a link to a neighbor is drawn as lossy
+pub const LOSSY_LINK_DROP_RATE: f64 = 0.2;
+
+/// Nodes and links learned from the path traces of the floods passing through the drone
+#[derive(Debug, Clone, Default)]
+pub struct TopologyMap {
+    nodes: HashMap<NodeId, NodeType>,
+    /// Links as (smaller ID, larger ID), with when they were last seen
+    links: HashMap<(NodeId, NodeId), Instant>,
+}
+
+impl TopologyMap {
+    /// Learns the nodes of a path trace and the links between them
+    pub fn learn_path(&mut self, path_trace: &[(NodeId, NodeType)], now: Instant) {
+        for &(id, node_type) in path_trace {
+            self.nodes.insert(id, node_type);
+        }
+        for pair in path_trace.windows(2) {
+            self.insert_link(pair[0].0, pair[1].0, now);
+        }
+    }
+
+    /// Records a link as seen at `seen`
+    pub fn insert_link(&mut self, a: NodeId, b: NodeId, seen: Instant) {
+        if a == b {
+            return;
+        }
+        let last_seen = self.links.entry(link_key(a, b)).or_insert(seen);
+        *last_seen = (*last_seen).max(seen);
+    }
+
+    /// Records the type of a node
+    pub fn insert_node(&mut self, id: NodeId, node_type: NodeType) {
+        self.nodes.insert(id, node_type);
+    }
+
+    /// Returns the type of a node, if it appeared in a path trace
+    pub fn node_type(&self, id: NodeId) -> Option<NodeType> {
+        self.nodes.get(&id).copied()
+    }
+
+    /// Returns the known nodes, sorted by ID
+    pub fn nodes(&self) -> Vec<(NodeId, NodeType)> {
+        let mut nodes: Vec<(NodeId, NodeType)> = self
+            .nodes
+            .iter()
+            .map(|(&id, &node_type)| (id, node_type))
+            .collect();
+        nodes.sort_unstable_by_key(|&(id, _)| id);
+        nodes
+    }
+
+    /// Returns the known links with their age at `now`, sorted
+    pub fn links_at(&self, now: Instant) -> Vec<(NodeId, NodeId, Duration)> {
+        let mut links: Vec<(NodeId, NodeId, Duration)> = self
+            .links
+            .iter()
+            .map(|(&(a, b), &seen)| (a, b, now.saturating_duration_since(seen)))
+            .collect();
+        links.sort_unstable_by_key(|&(a, b, _)| (a, b));
+        links
+    }
+
+    /// Returns the number of known links
+    pub fn len(&self) -> usize {
+        self.links.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.links.is_empty()
+    }
+}
+
+fn link_key(a: NodeId, b: NodeId) -> (NodeId, NodeId) {
+    (a.min(b), a.max(b))
+}
+
+/// State of a link in a `NetworkView`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkStatus {
+    /// Link of an observer to a neighbor, with its state
+    Neighbor(LinkState),
+    /// Link learned from a path trace in the last 30 seconds
+    Fresh,
+    /// Link learned from an older path trace
+    Stale,
+}
+
+impl LinkStatus {
+    /// Returns the precedence of the status when views are merged:
+    /// the state seen by a neighbor beats a path trace, and the worst state wins
+    fn precedence(self) -> u8 {
+        match self {
+            LinkStatus::Stale => 0,
+            LinkStatus::Fresh => 1,
+            LinkStatus::Neighbor(LinkState::Up) => 2,
+            LinkStatus::Neighbor(LinkState::Suspect) => 3,
+            LinkStatus::Neighbor(LinkState::Down) => 4,
+        }
+    }
+
+    fn dot_attributes(self) -> &'static str {
+        match self {
+            LinkStatus::Neighbor(LinkState::Up) => "color=darkgreen, penwidth=2",
+            LinkStatus::Neighbor(LinkState::Suspect) => "color=orange, penwidth=2",
+            LinkStatus::Neighbor(LinkState::Down) => "color=red, penwidth=2, style=dashed",
+            LinkStatus::Fresh => "color=black",
+            LinkStatus::Stale => "color=gray, style=dotted",
+        }
+    }
+}
+
+/// The network as seen by one or more drones, exportable as a DOT graph
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NetworkView {
+    /// Drones the view comes from, highlighted in the graph
+    pub observers: BTreeSet<NodeId>,
+    /// Known nodes, with their type if it appeared in a path trace
+    pub nodes: BTreeMap<NodeId, Option<NodeType>>,
+    /// Known links as (smaller ID, larger ID)
+    pub links: BTreeMap<(NodeId, NodeId), LinkStatus>,
+    /// Drop rates, from 0 to 1, of the links to the neighbors of the observers
+    pub drop_rates: BTreeMap<(NodeId, NodeId), f64>,
+}
+
+impl NetworkView {
+    /// Merges the views of many drones into one network picture
+    ///
+    /// > Note: a link keeps the status with the highest precedence:
+    /// > the state seen by a neighbor beats a path trace, and the worst state wins.
+    /// > A link seen by both its ends keeps the highest drop rate
+    pub fn merge(views: impl IntoIterator<Item = NetworkView>) -> Self {
+        let mut merged = Self::default();
+        for view in views {
+            merged.observers.extend(view.observers);
+            for (id, node_type) in view.nodes {
+                merged.add_node(id, node_type);
+            }
+            for ((a, b), status) in view.links {
+                merged.add_link(a, b, status);
+            }
+            for ((a, b), drop_rate) in view.drop_rates {
+                merged.add_drop_rate(a, b, drop_rate);
+            }
+        }
+        merged
+    }
+
+    /// Adds a node, keeping its type if already known
+    pub fn add_node(&mut self, id: NodeId, node_type: Option<NodeType>) {
+        let known = self.nodes.entry(id).or_insert(node_type);
+        if known.is_none() {
+            *known = node_type;
+        }
+    }
+
+    /// Adds a link and its nodes, keeping the status with the highest precedence
+    pub fn add_link(&mut self, a: NodeId, b: NodeId, status: LinkStatus) {
+        self.add_node(a, None);
+        self.add_node(b, None);
+        let current = self.links.entry(link_key(a, b)).or_insert(status);
+        if status.precedence() > current.precedence() {
+            *current = status;
+        }
+    }
+
+    /// Records the drop rate of a link, keeping the highest one
+    pub fn add_drop_rate(&mut self, a: NodeId, b: NodeId, drop_rate: f64) {
+        let current = self.drop_rates.entry(link_key(a, b)).or_insert(drop_rate);
+        *current = current.max(drop_rate);
+    }
+
+    /// Renders the view as an undirected Graphviz DOT graph
+    ///
+    /// > Note: the shape of a node tells its type (ellipse for drones, box for clients,
+    /// > octagon for servers, dashed circle if unknown), the observers are filled in gold
+    /// > and the links are coloured by state (green, orange, red) or freshness (black, dotted gray).
+    /// > The links with a drop rate of at least `LOSSY_LINK_DROP_RATE` are drawn in purple
+    /// > and labelled with it, unless they are down
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("graph network {\n");
+        for (&id, &node_type) in &self.nodes {
+            let shape = match node_type {
+                Some(NodeType::Drone) => "shape=ellipse",
+                Some(NodeType::Client) => "shape=box",
+                Some(NodeType::Server) => "shape=octagon",
+                None => "shape=circle, style=dashed",
+            };
+            let highlight = if self.observers.contains(&id) {
+                ", style=\"filled,bold\", fillcolor=gold"
+            } else {
+                ""
+            };
+            let _ = writeln!(dot, "    {id} [{shape}{highlight}];");
+        }
+        for (&(a, b), &status) in &self.links {
+            let drop_rate = self.drop_rates.get(&(a, b)).copied().unwrap_or_default();
+            if drop_rate >= LOSSY_LINK_DROP_RATE && status != LinkStatus::Neighbor(LinkState::Down)
+            {
+                let _ = writeln!(
+                    dot,
+                    "    {a} -- {b} [color=purple, penwidth=2, label=\"{:.0}% dropped\"];",
+                    drop_rate * 100.0
+                );
+            } else {
+                let _ = writeln!(dot, "    {a} -- {b} [{}];", status.dot_attributes());
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+impl RustBustersDrone {
+    /// Returns the view of the network of the drone: its neighbors, with the drop rates of their links,
+    /// and the topology learned from the path traces
+    pub fn network_view(&self) -> NetworkView {
+        let now = Instant::now();
+        let mut view = NetworkView::default();
+        view.observers.insert(self.id);
+        view.add_node(self.id, Some(NodeType::Drone));
+        for (id, node_type) in self.topology.nodes() {
+            view.add_node(id, Some(node_type));
+        }
+        for (a, b, age) in self.topology.links_at(now) {
+            let status = if age < FRESH_LINK_AGE {
+                LinkStatus::Fresh
+            } else {
+                LinkStatus::Stale
+            };
+            view.add_link(a, b, status);
+        }
+        for (id, info) in self.neighbors() {
+            view.add_link(self.id, id, LinkStatus::Neighbor(info.state));
+            view.add_drop_rate(self.id, id, info.drop_rate());
+        }
+        view
+    }
+
+    /// Exports the view of the network of the drone as a Graphviz DOT graph
+    ///
+    /// > Note: the views of many drones can be drawn together with `NetworkView::merge`
+    pub fn export_dot(&self) -> String {
+        self.network_view().to_dot()
+    }
+
+    /// Learns the topology from the path trace of a flood passing through the drone
+    pub(crate) fn learn_topology(&mut self, path_trace: &[(NodeId, NodeType)]) {
+        self.topology.learn_path(path_trace, Instant::now());
+    }
+}
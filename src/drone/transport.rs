@@ -0,0 +1,227 @@
+use crossbeam_channel::{Receiver, Sender};
+use wg_2024::packet::Packet;
+
+/// Outgoing link from a drone to one of its neighbors
+///
+/// > Note: `Sender<Packet>` is the default implementation, used by the
+/// > `wg_2024` `Drone` constructor and by the `AddSender` command
+pub trait PacketLink: Send {
+    /// Sends the packet to the neighbor on the other end of the link
+    ///
+    /// #### Errors
+    /// - If the neighbor is no longer reachable, an error message is returned
+    fn send_packet(&self, packet: Packet) -> Result<(), String>;
+}
+
+/// Incoming side of a drone, where the packets addressed to it arrive
+///
+/// > Note: `Receiver<Packet>` is the default implementation
+pub trait PacketSource {
+    /// Turns the source into the channel polled by the drone run loop
+    ///
+    /// #### Returns
+    /// The receiver the incoming packets are delivered to
+    fn into_receiver(self) -> Receiver<Packet>;
+}
+
+impl PacketLink for Sender<Packet> {
+    fn send_packet(&self, packet: Packet) -> Result<(), String> {
+        self.send(packet).map_err(|e| e.to_string())
+    }
+}
+
+impl PacketSource for Receiver<Packet> {
+    fn into_receiver(self) -> Receiver<Packet> {
+        self
+    }
+}
+
+#[cfg(all(unix, feature = "socket"))]
+pub mod unix_socket {
+    use super::{PacketLink, PacketSource};
+    use crossbeam_channel::{unbounded, Receiver};
+    use log::{debug, warn};
+    use serde::de::DeserializeOwned;
+    use serde::{Deserialize, Serialize};
+    use std::io;
+    use std::marker::PhantomData;
+    use std::os::unix::net::UnixDatagram;
+    use std::path::{Path, PathBuf};
+    use wg_2024::controller::DroneEvent;
+    use wg_2024::network::NodeId;
+    use wg_2024::packet::Packet;
+
+    /// Maximum size of a serialized datagram
+    pub const MAX_DATAGRAM_SIZE: usize = 64 * 1024;
+
+    /// Link to a peer listening on a Unix datagram socket
+    pub type UnixPacketLink = UnixLink<Packet>;
+
+    /// Unix datagram socket the packets addressed to a drone are received on
+    pub type UnixPacketSource = UnixSource<Packet>;
+
+    /// Command sent to a drone process over its control socket
+    ///
+    /// > Note: mirrors `DroneCommand`, with the neighbor channel replaced by its socket path
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub enum RemoteCommand {
+        Crash,
+        SetPacketDropRate(f32),
+        AddSender(NodeId, PathBuf),
+        RemoveSender(NodeId),
+    }
+
+    /// Event sent by a drone process to the controller socket
+    ///
+    /// > Note: mirrors `DroneEvent`
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub enum RemoteEvent {
+        PacketSent(Packet),
+        PacketDropped(Packet),
+        ControllerShortcut(Packet),
+    }
+
+    impl From<DroneEvent> for RemoteEvent {
+        fn from(event: DroneEvent) -> Self {
+            match event {
+                DroneEvent::PacketSent(packet) => RemoteEvent::PacketSent(packet),
+                DroneEvent::PacketDropped(packet) => RemoteEvent::PacketDropped(packet),
+                DroneEvent::ControllerShortcut(packet) => RemoteEvent::ControllerShortcut(packet),
+            }
+        }
+    }
+
+    impl From<RemoteEvent> for DroneEvent {
+        fn from(event: RemoteEvent) -> Self {
+            match event {
+                RemoteEvent::PacketSent(packet) => DroneEvent::PacketSent(packet),
+                RemoteEvent::PacketDropped(packet) => DroneEvent::PacketDropped(packet),
+                RemoteEvent::ControllerShortcut(packet) => DroneEvent::ControllerShortcut(packet),
+            }
+        }
+    }
+
+    /// Sending end of a Unix datagram socket carrying serialized `T` values
+    ///
+    /// > Note: once the peer process exits, sending fails with
+    /// > `ECONNREFUSED`, which the drone handles like a closed channel
+    pub struct UnixLink<T> {
+        socket: UnixDatagram,
+        _message: PhantomData<fn(T)>,
+    }
+
+    impl<T: Serialize> UnixLink<T> {
+        /// Connects to the socket of a peer
+        ///
+        /// #### Arguments
+        /// - `path`: The path of the socket the peer is bound to
+        ///
+        /// #### Errors
+        /// - If the socket cannot be created or the peer is not listening
+        pub fn connect<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+            let socket = UnixDatagram::unbound()?;
+            socket.connect(path)?;
+            Ok(Self {
+                socket,
+                _message: PhantomData,
+            })
+        }
+
+        /// Serializes the value and sends it to the peer
+        ///
+        /// #### Errors
+        /// - If the value cannot be serialized or the peer is no longer reachable
+        pub fn send(&self, value: &T) -> Result<(), String> {
+            let bytes = bincode::serialize(value).map_err(|e| e.to_string())?;
+            self.socket
+                .send(&bytes)
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        }
+    }
+
+    impl PacketLink for UnixLink<Packet> {
+        fn send_packet(&self, packet: Packet) -> Result<(), String> {
+            self.send(&packet)
+        }
+    }
+
+    /// Receiving end of a Unix datagram socket carrying serialized `T` values
+    pub struct UnixSource<T> {
+        socket: UnixDatagram,
+        path: PathBuf,
+        _message: PhantomData<fn() -> T>,
+    }
+
+    impl<T: DeserializeOwned + Send + 'static> UnixSource<T> {
+        /// Binds a new socket at the given path
+        ///
+        /// #### Arguments
+        /// - `path`: The path to bind the socket to
+        ///
+        /// #### Errors
+        /// - If the socket cannot be bound
+        ///
+        /// > Note: a stale socket file left at `path` by a killed process is removed
+        pub fn bind<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+            let path = path.as_ref().to_path_buf();
+            if path.exists() {
+                std::fs::remove_file(&path)?;
+            }
+            let socket = UnixDatagram::bind(&path)?;
+            Ok(Self {
+                socket,
+                path,
+                _message: PhantomData,
+            })
+        }
+
+        /// Spawns a thread that deserializes the incoming datagrams into the returned channel
+        ///
+        /// #### Returns
+        /// The receiver the deserialized values are delivered to
+        ///
+        /// > Note: the thread stays blocked on the socket after the receiver is dropped,
+        /// > and only stops once the next datagram cannot be delivered or the socket fails
+        pub fn into_channel(self) -> Receiver<T> {
+            let (value_send, value_recv) = unbounded();
+            std::thread::Builder::new()
+                .name(format!("socket-{}", self.path.display()))
+                .spawn(move || {
+                    let mut buf = vec![0; MAX_DATAGRAM_SIZE];
+                    loop {
+                        let len = match self.socket.recv(&mut buf) {
+                            Ok(len) => len,
+                            Err(e) => {
+                                warn!("Socket {} - Error in receiving: {}", self.path.display(), e);
+                                break;
+                            }
+                        };
+                        match bincode::deserialize::<T>(&buf[..len]) {
+                            Ok(value) => {
+                                if value_send.send(value).is_err() {
+                                    break;
+                                }
+                            }
+                            Err(e) => {
+                                warn!(
+                                    "Socket {} - Discarding malformed datagram: {}",
+                                    self.path.display(),
+                                    e
+                                );
+                            }
+                        }
+                    }
+                    debug!("Socket {} - Stopped receiving", self.path.display());
+                })
+                .expect("Failed to spawn socket thread");
+            value_recv
+        }
+    }
+
+    impl PacketSource for UnixSource<Packet> {
+        fn into_receiver(self) -> Receiver<Packet> {
+            self.into_channel()
+        }
+    }
+}
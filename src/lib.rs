@@ -1,9 +1,34 @@
 mod drone;
 
+pub use drone::arq;
+pub use drone::battery;
+pub use drone::chaos;
+pub use drone::crash;
+pub use drone::dedup;
+pub use drone::firewall;
+pub use drone::flood_cache;
+pub use drone::flood_scope;
+pub use drone::flood_trace;
 pub use drone::forward_packet;
 pub use drone::handle_command;
 pub use drone::handle_flood;
 pub use drone::hunt;
+pub use drone::latency;
+pub use drone::middleware;
+pub use drone::neighbors;
 pub use drone::optimize_route;
+pub use drone::rate_limit;
+pub use drone::report;
+pub use drone::reputation;
+pub use drone::scheduler;
 pub use drone::send_nack;
+pub use drone::snapshot;
+#[cfg(feature = "sounds")]
+pub use drone::sonification;
+#[cfg(feature = "sounds")]
+pub use drone::sounds;
+pub use drone::stats;
+pub use drone::telemetry;
+pub use drone::topology;
+pub use drone::transport;
 pub use drone::RustBustersDrone;